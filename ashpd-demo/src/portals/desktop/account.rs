@@ -67,14 +67,16 @@ impl AccountPage {
                     NotificationKind::Success,
                 );
                 imp.id_label.set_text(user_info.id());
-                imp.name_label.set_text(user_info.name());
+                imp.name_label.set_text(user_info.name().unwrap_or_default());
                 match user_info
-                    .image()
-                    .to_file_path()
-                    .map_err(|_| {
-                        glib::Error::new(glib::FileError::Failed, "Failed to retrieve file path")
+                    .image_bytes()
+                    .map_err(|err| glib::Error::new(glib::FileError::Failed, &err.to_string()))
+                    .and_then(|bytes| {
+                        bytes.ok_or_else(|| {
+                            glib::Error::new(glib::FileError::Noent, "No user avatar")
+                        })
                     })
-                    .and_then(gdk::Texture::from_filename)
+                    .and_then(|bytes| gdk::Texture::from_bytes(&glib::Bytes::from_owned(bytes)))
                 {
                     Ok(texture) => {
                         imp.avatar.set_custom_image(Some(&texture));
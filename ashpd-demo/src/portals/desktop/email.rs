@@ -1,7 +1,10 @@
 use std::fs::File;
 
 use adw::{prelude::*, subclass::prelude::*};
-use ashpd::{desktop::email::EmailRequest, WindowIdentifier};
+use ashpd::{
+    desktop::email::{EmailAddress, EmailRequest},
+    WindowIdentifier,
+};
 use gtk::{
     gio,
     glib::{self, clone},
@@ -117,18 +120,18 @@ impl EmailPage {
 
         let subject = is_empty(imp.subject.text());
         let body = is_empty(imp.body.text());
-        let addresses = is_empty(imp.addresses.text()).map(split_comma);
-        let bcc = is_empty(imp.bcc_entry.text()).map(split_comma);
-        let cc = is_empty(imp.cc_entry.text()).map(split_comma);
+        let addresses = is_empty(imp.addresses.text()).map(email_addresses);
+        let bcc = is_empty(imp.bcc_entry.text()).map(email_addresses);
+        let cc = is_empty(imp.cc_entry.text()).map(email_addresses);
         let root = self.native().unwrap();
         let identifier = WindowIdentifier::from_native(&root).await;
 
         let mut request = EmailRequest::default()
             .identifier(identifier)
             .subject(subject.as_deref())
-            .addresses::<Vec<_>, String>(addresses)
-            .cc::<Vec<_>, String>(cc)
-            .bcc::<Vec<_>, String>(bcc)
+            .addresses(addresses)
+            .cc(cc)
+            .bcc(bcc)
             .body(body.as_deref());
         let attachments = self.attachments();
         if !attachments.is_empty() {
@@ -186,3 +189,10 @@ impl EmailPage {
         }
     }
 }
+
+fn email_addresses(txt: String) -> Vec<EmailAddress> {
+    split_comma(txt)
+        .iter()
+        .filter_map(|address| EmailAddress::new(address.trim()).ok())
+        .collect()
+}
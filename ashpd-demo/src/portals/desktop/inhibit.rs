@@ -114,7 +114,7 @@ impl InhibitPage {
             SessionState::Running => tracing::info!("Session running"),
             SessionState::QueryEnd => {
                 tracing::info!("Session: query end");
-                proxy.inhibit(&identifier, flags, &reason).await?;
+                proxy.inhibit(&identifier, flags, reason.as_str()).await?;
                 if let Some(session) = imp.session.lock().await.as_ref() {
                     proxy.query_end_response(session).await?;
                 }
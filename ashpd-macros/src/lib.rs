@@ -0,0 +1,177 @@
+//! Procedural macros for [ashpd](https://docs.rs/ashpd).
+//!
+//! Use them through the `macros` feature of ashpd rather than depending on
+//! this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, LitStr, Type};
+
+/// Implements `ashpd::desktop::settings::PortalSettings` for a struct with
+/// named fields.
+///
+/// Each field maps to a setting, its key defaults to the field name with
+/// the underscores replaced by dashes. The namespace is set on the struct
+/// and can be overridden per field:
+///
+/// ```rust,ignore
+/// #[derive(Debug, Default, PortalSettings)]
+/// #[portal_settings(namespace = "org.freedesktop.appearance")]
+/// struct Theme {
+///     color_scheme: ColorScheme,
+///     accent_color: Option<Color>,
+///     #[portal_settings(namespace = "org.gnome.desktop.interface", key = "font-name")]
+///     font: String,
+///     #[portal_settings(skip)]
+///     loaded: bool,
+/// }
+/// ```
+///
+/// The field types must implement `TryFrom<OwnedValue>`, or be an `Option`
+/// of such a type for settings that might not be provided. Values of
+/// another type are ignored.
+#[proc_macro_derive(PortalSettings, attributes(portal_settings))]
+pub fn derive_portal_settings(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct Args {
+    namespace: Option<LitStr>,
+    key: Option<LitStr>,
+    skip: bool,
+}
+
+impl Args {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut args = Self::default();
+        for attr in attrs {
+            if !attr.path().is_ident("portal_settings") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("namespace") {
+                    args.namespace = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("key") {
+                    args.key = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("skip") {
+                    args.skip = true;
+                } else {
+                    return Err(meta.error("expected `namespace`, `key` or `skip`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(args)
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input,
+                    "PortalSettings requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "PortalSettings can only be derived for structs",
+            ))
+        }
+    };
+
+    let args = Args::parse(&input.attrs)?;
+    if let Some(key) = args.key {
+        return Err(syn::Error::new_spanned(
+            key,
+            "`key` is only allowed on fields",
+        ));
+    }
+
+    let mut namespaces = Vec::<String>::new();
+    let mut arms = Vec::new();
+    for field in fields {
+        let field_args = Args::parse(&field.attrs)?;
+        if field_args.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().unwrap();
+        let namespace = field_args
+            .namespace
+            .or_else(|| args.namespace.clone())
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    ident,
+                    "missing namespace, set it with `#[portal_settings(namespace = \"...\")]`",
+                )
+            })?;
+        let key = field_args.key.unwrap_or_else(|| {
+            let name = ident.to_string();
+            LitStr::new(
+                &name.trim_start_matches("r#").replace('_', "-"),
+                Span::call_site(),
+            )
+        });
+        if !namespaces.contains(&namespace.value()) {
+            namespaces.push(namespace.value());
+        }
+        // Optional fields are set once the setting is found
+        let value = if is_option(&field.ty) {
+            quote! { ::std::option::Option::Some(value) }
+        } else {
+            quote! { value }
+        };
+        arms.push(quote! {
+            (#namespace, #key) => {
+                match ::ashpd::desktop::settings::__setting_value(value) {
+                    ::std::option::Option::Some(value) => {
+                        self.#ident = #value;
+                        true
+                    }
+                    ::std::option::Option::None => false,
+                }
+            }
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ashpd::desktop::settings::PortalSettings for #name #ty_generics #where_clause {
+            const NAMESPACES: &'static [&'static str] = &[#(#namespaces),*];
+
+            fn update(
+                &mut self,
+                namespace: &str,
+                key: &str,
+                value: ::ashpd::zvariant::OwnedValue,
+            ) -> bool {
+                #[allow(unreachable_patterns)]
+                match (namespace, key) {
+                    #(#arms)*
+                    _ => false,
+                }
+            }
+        }
+    })
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
@@ -0,0 +1,311 @@
+//! Blocking wrappers of the portals, for the applications that don't run an
+//! async runtime, like command line tools or plugins.
+//!
+//! The calls are driven on a single executor shared by all the wrappers, the
+//! async-std global executor or an internal Tokio runtime depending on the
+//! enabled feature.
+//!
+//! **Note** Blocking from within an async runtime could deadlock it, the
+//! calls fail with an [`Error::InvalidState`] there, use the async API
+//! instead.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ashpd::blocking::DynamicLauncherProxy;
+//!
+//! fn main() -> ashpd::Result<()> {
+//!     let proxy = DynamicLauncherProxy::new()?;
+//!     println!("{:#?}", proxy.supported_launcher_types()?);
+//!     proxy.launch("org.example.App.Web.desktop")?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::future::Future;
+
+use enumflags2::BitFlags;
+
+use crate::{
+    desktop::{
+        dynamic_launcher::{
+            self, InstallToken, LauncherIcon, LauncherType, PrepareInstallOptions,
+            PrepareInstallResponse,
+        },
+        Icon,
+    },
+    Error, PortalProxy, WindowIdentifier,
+};
+
+#[cfg(feature = "tokio")]
+static RUNTIME: once_cell::sync::Lazy<std::io::Result<tokio::runtime::Runtime>> =
+    once_cell::sync::Lazy::new(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+    });
+
+/// Runs `future` to completion on the shared executor.
+fn block_on<T>(future: impl Future<Output = Result<T, Error>>) -> Result<T, Error> {
+    #[cfg(feature = "async-std")]
+    {
+        if async_std::task::try_current().is_some() {
+            return Err(Error::InvalidState(
+                "Blocking portal calls can't be made from an async task",
+            ));
+        }
+        async_std::task::block_on(future)
+    }
+    #[cfg(feature = "tokio")]
+    {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return Err(Error::InvalidState(
+                "Blocking portal calls can't be made from an async runtime",
+            ));
+        }
+        match &*RUNTIME {
+            Ok(runtime) => runtime.block_on(future),
+            Err(err) => Err(Error::IO(std::io::Error::new(err.kind(), err.to_string()))),
+        }
+    }
+}
+
+/// Blocking version of
+/// [`DynamicLauncherProxy`](crate::desktop::dynamic_launcher::DynamicLauncherProxy).
+#[derive(Debug)]
+#[doc(alias = "org.freedesktop.portal.DynamicLauncher")]
+pub struct DynamicLauncherProxy(dynamic_launcher::DynamicLauncherProxy<'static>);
+
+impl DynamicLauncherProxy {
+    /// Create a new instance of [`DynamicLauncherProxy`].
+    pub fn new() -> Result<Self, Error> {
+        block_on(dynamic_launcher::DynamicLauncherProxy::new()).map(Self)
+    }
+
+    /// See [`PortalProxy::set_call_timeout`].
+    pub fn set_call_timeout(&self, timeout: impl Into<Option<std::time::Duration>>) {
+        self.0.set_call_timeout(timeout);
+    }
+
+    /// Blocks until the user closes the dialog, see
+    /// [`DynamicLauncherProxy::prepare_install`](dynamic_launcher::DynamicLauncherProxy::prepare_install).
+    #[doc(alias = "PrepareInstall")]
+    pub fn prepare_install(
+        &self,
+        parent_window: &WindowIdentifier,
+        name: &str,
+        icon: impl Into<Icon>,
+        options: impl Into<PrepareInstallOptions>,
+    ) -> Result<PrepareInstallResponse, Error> {
+        block_on(async {
+            self.0
+                .prepare_install(parent_window, name, icon, options)
+                .await?
+                .response()
+        })
+    }
+
+    /// See
+    /// [`DynamicLauncherProxy::install`](dynamic_launcher::DynamicLauncherProxy::install).
+    #[doc(alias = "Install")]
+    pub fn install(
+        &self,
+        token: &InstallToken,
+        desktop_file_id: &str,
+        desktop_entry: impl Into<String>,
+    ) -> Result<(), Error> {
+        block_on(self.0.install(token, desktop_file_id, desktop_entry))
+    }
+
+    /// See
+    /// [`DynamicLauncherProxy::uninstall`](dynamic_launcher::DynamicLauncherProxy::uninstall).
+    #[doc(alias = "Uninstall")]
+    pub fn uninstall(&self, desktop_file_id: &str) -> Result<(), Error> {
+        block_on(self.0.uninstall(desktop_file_id))
+    }
+
+    /// See
+    /// [`DynamicLauncherProxy::launch`](dynamic_launcher::DynamicLauncherProxy::launch).
+    #[doc(alias = "Launch")]
+    pub fn launch(&self, desktop_file_id: &str) -> Result<(), Error> {
+        block_on(self.0.launch(desktop_file_id))
+    }
+
+    /// See
+    /// [`DynamicLauncherProxy::desktop_entry`](dynamic_launcher::DynamicLauncherProxy::desktop_entry).
+    #[doc(alias = "GetDesktopEntry")]
+    pub fn desktop_entry(&self, desktop_file_id: &str) -> Result<String, Error> {
+        block_on(self.0.desktop_entry(desktop_file_id))
+    }
+
+    /// See
+    /// [`DynamicLauncherProxy::icon`](dynamic_launcher::DynamicLauncherProxy::icon).
+    #[doc(alias = "GetIcon")]
+    pub fn icon(&self, desktop_file_id: &str) -> Result<LauncherIcon, Error> {
+        block_on(self.0.icon(desktop_file_id))
+    }
+
+    /// See
+    /// [`DynamicLauncherProxy::supported_launcher_types`](dynamic_launcher::DynamicLauncherProxy::supported_launcher_types).
+    #[doc(alias = "SupportedLauncherTypes")]
+    pub fn supported_launcher_types(&self) -> Result<BitFlags<LauncherType>, Error> {
+        block_on(self.0.supported_launcher_types())
+    }
+}
+
+#[cfg(all(test, feature = "async-std"))]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use zbus::{
+        dbus_interface,
+        zvariant::{OwnedObjectPath, OwnedValue, Value},
+        MessageHeader,
+    };
+
+    use super::*;
+    use crate::{desktop::dynamic_launcher::IconType, proxy::DESKTOP_PATH};
+
+    struct MockDynamicLauncher(Arc<Mutex<Vec<String>>>);
+
+    impl MockDynamicLauncher {
+        fn record(&self, call: String) {
+            self.0.lock().unwrap().push(call);
+        }
+    }
+
+    #[dbus_interface(name = "org.freedesktop.portal.DynamicLauncher")]
+    impl MockDynamicLauncher {
+        #[dbus_interface(property)]
+        fn supported_launcher_types(&self) -> u32 {
+            3
+        }
+
+        async fn prepare_install(
+            &self,
+            #[zbus(header)] header: MessageHeader<'_>,
+            #[zbus(connection)] connection: &zbus::Connection,
+            _parent_window: &str,
+            name: &str,
+            _icon: OwnedValue,
+            options: HashMap<String, OwnedValue>,
+        ) -> zbus::fdo::Result<OwnedObjectPath> {
+            self.record(format!("PrepareInstall {name}"));
+            let sender = header.sender().ok().flatten().unwrap();
+            let token = String::try_from(options["handle_token"].clone()).unwrap();
+            let path = format!(
+                "/org/freedesktop/portal/desktop/request/{}/{token}",
+                sender.trim_start_matches(':').replace('.', "_")
+            );
+            let results = HashMap::from([
+                ("name", Value::from(name)),
+                ("token", Value::from("install-token")),
+            ]);
+            connection
+                .emit_signal(
+                    None::<()>,
+                    path.as_str(),
+                    "org.freedesktop.portal.Request",
+                    "Response",
+                    &(0u32, results),
+                )
+                .await?;
+            Ok(OwnedObjectPath::try_from(path).unwrap())
+        }
+
+        fn install(
+            &self,
+            token: &str,
+            desktop_file_id: &str,
+            _desktop_entry: &str,
+            _options: HashMap<String, OwnedValue>,
+        ) {
+            self.record(format!("Install {token} {desktop_file_id}"));
+        }
+
+        fn uninstall(&self, desktop_file_id: &str, _options: HashMap<String, OwnedValue>) {
+            self.record(format!("Uninstall {desktop_file_id}"));
+        }
+
+        fn launch(&self, desktop_file_id: &str, _options: HashMap<String, OwnedValue>) {
+            self.record(format!("Launch {desktop_file_id}"));
+        }
+
+        fn get_desktop_entry(&self, desktop_file_id: &str) -> String {
+            self.record(format!("GetDesktopEntry {desktop_file_id}"));
+            "[Desktop Entry]\nType=Application\n".to_owned()
+        }
+
+        fn get_icon(&self, desktop_file_id: &str) -> (Value<'_>, &str, u32) {
+            self.record(format!("GetIcon {desktop_file_id}"));
+            (
+                Value::new(Value::from(("themed", Value::new(vec!["web-browser"])))),
+                "png",
+                64,
+            )
+        }
+    }
+
+    #[test]
+    fn dynamic_launcher() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let Some(_server) = async_std::task::block_on(async {
+            let server = crate::proxy::mock_desktop().await?;
+            server
+                .object_server()
+                .at(DESKTOP_PATH, MockDynamicLauncher(calls.clone()))
+                .await
+                .unwrap();
+            Some(server)
+        }) else {
+            return;
+        };
+
+        let proxy = DynamicLauncherProxy::new().unwrap();
+        let id = "org.example.App.Web.desktop";
+        assert_eq!(
+            proxy.supported_launcher_types().unwrap(),
+            LauncherType::Application | LauncherType::WebApplication
+        );
+        let response = proxy
+            .prepare_install(
+                &WindowIdentifier::default(),
+                "Web App",
+                Icon::with_names(["web-browser"]),
+                (),
+            )
+            .unwrap();
+        assert_eq!(response.name(), "Web App");
+        proxy
+            .install(response.token(), id, "[Desktop Entry]\n")
+            .unwrap();
+        proxy.launch(id).unwrap();
+        assert!(proxy
+            .desktop_entry(id)
+            .unwrap()
+            .starts_with("[Desktop Entry]"));
+        let icon = proxy.icon(id).unwrap();
+        assert_eq!(icon.icon(), &Icon::with_names(["web-browser"]));
+        assert_eq!(icon.type_(), IconType::Png);
+        proxy.uninstall(id).unwrap();
+        assert_eq!(
+            *calls.lock().unwrap(),
+            [
+                "PrepareInstall Web App".to_owned(),
+                "Install install-token org.example.App.Web.desktop".to_owned(),
+                format!("Launch {id}"),
+                format!("GetDesktopEntry {id}"),
+                format!("GetIcon {id}"),
+                format!("Uninstall {id}"),
+            ]
+        );
+
+        // Would block the executor
+        let err = async_std::task::block_on(async { proxy.launch(id) }).unwrap_err();
+        assert!(matches!(err, Error::InvalidState(_)));
+    }
+}
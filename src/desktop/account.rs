@@ -15,13 +15,15 @@
 //!         .await?
 //!         .response()?;
 //!
-//!     println!("Name: {}", response.name());
+//!     println!("Name: {}", response.name().unwrap_or("Unknown"));
 //!     println!("ID: {}", response.id());
 //!
 //!     Ok(())
 //! }
 //! ```
 
+use std::{fs, io};
+
 use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
 
 use super::HandleToken;
@@ -36,11 +38,69 @@ struct UserInformationOptions {
 
 #[derive(Debug, DeserializeDict, SerializeDict, Type)]
 /// The response of a [`UserInformationRequest`] request.
+///
+/// Only the [`id`](Self::id) is always provided, the user might decline to
+/// share the other fields or the backend might not know them.
 #[zvariant(signature = "dict")]
 pub struct UserInformation {
     id: String,
-    name: String,
-    image: url::Url,
+    name: Option<String>,
+    image: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The source of the user's avatar.
+pub enum AvatarSource {
+    /// The URI of the image, usually a `file://` one.
+    Uri(url::Url),
+    /// The image data, when provided inline as a `data:` URI.
+    Bytes(Vec<u8>),
+}
+
+impl AvatarSource {
+    fn parse(image: &str) -> Option<Self> {
+        if let Some(data) = image.strip_prefix("data:") {
+            let (metadata, content) = data.split_once(',')?;
+            let bytes = if metadata.ends_with(";base64") {
+                decode_base64(content)?
+            } else {
+                content.as_bytes().to_vec()
+            };
+            Some(Self::Bytes(bytes))
+        } else {
+            url::Url::parse(image).ok().map(Self::Uri)
+        }
+    }
+}
+
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let input = input.trim_end_matches('=').as_bytes();
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    for chunk in input.chunks(4) {
+        let mut buffer = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            buffer |= value(*c)? << (18 - 6 * i);
+        }
+        let bytes = buffer.to_be_bytes();
+        match chunk.len() {
+            4 => output.extend_from_slice(&bytes[1..4]),
+            3 => output.extend_from_slice(&bytes[1..3]),
+            2 => output.push(bytes[1]),
+            _ => return None,
+        }
+    }
+    Some(output)
 }
 
 impl UserInformation {
@@ -49,14 +109,31 @@ impl UserInformation {
         &self.id
     }
 
-    /// User name.
-    pub fn name(&self) -> &str {
-        &self.name
+    /// User name, if shared.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref().filter(|name| !name.is_empty())
     }
 
-    /// User image uri.
-    pub fn image(&self) -> &url::Url {
-        &self.image
+    /// User image, if any.
+    pub fn image(&self) -> Option<AvatarSource> {
+        self.image.as_deref().and_then(AvatarSource::parse)
+    }
+
+    /// The user image content, reading it from the disk if needed.
+    pub fn image_bytes(&self) -> io::Result<Option<Vec<u8>>> {
+        match self.image() {
+            Some(AvatarSource::Bytes(bytes)) => Ok(Some(bytes)),
+            Some(AvatarSource::Uri(uri)) => {
+                let path = uri.to_file_path().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("The image URI {uri} is not a local file"),
+                    )
+                })?;
+                fs::read(path).map(Some)
+            }
+            None => Ok(None),
+        }
     }
 
     /// Creates a new builder-pattern struct instance to construct
@@ -123,3 +200,44 @@ impl UserInformationRequest {
         proxy.user_information(&self.identifier, self.options).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AvatarSource, UserInformation};
+
+    #[test]
+    fn missing_user_information() {
+        let info = UserInformation {
+            id: "user".to_owned(),
+            name: Some(String::new()),
+            image: Some(String::new()),
+        };
+        assert_eq!(info.id(), "user");
+        assert_eq!(info.name(), None);
+        assert_eq!(info.image(), None);
+    }
+
+    #[test]
+    fn parse_avatar_source() {
+        assert_eq!(AvatarSource::parse(""), None);
+        assert_eq!(
+            AvatarSource::parse("file:///var/lib/AccountsService/icons/user"),
+            Some(AvatarSource::Uri(
+                url::Url::parse("file:///var/lib/AccountsService/icons/user").unwrap()
+            ))
+        );
+        assert_eq!(
+            AvatarSource::parse("data:image/png;base64,aGVsbG8="),
+            Some(AvatarSource::Bytes(b"hello".to_vec()))
+        );
+        assert_eq!(
+            AvatarSource::parse("data:image/png;base64,aGVsbG8h"),
+            Some(AvatarSource::Bytes(b"hello!".to_vec()))
+        );
+        assert_eq!(
+            AvatarSource::parse("data:text/plain,hi"),
+            Some(AvatarSource::Bytes(b"hi".to_vec()))
+        );
+        assert_eq!(AvatarSource::parse("data:image/png;base64,a"), None);
+    }
+}
@@ -30,11 +30,16 @@
 //! If no `command` is provided, the [`Exec`](https://specifications.freedesktop.org/desktop-entry-spec/desktop-entry-spec-latest.html#exec-variables) line from the [desktop
 //! file](https://specifications.freedesktop.org/desktop-entry-spec/desktop-entry-spec-latest.html#introduction) will be used.
 
+use std::path::{Path, PathBuf};
+
 use serde::Serialize;
 use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
 
 use super::{HandleToken, Request};
-use crate::{proxy::Proxy, Error, WindowIdentifier};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy, WindowIdentifier,
+};
 
 #[derive(SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
@@ -97,6 +102,13 @@ impl<'a> BackgroundProxy<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`BackgroundProxy::new`], failing with [`Error::NotSupported`]
+    /// if the portal doesn't provide `org.freedesktop.portal.Background`.
+    pub async fn new_checked() -> Result<BackgroundProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Background").await?;
+        Ok(Self(proxy))
+    }
+
     ///  Sets the status of the application running in background.
     ///
     /// # Arguments
@@ -104,10 +116,12 @@ impl<'a> BackgroundProxy<'a> {
     /// * `message` - A string that will be used as the status message of the
     ///   application.
     ///
+    /// Fails with [`Error::NotSupported`] without calling the portal if the
+    /// running interface is older than version 2, which introduced it.
+    ///
     /// # Specifications
     ///
     /// See also [`SetStatus`](https://flatpak.github.io/xdg-desktop-portal/#gdbus-method-org-freedesktop-portal-Background.SetStatus).
-
     pub async fn set_status(&self, message: &str) -> Result<(), Error> {
         self.0
             .call(
@@ -119,6 +133,32 @@ impl<'a> BackgroundProxy<'a> {
             .await
     }
 
+    /// Whether the autostart desktop file of the application exists.
+    ///
+    /// When auto start is granted, the portal writes
+    /// `$XDG_CONFIG_HOME/autostart/<app-id>.desktop` on the host, which is
+    /// `~/.config/autostart` by default. Combined with
+    /// [`Background::auto_start`], this lets a "launch at login" toggle
+    /// reflect the real state once the request completed.
+    ///
+    /// Returns `false` if the application has no application ID, or if the
+    /// autostart directory is not visible from inside the sandbox, for
+    /// example a Flatpak without access to `xdg-config/autostart`.
+    ///
+    /// # Errors
+    ///
+    /// An [`Error::IO`] is returned if the existence of the file can't be
+    /// checked.
+    pub async fn autostart_enabled(&self) -> Result<bool, Error> {
+        let Some(app_id) = crate::sandbox::app_id().await else {
+            return Ok(false);
+        };
+        let Some(config_dir) = host_config_dir() else {
+            return Ok(false);
+        };
+        Ok(autostart_path(&config_dir, &app_id).try_exists()?)
+    }
+
     async fn request_background(
         &self,
         identifier: &WindowIdentifier,
@@ -134,6 +174,14 @@ impl<'a> BackgroundProxy<'a> {
     }
 }
 
+impl AsProxy for BackgroundProxy<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for BackgroundProxy<'_> {}
+
 #[doc(alias = "xdp_portal_request_background")]
 /// A [builder-pattern] type to construct [`Background`].
 ///
@@ -195,3 +243,94 @@ impl BackgroundRequest {
             .await
     }
 }
+
+/// The user configuration directory of the host, Flatpak overrides
+/// `XDG_CONFIG_HOME` and passes the host one as `HOST_XDG_CONFIG_HOME`.
+fn host_config_dir() -> Option<PathBuf> {
+    std::env::var_os("HOST_XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+}
+
+fn autostart_path(config_dir: &Path, app_id: &str) -> PathBuf {
+    config_dir
+        .join("autostart")
+        .join(format!("{app_id}.desktop"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::autostart_path;
+
+    #[test]
+    fn test_autostart_path() {
+        assert_eq!(
+            autostart_path(Path::new("/home/user/.config"), "org.gnome.Fractal"),
+            Path::new("/home/user/.config/autostart/org.gnome.Fractal.desktop")
+        );
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn test_method_version() {
+        use std::{
+            collections::HashMap,
+            sync::{Arc, Mutex},
+        };
+
+        use zbus::{dbus_interface, zvariant::OwnedValue};
+
+        use super::BackgroundProxy;
+        use crate::proxy::DESKTOP_PATH;
+
+        struct MockBackground {
+            version: u32,
+            statuses: Arc<Mutex<Vec<String>>>,
+        }
+
+        #[dbus_interface(name = "org.freedesktop.portal.Background")]
+        impl MockBackground {
+            #[dbus_interface(property, name = "version")]
+            fn version(&self) -> u32 {
+                self.version
+            }
+
+            fn set_status(&self, options: HashMap<String, OwnedValue>) {
+                let message = String::try_from(options["message"].clone()).unwrap();
+                self.statuses.lock().unwrap().push(message);
+            }
+        }
+
+        async_std::task::block_on(async {
+            for version in [1, 2] {
+                let Some(server) = crate::proxy::mock_desktop().await else {
+                    return;
+                };
+                let statuses = Arc::new(Mutex::new(Vec::new()));
+                let portal = MockBackground {
+                    version,
+                    statuses: statuses.clone(),
+                };
+                server
+                    .object_server()
+                    .at(DESKTOP_PATH, portal)
+                    .await
+                    .unwrap();
+
+                let proxy = BackgroundProxy::new().await.unwrap();
+                let result = proxy.set_status("Syncing").await;
+                if version == 1 {
+                    assert!(result.unwrap_err().is_not_supported());
+                    assert!(statuses.lock().unwrap().is_empty());
+                } else {
+                    result.unwrap();
+                    assert_eq!(*statuses.lock().unwrap(), ["Syncing".to_owned()]);
+                }
+                drop(server);
+            }
+        });
+    }
+}
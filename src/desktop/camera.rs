@@ -22,10 +22,19 @@ use std::{
     os::unix::prelude::{IntoRawFd, RawFd},
 };
 
+use futures_util::{future::ready, stream, StreamExt};
 use zbus::zvariant::{OwnedFd, SerializeDict, Type, Value};
 
 use super::{HandleToken, Request};
-use crate::{proxy::Proxy, Error};
+use crate::{
+    documents::PermissionStore,
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
+
+/// The permission store table & resource of the camera access.
+const PERMISSION_TABLE: &str = "devices";
+const PERMISSION_ID: &str = "camera";
 
 #[derive(SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
@@ -36,6 +45,12 @@ struct CameraAccessOptions {
 /// The interface lets sandboxed applications access camera devices, such as web
 /// cams.
 ///
+/// Access is granted to all the cameras at once, the portal doesn't allow
+/// requesting a specific device. To use a specific camera, list the nodes
+/// available on the remote returned by [`Camera::open_pipe_wire_remote`], for
+/// example with `pipewire_streams` when the `pipewire` feature is enabled, and
+/// connect to the chosen node id.
+///
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.Camera`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.portal.Camera).
 #[derive(Debug)]
 #[doc(alias = "org.freedesktop.portal.Camera")]
@@ -48,6 +63,13 @@ impl<'a> Camera<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`Camera::new`], failing with [`Error::NotSupported`] if the
+    /// portal doesn't provide `org.freedesktop.portal.Camera`.
+    pub async fn new_checked() -> Result<Camera<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Camera").await?;
+        Ok(Self(proxy))
+    }
+
     /// Requests an access to the camera.
     ///
     /// # Specifications
@@ -62,6 +84,52 @@ impl<'a> Camera<'a> {
             .await
     }
 
+    /// A stream of whether the application is allowed to access the camera,
+    /// starting with the current state and emitted every time the user grants
+    /// or revokes the access, for example from the system settings.
+    ///
+    /// The portal doesn't signal it, the changes are received from the
+    /// [`PermissionStore`] instead, so no polling is involved. Applications
+    /// that are not sandboxed are always allowed.
+    ///
+    /// **Note** the permission store might not be accessible from within the
+    /// sandbox, in which case an error is returned and the application should
+    /// fall back to handling the errors of [`Self::open_pipe_wire_remote`].
+    pub async fn receive_access_changed(
+        &self,
+    ) -> Result<impl futures_util::Stream<Item = bool> + 'a, Error> {
+        let app_id = crate::sandbox::app_id()
+            .await
+            .map(|app_id| app_id.to_string());
+        let store = PermissionStore::<'a>::new().await?;
+        let changes = store.receive_changed().await?;
+        let mut granted = match &app_id {
+            Some(app_id) => store
+                .permission(PERMISSION_TABLE, PERMISSION_ID, app_id.as_str())
+                .await
+                .is_ok_and(|permissions| is_granted(&permissions)),
+            None => true,
+        };
+        let changes = changes.filter_map(move |changed| {
+            let app_id = app_id.as_deref();
+            let changed = app_id
+                .filter(|_| changed.table() == PERMISSION_TABLE && changed.id() == PERMISSION_ID)
+                .map(|app_id| {
+                    !changed.is_deleted()
+                        && changed
+                            .permissions()
+                            .get(app_id)
+                            .is_some_and(|permissions| is_granted(permissions))
+                })
+                .filter(|now| *now != granted);
+            if let Some(now) = changed {
+                granted = now;
+            }
+            ready(changed)
+        });
+        Ok(stream::once(ready(granted)).chain(changes))
+    }
+
     /// Open a file descriptor to the PipeWire remote where the camera nodes are
     /// available.
     ///
@@ -85,6 +153,27 @@ impl<'a> Camera<'a> {
         Ok(fd.into_raw_fd())
     }
 
+    /// Request access to the camera and open the PipeWire remote, returning
+    /// its file descriptor along with the node ids of the available cameras.
+    ///
+    /// Returns `None` if there is no camera or if the user denied the access.
+    ///
+    /// *Note* The returned node ids are the ones of the `media.role` type of
+    /// `Camera` nodes, see [`pipewire_streams`] for their properties.
+    #[cfg(feature = "pipewire")]
+    pub async fn open_stream(&self) -> Result<Option<(RawFd, Vec<u32>)>, Error> {
+        if !self.is_present().await? {
+            return Ok(None);
+        }
+        match self.request_access().await?.response() {
+            Err(Error::Response { .. }) => return Ok(None),
+            result => result?,
+        }
+        let fd = self.open_pipe_wire_remote().await?;
+        let streams = pipewire_streams(fd).await?;
+        Ok(Some((fd, streams.iter().map(Stream::node_id).collect())))
+    }
+
     /// A boolean stating whether there is any cameras available.
     ///
     /// # Specifications
@@ -95,6 +184,35 @@ impl<'a> Camera<'a> {
     pub async fn is_present(&self) -> Result<bool, Error> {
         self.0.property("IsCameraPresent").await
     }
+
+    /// A stream of whether there is any cameras available, emitted every time
+    /// a camera is plugged in or removed.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`IsCameraPresent`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-property-org-freedesktop-portal-Camera.IsCameraPresent).
+    #[doc(alias = "IsCameraPresent")]
+    pub async fn receive_is_present_changed(
+        &self,
+    ) -> Result<impl futures_util::Stream<Item = bool> + 'a, Error> {
+        self.0.receive_property_changed("IsCameraPresent").await
+    }
+}
+
+impl AsProxy for Camera<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for Camera<'_> {}
+
+/// Whether the stored permissions allow the access, rather than deny it or
+/// require to ask the user again.
+fn is_granted(permissions: &[String]) -> bool {
+    permissions
+        .first()
+        .is_some_and(|permission| permission == "yes")
 }
 
 #[cfg(feature = "pipewire")]
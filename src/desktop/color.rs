@@ -0,0 +1,195 @@
+use std::fmt;
+
+use serde::{
+    de::{self, Error as SeError, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use zbus::zvariant::{OwnedValue, Signature, Type, Value};
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+/// A color, as returned by
+/// [`ColorRequest`](crate::desktop::screenshot::ColorRequest) or
+/// [`Settings::accent_color`](crate::desktop::settings::Settings::accent_color).
+///
+/// **Note** the values are normalized sRGB values, in the range of `[0, 1]`.
+pub struct Color {
+    red: f64,
+    green: f64,
+    blue: f64,
+}
+
+impl Color {
+    /// Create a new color from its normalized components.
+    pub fn new(red: f64, green: f64, blue: f64) -> Self {
+        Self { red, green, blue }
+    }
+
+    /// Red.
+    pub fn red(&self) -> f64 {
+        self.red
+    }
+
+    /// Green.
+    pub fn green(&self) -> f64 {
+        self.green
+    }
+
+    /// Blue.
+    pub fn blue(&self) -> f64 {
+        self.blue
+    }
+
+    /// The color components converted to 8 bits values.
+    ///
+    /// Values outside of the `[0, 1]` range are clamped.
+    pub fn to_rgb8(&self) -> [u8; 3] {
+        let to_u8 = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [to_u8(self.red), to_u8(self.green), to_u8(self.blue)]
+    }
+
+    /// The color in the `#rrggbb` hexadecimal notation.
+    pub fn to_hex(&self) -> String {
+        let [red, green, blue] = self.to_rgb8();
+        format!("#{red:02x}{green:02x}{blue:02x}")
+    }
+}
+
+impl Type for Color {
+    fn signature() -> Signature<'static> {
+        <(f64, f64, f64)>::signature()
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ColorVisitor;
+
+        impl<'de> Visitor<'de> for ColorVisitor {
+            type Value = Color;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a (ddd) color or a vardict with a color entry")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut component = || {
+                    seq.next_element::<f64>()?
+                        .ok_or_else(|| A::Error::custom("Expected three color components"))
+                };
+                Ok(Color::new(component()?, component()?, component()?))
+            }
+
+            // The PickColor response is a vardict with the color stored in the
+            // `color` key.
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut color = None;
+                while let Some((key, value)) = map.next_entry::<String, Value<'_>>()? {
+                    if key == "color" {
+                        color = Some(
+                            Color::try_from(OwnedValue::from(value)).map_err(A::Error::custom)?,
+                        );
+                    }
+                }
+                color.ok_or_else(|| A::Error::missing_field("color"))
+            }
+        }
+
+        deserializer.deserialize_tuple(3, ColorVisitor)
+    }
+}
+
+impl TryFrom<OwnedValue> for Color {
+    type Error = zbus::zvariant::Error;
+
+    fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+        let (red, green, blue) = <(f64, f64, f64)>::try_from(value)?;
+        Ok(Self::new(red, green, blue))
+    }
+}
+
+#[cfg(feature = "gtk3")]
+impl From<Color> for gtk3::gdk::RGBA {
+    fn from(color: Color) -> Self {
+        gtk3::gdk::RGBA::new(color.red(), color.green(), color.blue(), 1.0)
+    }
+}
+
+#[cfg(feature = "gtk4")]
+impl From<Color> for gtk4::gdk::RGBA {
+    fn from(color: Color) -> Self {
+        gtk4::gdk::RGBA::builder()
+            .red(color.red() as f32)
+            .green(color.green() as f32)
+            .blue(color.blue() as f32)
+            .build()
+    }
+}
+
+impl fmt::Debug for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Color")
+            .field("red", &self.red())
+            .field("green", &self.green())
+            .field("blue", &self.blue())
+            .finish()
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format!(
+            "({}, {}, {})",
+            self.red(),
+            self.green(),
+            self.blue()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use byteorder::LE;
+    use zbus::zvariant::{from_slice, to_bytes, EncodingContext as Context};
+
+    use super::*;
+    use crate::desktop::Response;
+
+    #[test]
+    fn serialize_deserialize() {
+        let ctxt = Context::<LE>::new_dbus(0);
+        assert_eq!(Color::signature().as_str(), "(ddd)");
+
+        let color = Color::new(1.0, 0.5, 0.0);
+        let encoded = to_bytes(ctxt, &color).unwrap();
+        let decoded: Color = from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(decoded, color);
+
+        // The PickColor response
+        let mut results = HashMap::new();
+        results.insert("color", Value::from((1.0, 0.5, 0.0)));
+        let encoded = to_bytes(ctxt, &(0u32, results)).unwrap();
+        let decoded: Response<Color> = from_slice(&encoded, ctxt).unwrap();
+        assert!(matches!(decoded, Response::Ok(c) if c == color));
+    }
+
+    #[test]
+    fn conversions() {
+        let color = Color::new(1.0, 0.5, 0.0);
+        assert_eq!(color.to_rgb8(), [255, 128, 0]);
+        assert_eq!(color.to_hex(), "#ff8000");
+
+        let color = Color::new(1.2, -0.1, 0.2);
+        assert_eq!(color.to_hex(), "#ff0033");
+    }
+}
@@ -22,7 +22,10 @@ use serde::{Deserialize, Serialize};
 use zbus::zvariant::{SerializeDict, Type};
 
 use super::{HandleToken, Request};
-use crate::{proxy::Proxy, Error};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
 #[derive(SerializeDict, Type, Debug, Default)]
 /// Specified options for a [`DeviceProxy::access_device`] request.
@@ -105,6 +108,13 @@ impl<'a> DeviceProxy<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`DeviceProxy::new`], failing with [`Error::NotSupported`] if
+    /// the portal doesn't provide `org.freedesktop.portal.Device`.
+    pub async fn new_checked() -> Result<DeviceProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Device").await?;
+        Ok(Self(proxy))
+    }
+
     /// Asks for access to a device.
     ///
     /// # Arguments
@@ -131,3 +141,11 @@ impl<'a> DeviceProxy<'a> {
             .await
     }
 }
+
+impl AsProxy for DeviceProxy<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for DeviceProxy<'_> {}
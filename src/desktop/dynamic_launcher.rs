@@ -3,7 +3,7 @@
 //! ```rust,no_run
 //! use ashpd::{
 //!     desktop::{
-//!         dynamic_launcher::{DynamicLauncherProxy, LauncherType, PrepareInstallOptions},
+//!         dynamic_launcher::{DesktopEntry, DynamicLauncherProxy, LauncherType},
 //!         Icon,
 //!     },
 //!     WindowIdentifier,
@@ -11,25 +11,21 @@
 //!
 //! async fn run() -> ashpd::Result<()> {
 //!     let proxy = DynamicLauncherProxy::new().await?;
-//!     let (name, token) = proxy
+//!     let response = proxy
 //!         .prepare_install(
 //!             &WindowIdentifier::default(),
 //!             "My App",
 //!             Icon::with_names(&["dialog-symbolic"]),
-//!             PrepareInstallOptions::default(),
+//!             (),
 //!         )
 //!         .await?
 //!         .response()?;
 //!
 //!     // Name and Icon will be overwritten from what we provided above
 //!     // Exec will be overridden to call `flatpak run our-app` if the application is sandboxed
-//!     let desktop_entry = r#"
-//!         [Desktop Entry]
-//!         Comment=My Web App
-//!         Type=Application
-//!     "#;
+//!     let desktop_entry = DesktopEntry::default().comment("My Web App");
 //!     proxy
-//!         .install(&token, "some_file.desktop", desktop_entry)
+//!         .install(response.token(), "some_file.desktop", &desktop_entry)
 //!         .await?;
 //!
 //!     proxy.uninstall("some_file.desktop").await?;
@@ -37,15 +33,25 @@
 //! }
 //! ```
 
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt, fs, io, ops::Deref, path::Path, time::Duration};
 
 use enumflags2::{bitflags, BitFlags};
+use futures_util::StreamExt;
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{self, SerializeDict, Type};
+use zbus::{
+    names::WellKnownName,
+    zvariant::{self, DeserializeDict, OwnedValue, SerializeDict, Type, Value},
+};
 
 use super::{HandleToken, Icon, Request};
-use crate::{proxy::Proxy, Error, WindowIdentifier};
+use crate::{
+    helpers::WithExtra,
+    proxy::{retry, sealed::AsProxy, with_timeout, Proxy},
+    util::{join_all_limited, DEFAULT_CONCURRENCY},
+    Error, PortalError, PortalProxy, RetryPolicy, WindowIdentifier,
+};
 
 #[bitflags]
 #[derive(Default, Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Copy, Clone, Type)]
@@ -75,12 +81,124 @@ pub enum IconType {
     Svg,
 }
 
-#[derive(Deserialize, Type)]
+impl IconType {
+    const ALL: [Self; 3] = [Self::Png, Self::Jpeg, Self::Svg];
+
+    /// The MIME type of the icon format, e.g. to set the content type of an
+    /// upload.
+    pub fn as_mime(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+
+    /// The icon format of a MIME type, if supported.
+    pub fn from_mime(mime_type: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|type_| type_.as_mime() == mime_type)
+    }
+
+    /// The icon format of a file extension, without the leading dot and
+    /// ignoring the case, if supported.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let extension = extension.to_ascii_lowercase();
+        let name = match extension.as_str() {
+            "jpg" => "jpeg",
+            name => name,
+        };
+        Self::ALL.into_iter().find(|type_| type_.name() == name)
+    }
+
+    /// The name of the icon format on the bus, the MIME subtype without its
+    /// structured syntax suffix.
+    fn name(&self) -> &'static str {
+        let subtype = &self.as_mime()["image/".len()..];
+        subtype.split('+').next().unwrap_or(subtype)
+    }
+
+    /// Guess the icon format from the beginning of the image data, for
+    /// example of an icon downloaded by a browser.
+    ///
+    /// SVG icons are recognized by their `<svg` or `<?xml` start, after an
+    /// optional byte order mark and white spaces. Returns `None` for the
+    /// other formats.
+    pub fn guess_from_bytes(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"\x89PNG") {
+            return Some(Self::Png);
+        }
+        if data.starts_with(b"\xFF\xD8") {
+            return Some(Self::Jpeg);
+        }
+        let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+        let start = data.iter().position(|b| !b.is_ascii_whitespace())?;
+        let data = &data[start..];
+        if data.starts_with(b"<svg") || data.starts_with(b"<?xml") {
+            Some(Self::Svg)
+        } else {
+            None
+        }
+    }
+
+    /// The usual file extension of the icon format.
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Type)]
 #[zvariant(signature = "(vsu)")]
 /// The icon of the launcher.
 pub struct LauncherIcon(Icon, IconType, u32);
 
+impl<'de> Deserialize<'de> for LauncherIcon {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (icon, type_, size) = <(OwnedValue, IconType, u32)>::deserialize(deserializer)?;
+        let icon = Icon::try_from(icon).map_err(serde::de::Error::custom)?;
+        Ok(Self(icon, type_, size))
+    }
+}
+
+impl TryFrom<OwnedValue> for LauncherIcon {
+    type Error = crate::Error;
+    fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+        let fields = value
+            .downcast_ref::<zvariant::Structure>()
+            .map(|structure| structure.fields());
+        let Some([Value::Value(icon), Value::Str(type_), Value::U32(size)]) = fields else {
+            return Err(zvariant::Error::IncorrectType.into());
+        };
+        let type_ = IconType::ALL
+            .into_iter()
+            .find(|t| t.name() == type_.as_str())
+            .ok_or_else(|| PortalError::InvalidArgument(format!("Unknown icon type {type_}")))?;
+        let icon = Icon::try_from(OwnedValue::from(icon.as_ref().clone()))?;
+        Ok(Self(icon, type_, *size))
+    }
+}
+
 impl LauncherIcon {
+    /// Create a launcher icon, for example to pass to
+    /// [`DynamicLauncherProxy::prepare_install_with_icons`].
+    ///
+    /// # Arguments
+    ///
+    /// * `icon` - The icon, usually its bytes.
+    /// * `type_` - The format of the icon.
+    /// * `size` - The size of the icon, in pixels.
+    pub fn new(icon: impl Into<Icon>, type_: IconType, size: u32) -> Self {
+        Self(icon.into(), type_, size)
+    }
+
     /// The actual icon.
     pub fn icon(&self) -> &Icon {
         &self.0
@@ -95,12 +213,435 @@ impl LauncherIcon {
     pub fn size(&self) -> u32 {
         self.2
     }
+
+    /// The image data of the icon, `None` if it is not an [`Icon::Bytes`].
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match &self.0 {
+            Icon::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Write the image data of the icon to `path`, with its extension
+    /// replaced by the one of the [`IconType`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidInput`] if the icon
+    /// is not an [`Icon::Bytes`].
+    pub fn save_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let bytes = self.bytes().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The launcher icon has no image data",
+            )
+        })?;
+        fs::write(path.as_ref().with_extension(self.1.extension()), bytes)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// The type of a desktop entry.
+pub enum EntryType {
+    #[default]
+    /// An application.
+    Application,
+    /// A link to a URL.
+    Link,
+}
+
+impl EntryType {
+    /// The value of the `Type` key.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Application => "Application",
+            Self::Link => "Link",
+        }
+    }
+}
+
+impl fmt::Display for EntryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl AsRef<str> for EntryType {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<EntryType> for &'static str {
+    fn from(e: EntryType) -> Self {
+        e.as_str()
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// A [builder-pattern] type to write the desktop entry passed to
+/// [`DynamicLauncherProxy::install`].
+///
+/// The `Name` and `Icon` keys are not exposed as the portal overrides them
+/// with the ones passed to [`DynamicLauncherProxy::prepare_install`]. The
+/// `Exec` key is omitted unless set with [`DesktopEntry::exec`].
+///
+/// An installed entry can be read back with [`DesktopEntry::parse`].
+///
+/// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
+pub struct DesktopEntry {
+    entry_type: EntryType,
+    comment: Option<String>,
+    url: Option<String>,
+    exec: Option<String>,
+    categories: Vec<String>,
+    keywords: Vec<String>,
+    raw_fields: Vec<(String, String)>,
+}
+
+impl DesktopEntry {
+    /// Sets the entry type, defaults to [`EntryType::Application`].
+    #[must_use]
+    pub fn entry_type(mut self, entry_type: EntryType) -> Self {
+        self.entry_type = entry_type;
+        self
+    }
+
+    /// Sets the tooltip of the entry.
+    #[must_use]
+    pub fn comment<'a>(mut self, comment: impl Into<Option<&'a str>>) -> Self {
+        self.comment = comment.into().map(ToOwned::to_owned);
+        self
+    }
+
+    /// Sets the URL the entry points to, only used by [`EntryType::Link`].
+    #[must_use]
+    pub fn url<'a>(mut self, url: impl Into<Option<&'a str>>) -> Self {
+        self.url = url.into().map(ToOwned::to_owned);
+        self
+    }
+
+    /// Sets the command line launching the entry.
+    ///
+    /// **Note** the portal overrides it when the application is sandboxed,
+    /// see [`DynamicLauncherProxy::will_override_exec`].
+    #[must_use]
+    pub fn exec<'a>(mut self, exec: impl Into<Option<&'a str>>) -> Self {
+        self.exec = exec.into().map(ToOwned::to_owned);
+        self
+    }
+
+    /// Sets the categories in which the entry should be shown in a menu.
+    #[must_use]
+    pub fn categories<P: IntoIterator<Item = I>, I: AsRef<str>>(mut self, categories: P) -> Self {
+        self.categories = categories
+            .into_iter()
+            .map(|s| s.as_ref().to_owned())
+            .collect();
+        self
+    }
+
+    /// Sets the keywords used to search for the entry, in addition to its
+    /// name.
+    #[must_use]
+    pub fn keywords<P: IntoIterator<Item = I>, I: AsRef<str>>(mut self, keywords: P) -> Self {
+        self.keywords = keywords
+            .into_iter()
+            .map(|s| s.as_ref().to_owned())
+            .collect();
+        self
+    }
+
+    /// Adds a key that this type doesn't expose, like `StartupWMClass` or a
+    /// localized key such as `Comment[fr]`. The value is escaped but
+    /// otherwise written as is.
+    ///
+    /// **Note** the key has to be made of alphanumeric characters and dashes,
+    /// optionally followed by a locale, which is checked by
+    /// [`DynamicLauncherProxy::install_entry`].
+    #[must_use]
+    pub fn raw_field(mut self, key: &str, value: &str) -> Self {
+        self.raw_fields.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// The content of the desktop entry file, same as its
+    /// [`Display`](std::fmt::Display) implementation.
+    pub fn to_desktop_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Parse the `[Desktop Entry]` group of a desktop entry file, for example
+    /// the one returned by [`DynamicLauncherProxy::desktop_entry`].
+    ///
+    /// The comments and the other groups, like the desktop actions, are
+    /// skipped. The values are unescaped, except for `\;` outside of the
+    /// `Categories` and `Keywords` lists.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortalError::InvalidArgument`] if the content is not a
+    /// key file starting with a `[Desktop Entry]` group, or if the entry type
+    /// is neither `Application` nor `Link`.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        validate_desktop_entry(content)?;
+        let mut entry = Self::default();
+        let mut in_group = false;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(group) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                in_group = group == "Desktop Entry";
+                continue;
+            }
+            // Every other line is a key=value pair, see `validate_desktop_entry`
+            let Some((key, value)) = line.split_once('=').filter(|_| in_group) else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim_start());
+            match key {
+                "Type" => {
+                    entry.entry_type = match value {
+                        "Application" => EntryType::Application,
+                        "Link" => EntryType::Link,
+                        _ => {
+                            return Err(PortalError::InvalidArgument(format!(
+                                "Unsupported desktop entry type '{value}'"
+                            ))
+                            .into())
+                        }
+                    }
+                }
+                "Comment" => entry.comment = Some(unescape_value(value)),
+                "URL" => entry.url = Some(unescape_value(value)),
+                "Exec" => entry.exec = Some(unescape_value(value)),
+                "Categories" => entry.categories = split_list(value),
+                "Keywords" => entry.keywords = split_list(value),
+                key => entry
+                    .raw_fields
+                    .push((key.to_owned(), unescape_value(value))),
+            }
+        }
+        Ok(entry)
+    }
+
+    /// The value of `key`, for example `Exec`, `Comment` or `Name`.
+    ///
+    /// **Note** the `Categories` and `Keywords` lists are not returned.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        match key {
+            "Type" => Some(self.entry_type.as_ref()),
+            "Comment" => self.comment.as_deref(),
+            "URL" => self.url.as_deref(),
+            "Exec" => self.exec.as_deref(),
+            "Categories" | "Keywords" => None,
+            key => self
+                .raw_fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.as_str()),
+        }
+    }
+
+    /// The name of the entry, only set on a parsed entry as the portal
+    /// overrides it.
+    pub fn name(&self) -> Option<&str> {
+        self.get("Name")
+    }
+
+    /// The value of `key` for `locale`, for example `de_DE.UTF-8`.
+    ///
+    /// Falls back to less specific locales, then to the value that isn't
+    /// localized, following the desktop entry specification.
+    pub fn localized(&self, key: &str, locale: &str) -> Option<&str> {
+        let (locale, modifier) = match locale.split_once('@') {
+            Some((locale, modifier)) => (locale, Some(modifier)),
+            None => (locale, None),
+        };
+        // The encoding is ignored
+        let locale = locale.split('.').next().unwrap_or(locale);
+        let (lang, country) = match locale.split_once('_') {
+            Some((lang, country)) => (lang, Some(country)),
+            None => (locale, None),
+        };
+        let mut locales = Vec::with_capacity(4);
+        if let (Some(country), Some(modifier)) = (country, modifier) {
+            locales.push(format!("{lang}_{country}@{modifier}"));
+        }
+        if let Some(country) = country {
+            locales.push(format!("{lang}_{country}"));
+        }
+        if let Some(modifier) = modifier {
+            locales.push(format!("{lang}@{modifier}"));
+        }
+        locales.push(lang.to_owned());
+        locales
+            .iter()
+            .find_map(|locale| self.get(&format!("{key}[{locale}]")))
+            .or_else(|| self.get(key))
+    }
+
+    /// Checks the keys added with [`DesktopEntry::raw_field`].
+    fn validate(&self) -> Result<(), PortalError> {
+        match self.raw_fields.iter().find(|(key, _)| !is_valid_key(key)) {
+            Some((key, _)) => Err(PortalError::InvalidArgument(format!(
+                "Invalid desktop entry key '{key}'"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Whether `key` is a valid desktop entry key, with an optional locale.
+fn is_valid_key(key: &str) -> bool {
+    let (name, locale) = match key.split_once('[') {
+        Some((name, locale)) => match locale.strip_suffix(']') {
+            Some(locale) => (name, Some(locale)),
+            None => return false,
+        },
+        None => (key, None),
+    };
+    !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && locale.is_none_or(|locale| {
+            !locale.is_empty()
+                && locale
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "_@.".contains(c))
+        })
+}
+
+/// Escape a value following the desktop entry specification.
+fn escape_value(value: &str, escape_separator: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            ';' if escape_separator => escaped.push_str("\\;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Unescape a value following the desktop entry specification, `\;` is kept
+/// escaped as it is only meaningful in lists.
+fn unescape_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') => unescaped.push('\\'),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Split a list value on the `;` separators that are not escaped.
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(';') => item.push(';'),
+                Some(c) => {
+                    item.push('\\');
+                    item.push(c);
+                }
+                None => item.push('\\'),
+            },
+            ';' => items.push(unescape_value(&std::mem::take(&mut item))),
+            c => item.push(c),
+        }
+    }
+    if !item.is_empty() {
+        items.push(unescape_value(&item));
+    }
+    items
+}
+
+/// Whether [`DynamicLauncherProxy::uninstall`] failed because the launcher
+/// is not installed.
+fn is_not_installed(error: &Error) -> bool {
+    match error {
+        Error::Portal(PortalError::NotFound(_)) => true,
+        // The GIO `NotFound` error of deleting the desktop file
+        Error::Portal(PortalError::ZBus(zbus::Error::MethodError(name, _, _))) => {
+            name.as_str() == "org.gtk.GDBus.UnmappedGError.Quark._g_2dio_2derror_2dquark.Code1"
+        }
+        _ => false,
+    }
+}
+
+impl fmt::Display for DesktopEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[Desktop Entry]")?;
+        writeln!(f, "Type={}", self.entry_type)?;
+        if let Some(comment) = &self.comment {
+            writeln!(f, "Comment={}", escape_value(comment, false))?;
+        }
+        if self.entry_type == EntryType::Link {
+            if let Some(url) = &self.url {
+                writeln!(f, "URL={}", escape_value(url, false))?;
+            }
+        }
+        if let Some(exec) = &self.exec {
+            writeln!(f, "Exec={}", escape_value(exec, false))?;
+        }
+        for (key, values) in [
+            ("Categories", &self.categories),
+            ("Keywords", &self.keywords),
+        ] {
+            if !values.is_empty() {
+                write!(f, "{key}=")?;
+                for value in values {
+                    write!(f, "{};", escape_value(value, true))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        for (key, value) in &self.raw_fields {
+            writeln!(f, "{key}={}", escape_value(value, false))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&DesktopEntry> for String {
+    fn from(entry: &DesktopEntry) -> Self {
+        entry.to_string()
+    }
+}
+
+impl From<DesktopEntry> for String {
+    fn from(entry: DesktopEntry) -> Self {
+        entry.to_string()
+    }
 }
 
 #[derive(Debug, Default, SerializeDict, Type)]
 #[zvariant(signature = "dict")]
-/// Options to pass to [`DynamicLauncherProxy::prepare_install`]
-pub struct PrepareInstallOptions {
+struct PrepareInstallOptionsDict {
     handle_token: HandleToken,
     modal: Option<bool>,
     launcher_type: LauncherType,
@@ -109,54 +650,232 @@ pub struct PrepareInstallOptions {
     editable_icon: Option<bool>,
 }
 
+#[derive(Debug, Clone, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+/// The response of [`DynamicLauncherProxy::prepare_install`].
+pub struct PrepareInstallResponse {
+    name: String,
+    token: InstallToken,
+    icon: Option<Icon>,
+}
+
+impl PrepareInstallResponse {
+    /// The name of the launcher, as chosen by the user if
+    /// [`PrepareInstallOptions::editable_name`] was set.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The token to pass to [`DynamicLauncherProxy::install`].
+    pub fn token(&self) -> &InstallToken {
+        &self.token
+    }
+
+    /// The icon chosen by the user, if the portal returned it.
+    pub fn icon(&self) -> Option<&Icon> {
+        self.icon.as_ref()
+    }
+}
+
+impl From<PrepareInstallResponse> for (String, String) {
+    fn from(response: PrepareInstallResponse) -> Self {
+        (response.name, response.token.0)
+    }
+}
+
+#[derive(Debug, Default)]
+/// Options to pass to [`DynamicLauncherProxy::prepare_install`]
+pub struct PrepareInstallOptions {
+    options: PrepareInstallOptionsDict,
+    extra: HashMap<String, OwnedValue>,
+}
+
+impl From<()> for PrepareInstallOptions {
+    fn from(_: ()) -> Self {
+        Self::default()
+    }
+}
+
 impl PrepareInstallOptions {
     /// Sets whether the dialog should be a modal.
     pub fn modal(mut self, modal: impl Into<Option<bool>>) -> Self {
-        self.modal = modal.into();
+        self.options.modal = modal.into();
         self
     }
 
     /// Sets the launcher type.
     pub fn launcher_type(mut self, launcher_type: LauncherType) -> Self {
-        self.launcher_type = launcher_type;
+        self.options.launcher_type = launcher_type;
         self
     }
 
     /// The URL for a [`LauncherType::WebApplication`] otherwise it is not
     /// needed.
     pub fn target<'a>(mut self, target: impl Into<Option<&'a str>>) -> Self {
-        self.target = target.into().map(ToOwned::to_owned);
+        self.options.target = target.into().map(ToOwned::to_owned);
         self
     }
 
     /// Sets whether the name should be editable.
     pub fn editable_name(mut self, editable_name: impl Into<Option<bool>>) -> Self {
-        self.editable_name = editable_name.into();
+        self.options.editable_name = editable_name.into();
         self
     }
 
     /// Sets whether the icon should be editable.
     pub fn editable_icon(mut self, editable_icon: impl Into<Option<bool>>) -> Self {
-        self.editable_icon = editable_icon.into();
+        self.options.editable_icon = editable_icon.into();
+        self
+    }
+
+    /// Adds a raw option, for options the portal supports but this type
+    /// doesn't expose yet.
+    ///
+    /// **Note** the option is sent as is, without any validation, and
+    /// replaces the typed one with the same key.
+    #[must_use]
+    pub fn extra<'a>(mut self, key: &str, value: impl Into<Value<'a>>) -> Self {
+        self.extra.insert(key.to_owned(), value.into().to_owned());
         self
     }
+
+    /// Checks the target matches the launcher type, done by
+    /// [`DynamicLauncherProxy::prepare_install`] before calling the portal.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortalError::InvalidArgument`] if a
+    /// [`LauncherType::WebApplication`] has no target. A
+    /// [`LauncherType::Application`] with a target only logs a warning, the
+    /// portal ignores it.
+    pub fn validate(&self) -> Result<(), Error> {
+        let has_target = self.options.target.is_some() || self.extra.contains_key("target");
+        match (self.options.launcher_type, has_target) {
+            (LauncherType::WebApplication, false) => Err(PortalError::InvalidArgument(
+                "A web application launcher requires a target URL".to_owned(),
+            )
+            .into()),
+            (LauncherType::Application, true) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("The target is only used by web application launchers");
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Default, SerializeDict, Type)]
+#[zvariant(signature = "dict")]
+/// Options to pass to [`DynamicLauncherProxy::launch_with_options`]
+pub struct LaunchOptions {
+    activation_token: Option<String>,
+}
+
+impl LaunchOptions {
+    /// Sets the token used to activate the launched application, for example
+    /// the one received from [`Activated::activation_token`] or obtained
+    /// from the compositor.
+    ///
+    /// [`Activated::activation_token`]: crate::desktop::global_shortcuts::Activated::activation_token
+    #[must_use]
+    pub fn activation_token<'a>(mut self, activation_token: impl Into<Option<&'a str>>) -> Self {
+        self.activation_token = activation_token.into().map(ToOwned::to_owned);
+        self
+    }
+}
+
+/// The token allowing to install a launcher, returned by
+/// [`DynamicLauncherProxy::prepare_install`] or
+/// [`DynamicLauncherProxy::request_install_token`] and passed to
+/// [`DynamicLauncherProxy::install`].
+///
+/// A token can only be used once, and the portal forgets it after 5 minutes,
+/// so it should not be stored.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(transparent)]
+#[zvariant(signature = "s")]
+pub struct InstallToken(String);
+
+impl InstallToken {
+    /// The token as a string, for example for logging.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for InstallToken {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<String> for InstallToken {
+    fn from(token: String) -> Self {
+        Self(token)
+    }
 }
 
+impl fmt::Display for InstallToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Default, SerializeDict, Type)]
+#[zvariant(signature = "dict")]
+/// Options to pass to [`DynamicLauncherProxy::install_with_options`].
+///
+/// **Note** the portal doesn't accept any option yet.
+pub struct InstallOptions {}
+
 /// The interface lets sandboxed applications install launchers like Web
 /// Application from your browser or Steam.
 ///
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.DynamicLauncher`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.portal.DynamicLauncher).
 #[derive(Debug)]
 #[doc(alias = "org.freedesktop.portal.DynamicLauncher")]
-pub struct DynamicLauncherProxy<'a>(Proxy<'a>);
+pub struct DynamicLauncherProxy<'a>(Proxy<'a>, OnceCell<BitFlags<LauncherType>>);
 
 impl<'a> DynamicLauncherProxy<'a> {
     /// Create a new instance of [`DynamicLauncherProxy`].
     pub async fn new() -> Result<DynamicLauncherProxy<'a>, Error> {
         let proxy = Proxy::new_desktop("org.freedesktop.portal.DynamicLauncher").await?;
-        Ok(Self(proxy))
+        Ok(Self(proxy, OnceCell::new()))
     }
 
+    /// Same as [`DynamicLauncherProxy::new`], failing with
+    /// [`Error::NotSupported`] if the portal doesn't provide
+    /// `org.freedesktop.portal.DynamicLauncher`.
+    pub async fn new_checked() -> Result<DynamicLauncherProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.DynamicLauncher").await?;
+        Ok(Self(proxy, OnceCell::new()))
+    }
+
+    /// Create a new instance of [`DynamicLauncherProxy`] on a session bus
+    /// connection owned by the application, instead of the one shared by the
+    /// proxies created with [`Self::new`].
+    ///
+    /// The requests, e.g. from [`Self::prepare_install`], are created and
+    /// listened to on the same connection, which
+    /// [`PortalProxy::cancel_request`] and [`PortalProxy::close_all`] close
+    /// them through.
+    pub async fn from_connection(
+        connection: &zbus::Connection,
+    ) -> Result<DynamicLauncherProxy<'a>, Error> {
+        let proxy =
+            Proxy::from_connection(connection, "org.freedesktop.portal.DynamicLauncher").await?;
+        Ok(Self(proxy, OnceCell::new()))
+    }
+
+    /// # Errors
+    ///
+    /// The icon is checked with [`Icon::validate_for_launcher`] and the
+    /// options with [`PrepareInstallOptions::validate`] before calling the
+    /// portal.
+    ///
     /// # Specifications
     ///
     /// See also [`PrepareInstall`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-DynamicLauncher.PrepareInstall).
@@ -167,31 +886,154 @@ impl<'a> DynamicLauncherProxy<'a> {
         &self,
         parent_window: &WindowIdentifier,
         name: &str,
-        icon: Icon,
-        options: PrepareInstallOptions,
-    ) -> Result<Request<(String, String)>, Error> {
+        icon: impl Into<Icon>,
+        options: impl Into<PrepareInstallOptions>,
+    ) -> Result<Request<PrepareInstallResponse>, Error> {
+        let mut request = self
+            .send_prepare_install(parent_window, name, icon, options)
+            .await?;
+        request.wait_response().await?;
+        Ok(request)
+    }
+
+    /// Same as [`Self::prepare_install`], returning the request as soon as
+    /// the dialog is shown instead of once the user closed it.
+    ///
+    /// Its response is waited for by awaiting the request, or with
+    /// [`Request::response_with_timeout`] to give up on the dialog after a
+    /// while. [`Request::response`] fails with [`Error::NoResponse`] until
+    /// then.
+    #[doc(alias = "PrepareInstall")]
+    pub async fn send_prepare_install(
+        &self,
+        parent_window: &WindowIdentifier,
+        name: &str,
+        icon: impl Into<Icon>,
+        options: impl Into<PrepareInstallOptions>,
+    ) -> Result<Request<PrepareInstallResponse>, Error> {
+        let icon = icon.into();
+        icon.validate_for_launcher()?;
+        let options = options.into();
+        options.validate()?;
+        let PrepareInstallOptions { options, extra } = options;
         self.0
-            .request(
+            .send_request(
                 &options.handle_token,
                 "PrepareInstall",
-                &(parent_window, name, icon, &options),
+                &(
+                    parent_window,
+                    name,
+                    icon.as_value(),
+                    WithExtra(&options, &extra),
+                ),
             )
             .await
     }
 
+    /// Same as [`Self::prepare_install`], picking the best of several
+    /// versions of the icon as the portal only accepts one.
+    ///
+    /// An SVG icon is preferred, then the largest PNG one, then the largest
+    /// JPEG one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortalError::InvalidArgument`] if `icons` is empty.
+    pub async fn prepare_install_with_icons(
+        &self,
+        parent_window: &WindowIdentifier,
+        name: &str,
+        icons: &[LauncherIcon],
+        options: impl Into<PrepareInstallOptions>,
+    ) -> Result<Request<PrepareInstallResponse>, Error> {
+        let icon = best_icon(icons).ok_or_else(|| {
+            PortalError::InvalidArgument("At least one icon is required".to_owned())
+        })?;
+        self.prepare_install(parent_window, name, icon.icon().clone(), options)
+            .await
+    }
+
+    /// Install a web application launcher in one go, calling
+    /// [`Self::prepare_install`] then [`Self::install`] with a minimal
+    /// desktop entry.
+    ///
+    /// The desktop file ID is made of the application ID, when running in a
+    /// Flatpak, and of the name chosen by the user.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_window` - Identifier for the application window.
+    /// * `name` - The suggested name of the launcher.
+    /// * `icon` - The suggested icon of the launcher.
+    /// * `url` - The URL the launcher opens.
+    /// * `comment` - The comment of the desktop entry.
+    ///
+    /// # Returns
+    ///
+    /// The desktop file ID of the installed launcher.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Response`] if the user cancelled the dialog, see
+    /// [`Error::is_cancelled`], in which case nothing is installed.
+    pub async fn install_web_app(
+        &self,
+        parent_window: &WindowIdentifier,
+        name: &str,
+        icon: impl Into<Icon>,
+        url: &str,
+        comment: Option<&str>,
+    ) -> Result<String, Error> {
+        let options = PrepareInstallOptions::default()
+            .launcher_type(LauncherType::WebApplication)
+            .target(url);
+        let response = self
+            .prepare_install(parent_window, name, icon, options)
+            .await?
+            .response()?;
+        let app_id = crate::sandbox::app_id().await;
+        let desktop_file_id = web_app_desktop_file_id(app_id.as_deref(), response.name());
+        let entry = DesktopEntry::default().comment(comment);
+        self.install(response.token(), &desktop_file_id, &entry)
+            .await?;
+        Ok(desktop_file_id)
+    }
+
+    /// # Errors
+    ///
+    /// The icon is checked with [`Icon::validate_for_launcher`] before
+    /// calling the portal.
+    ///
     /// # Specifications
     ///
     /// See also [`RequestInstallToken`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-DynamicLauncher.RequestInstallToken).
     #[doc(alias = "RequestInstallToken")]
     #[doc(alias = "xdp_portal_dynamic_launcher_request_install_token")]
-    pub async fn request_install_token(&self, name: &str, icon: Icon) -> Result<String, Error> {
+    pub async fn request_install_token(
+        &self,
+        name: &str,
+        icon: impl Into<Icon>,
+    ) -> Result<InstallToken, Error> {
+        let icon = icon.into();
+        icon.validate_for_launcher()?;
         // No supported options for now
         let options: HashMap<&str, zvariant::Value<'_>> = HashMap::new();
         self.0
-            .call::<String>("RequestInstallToken", &(name, icon, options))
+            .call::<InstallToken>("RequestInstallToken", &(name, icon.as_value(), options))
             .await
     }
 
+    /// The portal rewrites the `Exec` key of the desktop entry to launch the
+    /// sandboxed application, see [`Self::will_override_exec`].
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token returned by [`Self::prepare_install`] or
+    ///   [`Self::request_install_token`].
+    /// * `desktop_file_id` - The `.desktop` file name.
+    /// * `desktop_entry` - The desktop entry, either as a string or a
+    ///   [`DesktopEntry`].
+    ///
     /// # Specifications
     ///
     /// See also [`Install`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-DynamicLauncher.Install).
@@ -199,17 +1041,115 @@ impl<'a> DynamicLauncherProxy<'a> {
     #[doc(alias = "xdp_portal_dynamic_launcher_install")]
     pub async fn install(
         &self,
-        token: &str,
+        token: &InstallToken,
         desktop_file_id: &str,
-        desktop_entry: &str,
+        desktop_entry: impl Into<String>,
     ) -> Result<(), Error> {
-        // No supported options for now
-        let options: HashMap<&str, zvariant::Value<'_>> = HashMap::new();
+        self.install_with_options(
+            token,
+            desktop_file_id,
+            desktop_entry,
+            InstallOptions::default(),
+        )
+        .await
+    }
+
+    /// Same as [`Self::install`], with [`InstallOptions`].
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token returned by [`Self::prepare_install`] or
+    ///   [`Self::request_install_token`].
+    /// * `desktop_file_id` - The `.desktop` file name.
+    /// * `desktop_entry` - The desktop entry, either as a string or a
+    ///   [`DesktopEntry`].
+    /// * `options` - The [`InstallOptions`].
+    #[doc(alias = "Install")]
+    pub async fn install_with_options(
+        &self,
+        token: &InstallToken,
+        desktop_file_id: &str,
+        desktop_entry: impl Into<String>,
+        options: InstallOptions,
+    ) -> Result<(), Error> {
+        let desktop_entry = desktop_entry.into();
         self.0
-            .call::<()>("Install", &(token, desktop_file_id, desktop_entry, options))
+            .call::<()>(
+                "Install",
+                &(token, desktop_file_id, desktop_entry, &options),
+            )
             .await
     }
 
+    /// Install a [`DesktopEntry`], see [`Self::install`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortalError::InvalidArgument`] if a key added with
+    /// [`DesktopEntry::raw_field`] is invalid.
+    pub async fn install_entry(
+        &self,
+        token: &InstallToken,
+        desktop_file_id: &str,
+        entry: &DesktopEntry,
+    ) -> Result<(), Error> {
+        entry.validate()?;
+        self.install(token, desktop_file_id, entry).await
+    }
+
+    /// Validate the arguments of [`Self::install`] without installing
+    /// anything.
+    ///
+    /// The desktop file ID has to be a `.desktop` file name, prefixed by the
+    /// application ID when it is known, and the desktop entry has to be a
+    /// valid key file starting with a `[Desktop Entry]` group. The portal is
+    /// then probed to check it provides the interface.
+    ///
+    /// **Note** the token is only checked not to be empty, as the portal
+    /// consumes it when validating it. The icon is associated with the token
+    /// and is decoded by [`Self::prepare_install`] or
+    /// [`Self::request_install_token`] already.
+    ///
+    /// # Arguments
+    ///
+    /// See [`Self::install`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortalError::InvalidArgument`] describing the first invalid
+    /// argument, or an [`Error::NotSupported`] if the running portal doesn't
+    /// provide the interface.
+    pub async fn install_dry_run(
+        &self,
+        token: &InstallToken,
+        desktop_file_id: &str,
+        desktop_entry: impl Into<String>,
+    ) -> Result<(), Error> {
+        if token.is_empty() {
+            return Err(PortalError::InvalidArgument("The token is empty".to_owned()).into());
+        }
+        let app_id = crate::sandbox::app_id().await;
+        validate_desktop_file_id(desktop_file_id, app_id.as_deref())?;
+        validate_desktop_entry(&desktop_entry.into())?;
+        if !self.0.is_exported().await? {
+            return Err(Error::NotSupported {
+                interface: self.0.interface().to_string(),
+                member: String::new(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether [`Self::install`] will override the `Exec` key of the desktop
+    /// entry, with `flatpak run` or `snap run`, which is the case when the
+    /// application is running inside a Flatpak or a Snap.
+    ///
+    /// **Note** [`DesktopEntry`] omits the `Exec` key unless it is set with
+    /// [`DesktopEntry::exec`].
+    pub async fn will_override_exec(&self) -> bool {
+        crate::sandbox::kind().await != crate::sandbox::Sandbox::None
+    }
+
     /// # Specifications
     ///
     /// See also [`Uninstall`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-DynamicLauncher.Uninstall).
@@ -223,6 +1163,36 @@ impl<'a> DynamicLauncherProxy<'a> {
             .await
     }
 
+    /// Uninstall several launchers, see [`Self::uninstall`].
+    ///
+    /// Every launcher is attempted even if some fail. The ones that are not
+    /// installed, or already uninstalled, are not considered failures.
+    ///
+    /// # Errors
+    ///
+    /// Returns the desktop file IDs that couldn't be uninstalled, along with
+    /// their errors.
+    #[doc(alias = "Uninstall")]
+    pub async fn uninstall_many(
+        &self,
+        desktop_file_ids: &[&str],
+    ) -> Result<(), Vec<(String, Error)>> {
+        let mut errors = Vec::new();
+        for desktop_file_id in desktop_file_ids {
+            match self.uninstall(desktop_file_id).await {
+                Err(err) if !is_not_installed(&err) => {
+                    errors.push(((*desktop_file_id).to_owned(), err));
+                }
+                _ => (),
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     /// # Specifications
     ///
     /// See also [`GetDesktopEntry`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-DynamicLauncher.GetDesktopEntry).
@@ -232,6 +1202,12 @@ impl<'a> DynamicLauncherProxy<'a> {
         self.0.call("GetDesktopEntry", &(desktop_file_id)).await
     }
 
+    /// Same as [`Self::desktop_entry`], parsed with [`DesktopEntry::parse`].
+    #[doc(alias = "GetDesktopEntry")]
+    pub async fn parsed_desktop_entry(&self, desktop_file_id: &str) -> Result<DesktopEntry, Error> {
+        DesktopEntry::parse(&self.desktop_entry(desktop_file_id).await?)
+    }
+
     /// # Specifications
     ///
     /// See also [`GetIcon`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-DynamicLauncher.GetIcon).
@@ -241,17 +1217,147 @@ impl<'a> DynamicLauncherProxy<'a> {
         self.0.call("GetIcon", &(desktop_file_id)).await
     }
 
+    /// Same as [`Self::desktop_entry`], retried on transient errors.
+    #[doc(alias = "GetDesktopEntry")]
+    pub async fn desktop_entry_with_retry(
+        &self,
+        desktop_file_id: &str,
+        policy: RetryPolicy,
+    ) -> Result<String, Error> {
+        self.0
+            .call_with_retry("GetDesktopEntry", &(desktop_file_id), policy)
+            .await
+    }
+
+    /// Same as [`Self::icon`], retried on transient errors.
+    #[doc(alias = "GetIcon")]
+    pub async fn icon_with_retry(
+        &self,
+        desktop_file_id: &str,
+        policy: RetryPolicy,
+    ) -> Result<LauncherIcon, Error> {
+        self.0
+            .call_with_retry("GetIcon", &(desktop_file_id), policy)
+            .await
+    }
+
+    /// Retrieve the desktop entry and the icon of each of the installed
+    /// launchers, with up to [`DEFAULT_CONCURRENCY`] of them being
+    /// retrieved at the same time.
+    ///
+    /// The portal can't list the installed launchers, applications have to
+    /// keep track of their IDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `desktop_file_ids` - The `.desktop` file names.
+    ///
+    /// # Returns
+    ///
+    /// The desktop file ID, desktop entry and icon of each launcher, in the
+    /// same order. The launchers that couldn't be retrieved, for example
+    /// because they were uninstalled, are skipped.
+    pub async fn entries(&self, desktop_file_ids: &[&str]) -> Vec<(String, String, LauncherIcon)> {
+        let entries = desktop_file_ids.iter().map(|id| async move {
+            let (entry, icon) =
+                futures_util::try_join!(self.desktop_entry(id), self.icon(id)).ok()?;
+            Some((id.to_string(), entry, icon))
+        });
+        join_all_limited(entries, DEFAULT_CONCURRENCY)
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
     /// # Specifications
     ///
     /// See also [`Launch`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-DynamicLauncher.Launch).
     #[doc(alias = "Launch")]
     #[doc(alias = "xdp_portal_dynamic_launcher_launch")]
     pub async fn launch(&self, desktop_file_id: &str) -> Result<(), Error> {
-        // TODO: handle activation_token
-        let options: HashMap<&str, zvariant::Value<'_>> = HashMap::new();
+        self.launch_with_options(desktop_file_id, LaunchOptions::default())
+            .await
+    }
+
+    /// Launch the installed desktop entry, passing it an activation token so
+    /// that the application window can be focused on Wayland.
+    ///
+    /// # Arguments
+    ///
+    /// * `desktop_file_id` - The `.desktop` file name.
+    /// * `options` - The [`LaunchOptions`].
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Launch`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-DynamicLauncher.Launch).
+    #[doc(alias = "Launch")]
+    pub async fn launch_with_options(
+        &self,
+        desktop_file_id: &str,
+        options: LaunchOptions,
+    ) -> Result<(), Error> {
         self.0.call("Launch", &(desktop_file_id, &options)).await
     }
 
+    /// Launch the installed desktop entry and wait for the application to
+    /// start.
+    ///
+    /// The portal doesn't report whether the application started, so this
+    /// waits for the D-Bus name matching the desktop file ID, without the
+    /// `.desktop` suffix, to be owned. This is best effort: it only works
+    /// for D-Bus activatable applications, or the ones owning such a name
+    /// once started.
+    ///
+    /// # Arguments
+    ///
+    /// * `desktop_file_id` - The `.desktop` file name.
+    /// * `timeout` - How long to wait for the application.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::IO`] error of kind
+    /// [`std::io::ErrorKind::TimedOut`] if the name wasn't owned in time, in
+    /// which case the application might still have started.
+    pub async fn launch_and_wait(
+        &self,
+        desktop_file_id: &str,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        let name = desktop_file_id.trim_end_matches(".desktop");
+        let name = WellKnownName::try_from(name)
+            .map_err(|_| Error::ParseError("Failed to parse desktop file id, invalid bus name"))?;
+        let dbus = zbus::fdo::DBusProxy::new(self.0.connection()).await?;
+        // Listen before launching to not miss the application starting
+        let mut owner_changed = dbus
+            .receive_name_owner_changed_with_args(&[(0, name.as_str())])
+            .await?;
+        self.launch(desktop_file_id).await?;
+        if dbus.name_has_owner(name.as_ref().into()).await? {
+            return Ok(());
+        }
+        with_timeout(Some(timeout), async {
+            while let Some(signal) = owner_changed.next().await {
+                if signal.args()?.new_owner().is_some() {
+                    return Ok(());
+                }
+            }
+            Err(Error::NoResponse)
+        })
+        .await
+    }
+
+    /// The version of the interface provided by the running portal.
+    ///
+    /// All the methods are part of the first version of the interface.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`version`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-property-org-freedesktop-portal-DynamicLauncher.version).
+    pub async fn version(&self) -> Result<u32, Error> {
+        self.0.version().await
+    }
+
     /// # Specifications
     ///
     /// See also [`SupportedLauncherTypes`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-property-org-freedesktop-portal-DynamicLauncher.SupportedLauncherTypes).
@@ -261,6 +1367,137 @@ impl<'a> DynamicLauncherProxy<'a> {
             .property::<BitFlags<LauncherType>>("SupportedLauncherTypes")
             .await
     }
+
+    /// Same as [`Self::supported_launcher_types`], retried on transient
+    /// errors.
+    #[doc(alias = "SupportedLauncherTypes")]
+    pub async fn supported_launcher_types_with_retry(
+        &self,
+        policy: RetryPolicy,
+    ) -> Result<BitFlags<LauncherType>, Error> {
+        retry(policy, || self.supported_launcher_types()).await
+    }
+
+    /// Same as [`Self::supported_launcher_types`], but the value is only
+    /// retrieved once for the lifetime of the proxy, as it doesn't change at
+    /// runtime.
+    ///
+    /// Errors are not cached, it is retrieved again on the next call.
+    pub async fn supported_launcher_types_cached(&self) -> Result<BitFlags<LauncherType>, Error> {
+        if let Some(types) = self.1.get() {
+            return Ok(*types);
+        }
+        let types = self.supported_launcher_types().await?;
+        Ok(*self.1.get_or_init(|| types))
+    }
+
+    /// Whether the portal supports installing launchers of the given type,
+    /// for example before offering to install a web application.
+    ///
+    /// See [`Self::supported_launcher_types_cached`].
+    pub async fn supports(&self, launcher_type: LauncherType) -> Result<bool, Error> {
+        Ok(self
+            .supported_launcher_types_cached()
+            .await?
+            .contains(launcher_type))
+    }
+}
+
+impl AsProxy for DynamicLauncherProxy<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for DynamicLauncherProxy<'_> {}
+
+/// The icon with the best quality, see
+/// [`DynamicLauncherProxy::prepare_install_with_icons`].
+fn best_icon(icons: &[LauncherIcon]) -> Option<&LauncherIcon> {
+    icons.iter().max_by_key(|icon| {
+        let rank = match icon.type_() {
+            IconType::Svg => 2,
+            IconType::Png => 1,
+            IconType::Jpeg => 0,
+        };
+        (rank, icon.size())
+    })
+}
+
+/// Checks the desktop file ID the same way the portal does.
+fn validate_desktop_file_id(
+    desktop_file_id: &str,
+    app_id: Option<&str>,
+) -> Result<(), PortalError> {
+    let invalid = |reason: &str| {
+        PortalError::InvalidArgument(format!(
+            "Invalid desktop file id '{desktop_file_id}': {reason}"
+        ))
+    };
+    if !desktop_file_id.ends_with(".desktop") {
+        return Err(invalid("missing the .desktop suffix"));
+    }
+    if desktop_file_id.contains('/') {
+        return Err(invalid("not a file name"));
+    }
+    if let Some(app_id) = app_id {
+        if !desktop_file_id.starts_with(&format!("{app_id}.")) {
+            return Err(invalid(&format!(
+                "not prefixed by the application id {app_id}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The desktop file ID of a web application named `name`, prefixed by the
+/// application ID when it is known.
+fn web_app_desktop_file_id(app_id: Option<&str>, name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    let name = if name.is_empty() { "WebApp" } else { &name };
+    match app_id {
+        Some(app_id) => format!("{app_id}.{name}.desktop"),
+        None => format!("{name}.desktop"),
+    }
+}
+
+/// Checks the desktop entry is a key file with a `[Desktop Entry]` group.
+fn validate_desktop_entry(desktop_entry: &str) -> Result<(), PortalError> {
+    let mut first_group = None;
+    for (number, line) in desktop_entry.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(group) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            first_group.get_or_insert(group);
+        } else if first_group.is_none() {
+            return Err(PortalError::InvalidArgument(format!(
+                "Invalid desktop entry: line {} is outside of a group",
+                number + 1
+            )));
+        } else if !matches!(line.split_once('='), Some((key, _)) if !key.trim().is_empty()) {
+            return Err(PortalError::InvalidArgument(format!(
+                "Invalid desktop entry: line {} is not a key=value pair",
+                number + 1
+            )));
+        }
+    }
+    match first_group {
+        Some("Desktop Entry") => Ok(()),
+        _ => Err(PortalError::InvalidArgument(
+            "Invalid desktop entry: it doesn't start with a [Desktop Entry] group".to_owned(),
+        )),
+    }
 }
 
 #[cfg(test)]
@@ -275,4 +1512,741 @@ mod test {
         let icon = vec![IconType::Png];
         assert_eq!(serde_json::to_string(&icon).unwrap(), "[\"png\"]");
     }
+
+    #[test]
+    fn test_icon_argument() {
+        let ctxt = zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        let icon = Icon::from_bytes(vec![1, 2, 3]);
+        let options: HashMap<&str, zvariant::Value<'_>> = HashMap::new();
+        let body = ("My App", icon.as_value(), options);
+        assert_eq!(
+            zvariant::Value::from(body.clone()).value_signature(),
+            "(sva{sv})"
+        );
+
+        let encoded = zvariant::to_bytes(ctxt, &body).unwrap();
+        let (_, decoded, _): (String, OwnedValue, HashMap<String, OwnedValue>) =
+            zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(Icon::try_from(decoded).unwrap(), icon);
+
+        let encoded = zvariant::to_bytes(ctxt, &(icon.as_value(), IconType::Png, 64u32)).unwrap();
+        let decoded: LauncherIcon = zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(decoded.icon(), &icon);
+        assert_eq!(decoded.size(), 64);
+    }
+
+    #[test]
+    fn test_icon_bytes() {
+        let png = vec![0x89, b'P', b'N', b'G'];
+        let icon = LauncherIcon(Icon::from_bytes(png.clone()), IconType::Png, 64);
+        assert_eq!(icon.bytes(), Some(png.as_slice()));
+
+        let path = std::env::temp_dir().join(format!("ashpd-launcher-icon-{}", std::process::id()));
+        icon.save_to(&path).unwrap();
+        let saved = path.with_extension("png");
+        assert_eq!(fs::read(&saved).unwrap(), png);
+        fs::remove_file(saved).unwrap();
+
+        let icon = LauncherIcon(Icon::with_names(["app"]), IconType::Svg, 64);
+        assert_eq!(icon.bytes(), None);
+        assert_eq!(
+            icon.save_to(&path).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_best_icon() {
+        let icon = |type_, size| LauncherIcon::new(vec![size as u8], type_, size);
+        assert_eq!(best_icon(&[]), None);
+
+        let icons = [
+            icon(IconType::Jpeg, 512),
+            icon(IconType::Png, 64),
+            icon(IconType::Png, 256),
+            icon(IconType::Png, 128),
+        ];
+        assert_eq!(best_icon(&icons), Some(&icons[2]));
+
+        let icons = [
+            icon(IconType::Png, 512),
+            icon(IconType::Svg, 64),
+            icon(IconType::Jpeg, 1024),
+        ];
+        assert_eq!(best_icon(&icons), Some(&icons[1]));
+
+        let icons = [icon(IconType::Jpeg, 32), icon(IconType::Jpeg, 48)];
+        assert_eq!(best_icon(&icons), Some(&icons[1]));
+    }
+
+    #[test]
+    fn test_icon_as_mime() {
+        for type_ in IconType::ALL {
+            assert_eq!(IconType::from_mime(type_.as_mime()), Some(type_));
+            assert_eq!(
+                serde_json::to_string(&type_).unwrap(),
+                format!("\"{}\"", type_.name())
+            );
+        }
+        assert_eq!(IconType::Png.as_mime(), "image/png");
+        assert_eq!(IconType::Jpeg.as_mime(), "image/jpeg");
+        assert_eq!(IconType::Svg.as_mime(), "image/svg+xml");
+        assert_eq!(IconType::from_mime("image/gif"), None);
+    }
+
+    #[test]
+    fn test_icon_from_value() {
+        let ctxt = zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        let icon = Icon::with_names(["web-browser"]);
+        for (type_, name) in [
+            (IconType::Png, "png"),
+            (IconType::Jpeg, "jpeg"),
+            (IconType::Svg, "svg"),
+        ] {
+            let body = (icon.as_value(), name, 64u32);
+            let value = OwnedValue::from(Value::from(body.clone()));
+            assert_eq!(value.value_signature(), LauncherIcon::signature());
+            let expected = LauncherIcon::new(icon.clone(), type_, 64);
+            assert_eq!(LauncherIcon::try_from(value).unwrap(), expected);
+
+            let encoded = zvariant::to_bytes(ctxt, &body).unwrap();
+            let decoded: LauncherIcon = zvariant::from_slice(&encoded, ctxt).unwrap();
+            assert_eq!(decoded, expected);
+        }
+
+        let value = OwnedValue::from(Value::from((icon.as_value(), "gif", 64u32)));
+        assert!(LauncherIcon::try_from(value).is_err());
+        let value = OwnedValue::from(Value::from(("png", icon.as_value(), 64u32)));
+        assert!(LauncherIcon::try_from(value).is_err());
+    }
+
+    #[test]
+    fn test_icon_type_guess() {
+        assert_eq!(IconType::from_extension("png"), Some(IconType::Png));
+        assert_eq!(IconType::from_extension("JPG"), Some(IconType::Jpeg));
+        assert_eq!(IconType::from_extension("jpeg"), Some(IconType::Jpeg));
+        assert_eq!(IconType::from_extension("svg"), Some(IconType::Svg));
+        assert_eq!(IconType::from_extension("gif"), None);
+
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        assert_eq!(IconType::guess_from_bytes(png), Some(IconType::Png));
+        let jpeg = b"\xFF\xD8\xFF\xE0\0\x10JFIF\0";
+        assert_eq!(IconType::guess_from_bytes(jpeg), Some(IconType::Jpeg));
+        let svg = b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>";
+        assert_eq!(IconType::guess_from_bytes(svg), Some(IconType::Svg));
+        let xml = b"\xEF\xBB\xBF\n  <?xml version=\"1.0\"?><svg/>";
+        assert_eq!(IconType::guess_from_bytes(xml), Some(IconType::Svg));
+
+        assert_eq!(IconType::guess_from_bytes(b"GIF89a"), None);
+        assert_eq!(IconType::guess_from_bytes(b"  \n"), None);
+        assert_eq!(IconType::guess_from_bytes(b""), None);
+    }
+
+    #[test]
+    fn test_desktop_entry() {
+        assert_eq!(
+            DesktopEntry::default().to_string(),
+            "[Desktop Entry]\nType=Application\n"
+        );
+
+        let entry = DesktopEntry::default()
+            .categories(["Network", "WebBrowser"])
+            .url("https://example.org")
+            .comment("My Web App")
+            .entry_type(EntryType::Link);
+        assert_eq!(
+            String::from(&entry),
+            "[Desktop Entry]\nType=Link\nComment=My Web App\nURL=https://example.org\nCategories=Network;WebBrowser;\n"
+        );
+
+        // URL is only meaningful for links
+        let entry = DesktopEntry::default().url("https://example.org");
+        assert_eq!(entry.to_string(), "[Desktop Entry]\nType=Application\n");
+
+        let entry = DesktopEntry::default()
+            .comment("First line\nSecond; line")
+            .categories(["A;B"]);
+        assert_eq!(
+            entry.to_string(),
+            "[Desktop Entry]\nType=Application\nComment=First line\\nSecond; line\nCategories=A\\;B;\n"
+        );
+    }
+
+    /// Parses the keys of the `[Desktop Entry]` group, unescaping the values.
+    fn parse_desktop_entry(content: &str) -> HashMap<String, String> {
+        let mut lines = content.lines();
+        assert_eq!(lines.next(), Some("[Desktop Entry]"));
+        lines
+            .map(|line| {
+                let (key, value) = line.split_once('=').unwrap();
+                let mut unescaped = String::new();
+                let mut chars = value.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => match chars.next().unwrap() {
+                            'n' => unescaped.push('\n'),
+                            't' => unescaped.push('\t'),
+                            'r' => unescaped.push('\r'),
+                            // Keeps `\;` escaped, as it is only meaningful in lists
+                            ';' => unescaped.push_str("\\;"),
+                            c => unescaped.push(c),
+                        },
+                        c => unescaped.push(c),
+                    }
+                }
+                (key.to_owned(), unescaped)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_desktop_entry_round_trip() {
+        let entry = DesktopEntry::default()
+            .comment("Line\nwith = and \\")
+            .exec("my-app --url=%u")
+            .categories(["Network"])
+            .keywords(["web", "a;b"])
+            .raw_field("StartupWMClass", "my-app")
+            .raw_field("Comment[fr]", "Mon application");
+        assert!(entry.validate().is_ok());
+        let content = entry.to_desktop_string();
+        assert!(validate_desktop_entry(&content).is_ok());
+
+        let keys = parse_desktop_entry(&content);
+        assert_eq!(keys["Type"], "Application");
+        assert_eq!(keys["Comment"], "Line\nwith = and \\");
+        assert_eq!(keys["Exec"], "my-app --url=%u");
+        assert_eq!(keys["Categories"], "Network;");
+        assert_eq!(keys["Keywords"], "web;a\\;b;");
+        assert_eq!(keys["StartupWMClass"], "my-app");
+        assert_eq!(keys["Comment[fr]"], "Mon application");
+        assert!(!keys.contains_key("Name"));
+        assert!(!keys.contains_key("Icon"));
+
+        for key in [
+            "",
+            "Bad=Key",
+            "Bad Key",
+            "Line\nBreak",
+            "Comment[fr",
+            "Comment[]",
+        ] {
+            assert!(DesktopEntry::default()
+                .raw_field(key, "value")
+                .validate()
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_prepare_install_validation() {
+        assert!(PrepareInstallOptions::default().validate().is_ok());
+        let web = PrepareInstallOptions::default().launcher_type(LauncherType::WebApplication);
+        assert!(matches!(
+            web.validate(),
+            Err(Error::Portal(PortalError::InvalidArgument(_)))
+        ));
+        assert!(web.target("https://example.org").validate().is_ok());
+        let web = PrepareInstallOptions::default().launcher_type(LauncherType::WebApplication);
+        assert!(web
+            .extra("target", "https://example.org")
+            .validate()
+            .is_ok());
+
+        assert!(PrepareInstallOptions::default()
+            .target("https://example.org")
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_desktop_entry_parse() {
+        let content = "# Installed by the portal\n\
+            [Desktop Entry]\n\
+            Type=Link\n\
+            Name=My App\n\
+            Name[de]=Meine App\n\
+            Name[pt_BR]=Meu App\n\
+            Name[sr@latin]=Moja App\n\
+            Comment = Line\\nTab\\tSlash\\\\Space\\send\n\
+            URL=https://example.org\n\
+            Exec=flatpak run org.example.App --url=%u\n\
+            Categories=Network;A\\;B;\n\
+            Keywords=web\n\
+            Icon=/path/to/icon.png\n\
+            \n\
+            [Desktop Action New]\n\
+            Name=New Window\n";
+        let entry = DesktopEntry::parse(content).unwrap();
+        assert_eq!(entry.entry_type, EntryType::Link);
+        assert_eq!(entry.get("Type"), Some("Link"));
+        assert_eq!(entry.name(), Some("My App"));
+        assert_eq!(entry.get("Comment"), Some("Line\nTab\tSlash\\Space end"));
+        assert_eq!(entry.get("URL"), Some("https://example.org"));
+        assert_eq!(
+            entry.get("Exec"),
+            Some("flatpak run org.example.App --url=%u")
+        );
+        assert_eq!(entry.get("Icon"), Some("/path/to/icon.png"));
+        assert_eq!(entry.get("Categories"), None);
+        assert_eq!(entry.categories, ["Network", "A;B"]);
+        assert_eq!(entry.keywords, ["web"]);
+
+        assert_eq!(entry.localized("Name", "de"), Some("Meine App"));
+        assert_eq!(entry.localized("Name", "de_AT.UTF-8"), Some("Meine App"));
+        assert_eq!(entry.localized("Name", "pt_BR"), Some("Meu App"));
+        assert_eq!(entry.localized("Name", "pt"), Some("My App"));
+        assert_eq!(entry.localized("Name", "sr_RS@latin"), Some("Moja App"));
+        assert_eq!(entry.localized("Name", "fr_FR"), Some("My App"));
+        assert_eq!(entry.localized("GenericName", "fr"), None);
+
+        // The written entry is read back the same
+        let parsed = DesktopEntry::parse(&entry.to_desktop_string()).unwrap();
+        assert_eq!(parsed, entry);
+
+        assert!(DesktopEntry::parse("[Desktop Entry]\nType=Directory\n").is_err());
+        assert!(DesktopEntry::parse("Name=My App\n").is_err());
+    }
+
+    #[test]
+    fn test_launch_options() {
+        let ctxt = zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        let encode = |options: LaunchOptions| {
+            let encoded = zvariant::to_bytes(ctxt, &options).unwrap();
+            zvariant::from_slice::<_, HashMap<String, OwnedValue>>(&encoded, ctxt).unwrap()
+        };
+
+        assert!(encode(LaunchOptions::default()).is_empty());
+        let options = encode(LaunchOptions::default().activation_token("token"));
+        assert_eq!(options.len(), 1);
+        assert_eq!(
+            String::try_from(options["activation_token"].clone()).unwrap(),
+            "token"
+        );
+        assert!(encode(LaunchOptions::default().activation_token(None)).is_empty());
+    }
+
+    #[test]
+    fn test_prepare_install_response() {
+        assert_eq!(PrepareInstallResponse::signature(), "a{sv}");
+        let ctxt = zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        let icon = Icon::from_bytes(vec![0x89, b'P', b'N', b'G']);
+        let results = HashMap::from([
+            ("name", Value::from("Renamed App")),
+            ("token", Value::from("some-token")),
+            ("icon", icon.as_value()),
+        ]);
+        let encoded = zvariant::to_bytes(ctxt, &results).unwrap();
+        let response: PrepareInstallResponse = zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(response.name(), "Renamed App");
+        assert_eq!(response.token().as_str(), "some-token");
+        assert_eq!(response.icon(), Some(&icon));
+        assert_eq!(
+            <(String, String)>::from(response),
+            ("Renamed App".to_owned(), "some-token".to_owned())
+        );
+
+        // The icon is optional
+        let results = HashMap::from([
+            ("name", Value::from("My App")),
+            ("token", Value::from("some-token")),
+        ]);
+        let encoded = zvariant::to_bytes(ctxt, &results).unwrap();
+        let response: PrepareInstallResponse = zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(response.name(), "My App");
+        assert!(response.icon().is_none());
+    }
+
+    #[test]
+    fn test_install_token() {
+        assert_eq!(InstallToken::signature(), "s");
+        let token = InstallToken::from("some-token".to_owned());
+        assert_eq!(token.as_str(), "some-token");
+        assert_eq!(token.to_string(), "some-token");
+        // Derefs to a str
+        let token_str: &str = &token;
+        assert_eq!(token_str, "some-token");
+        assert!(token.starts_with("some"));
+
+        // Sent as a plain string
+        let ctxt = zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        let encoded = zvariant::to_bytes(ctxt, &token).unwrap();
+        assert_eq!(encoded, zvariant::to_bytes(ctxt, "some-token").unwrap());
+        let decoded: InstallToken = zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(decoded, token);
+    }
+
+    #[test]
+    fn test_install_options() {
+        assert_eq!(InstallOptions::signature(), "a{sv}");
+        let ctxt = zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        // Same as the empty dict that used to be sent
+        let empty: HashMap<&str, zvariant::Value<'_>> = HashMap::new();
+        assert_eq!(
+            zvariant::to_bytes(ctxt, &InstallOptions::default()).unwrap(),
+            zvariant::to_bytes(ctxt, &empty).unwrap()
+        );
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn test_from_connection() {
+        use zbus::{
+            dbus_interface,
+            zvariant::{OwnedObjectPath, Value},
+            MessageHeader,
+        };
+
+        use crate::proxy::DESKTOP_PATH;
+
+        struct MockDynamicLauncher;
+
+        #[dbus_interface(name = "org.freedesktop.portal.DynamicLauncher")]
+        impl MockDynamicLauncher {
+            #[dbus_interface(property)]
+            fn supported_launcher_types(&self) -> u32 {
+                1
+            }
+
+            async fn prepare_install(
+                &self,
+                #[zbus(header)] header: MessageHeader<'_>,
+                #[zbus(connection)] connection: &zbus::Connection,
+                _parent_window: &str,
+                name: &str,
+                _icon: OwnedValue,
+                options: HashMap<String, OwnedValue>,
+            ) -> zbus::fdo::Result<OwnedObjectPath> {
+                let sender = header.sender().ok().flatten().unwrap();
+                let token = String::try_from(options["handle_token"].clone()).unwrap();
+                let path = format!(
+                    "/org/freedesktop/portal/desktop/request/{}/{token}",
+                    sender.trim_start_matches(':').replace('.', "_")
+                );
+                let results = HashMap::from([
+                    ("name", Value::from(name)),
+                    ("token", Value::from(format!("{name}-token"))),
+                ]);
+                connection
+                    .emit_signal(
+                        None::<()>,
+                        path.as_str(),
+                        "org.freedesktop.portal.Request",
+                        "Response",
+                        &(0u32, results),
+                    )
+                    .await?;
+                Ok(OwnedObjectPath::try_from(path).unwrap())
+            }
+        }
+
+        async_std::task::block_on(async {
+            let Some(server) = crate::proxy::mock_desktop().await else {
+                return;
+            };
+            server
+                .object_server()
+                .at(DESKTOP_PATH, MockDynamicLauncher)
+                .await
+                .unwrap();
+
+            let connection = zbus::Connection::session().await.unwrap();
+            let first = DynamicLauncherProxy::from_connection(&connection)
+                .await
+                .unwrap();
+            let second = DynamicLauncherProxy::from_connection(&connection)
+                .await
+                .unwrap();
+            let window = WindowIdentifier::default();
+            for (proxy, name) in [(&first, "First"), (&second, "Second")] {
+                assert!(proxy.supports(LauncherType::Application).await.unwrap());
+                // The same request as `prepare_install`, with the raw results
+                let token = HandleToken::default();
+                let options = HashMap::from([("handle_token", Value::from(token.to_string()))]);
+                let icon = Icon::Bytes(vec![0]);
+                let results = proxy
+                    .0
+                    .request::<HashMap<String, OwnedValue>>(
+                        &token,
+                        "PrepareInstall",
+                        &(&window, name, icon.as_value(), options),
+                    )
+                    .await
+                    .unwrap()
+                    .response()
+                    .unwrap();
+                assert_eq!(
+                    String::try_from(results["token"].clone()).unwrap(),
+                    format!("{name}-token")
+                );
+            }
+        });
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn test_install_web_app() {
+        use std::sync::{Arc, Mutex};
+
+        use zbus::{
+            dbus_interface,
+            zvariant::{OwnedObjectPath, Value},
+            MessageHeader,
+        };
+
+        use crate::proxy::DESKTOP_PATH;
+
+        struct MockDynamicLauncher(Arc<Mutex<Vec<String>>>);
+
+        #[dbus_interface(name = "org.freedesktop.portal.DynamicLauncher")]
+        impl MockDynamicLauncher {
+            async fn prepare_install(
+                &self,
+                #[zbus(header)] header: MessageHeader<'_>,
+                #[zbus(connection)] connection: &zbus::Connection,
+                _parent_window: &str,
+                name: &str,
+                _icon: OwnedValue,
+                options: HashMap<String, OwnedValue>,
+            ) -> zbus::fdo::Result<OwnedObjectPath> {
+                let target = String::try_from(options["target"].clone()).unwrap();
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("PrepareInstall {name} {target}"));
+                let sender = header.sender().ok().flatten().unwrap();
+                let token = String::try_from(options["handle_token"].clone()).unwrap();
+                let path = format!(
+                    "/org/freedesktop/portal/desktop/request/{}/{token}",
+                    sender.trim_start_matches(':').replace('.', "_")
+                );
+                // The user renames the launcher, or cancels the dialog
+                let (code, results) = if name == "Cancelled" {
+                    (1u32, HashMap::new())
+                } else {
+                    let results = HashMap::from([
+                        ("name", Value::from("My Web App")),
+                        ("token", Value::from("install-token")),
+                    ]);
+                    (0, results)
+                };
+                connection
+                    .emit_signal(
+                        None::<()>,
+                        path.as_str(),
+                        "org.freedesktop.portal.Request",
+                        "Response",
+                        &(code, results),
+                    )
+                    .await?;
+                Ok(OwnedObjectPath::try_from(path).unwrap())
+            }
+
+            fn install(
+                &self,
+                token: &str,
+                desktop_file_id: &str,
+                desktop_entry: &str,
+                _options: HashMap<String, OwnedValue>,
+            ) {
+                assert!(desktop_entry.contains("Comment=A web app\n"));
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("Install {token} {desktop_file_id}"));
+            }
+        }
+
+        async_std::task::block_on(async {
+            let Some(server) = crate::proxy::mock_desktop().await else {
+                return;
+            };
+            let calls = Arc::new(Mutex::new(Vec::new()));
+            server
+                .object_server()
+                .at(DESKTOP_PATH, MockDynamicLauncher(calls.clone()))
+                .await
+                .unwrap();
+
+            let proxy = DynamicLauncherProxy::new().await.unwrap();
+            let window = WindowIdentifier::default();
+            let icon = Icon::with_names(["web-browser"]);
+            let desktop_file_id = proxy
+                .install_web_app(
+                    &window,
+                    "Web App",
+                    icon.clone(),
+                    "https://example.org",
+                    Some("A web app"),
+                )
+                .await
+                .unwrap();
+            assert_eq!(desktop_file_id, "My_Web_App.desktop");
+            assert_eq!(
+                std::mem::take(&mut *calls.lock().unwrap()),
+                [
+                    "PrepareInstall Web App https://example.org",
+                    "Install install-token My_Web_App.desktop"
+                ]
+            );
+
+            let err = proxy
+                .install_web_app(&window, "Cancelled", icon, "https://example.org", None)
+                .await
+                .unwrap_err();
+            assert!(err.is_cancelled());
+            assert_eq!(
+                *calls.lock().unwrap(),
+                ["PrepareInstall Cancelled https://example.org"]
+            );
+        });
+    }
+
+    #[test]
+    fn test_web_app_desktop_file_id() {
+        assert_eq!(
+            web_app_desktop_file_id(Some("org.example.Browser"), "My Web-App"),
+            "org.example.Browser.My_Web-App.desktop"
+        );
+        assert_eq!(web_app_desktop_file_id(None, "Café"), "Caf_.desktop");
+        assert_eq!(web_app_desktop_file_id(None, ""), "WebApp.desktop");
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn test_uninstall_many() {
+        use std::sync::{Arc, Mutex};
+
+        use zbus::dbus_interface;
+
+        use crate::proxy::DESKTOP_PATH;
+
+        struct MockDynamicLauncher(Arc<Mutex<Vec<String>>>);
+
+        #[dbus_interface(name = "org.freedesktop.portal.DynamicLauncher")]
+        impl MockDynamicLauncher {
+            fn uninstall(
+                &self,
+                desktop_file_id: &str,
+                _options: HashMap<String, OwnedValue>,
+            ) -> Result<(), PortalError> {
+                self.0.lock().unwrap().push(desktop_file_id.to_owned());
+                match desktop_file_id {
+                    "org.example.App.Missing.desktop" => {
+                        Err(PortalError::NotFound("Not installed".to_owned()))
+                    }
+                    "org.example.App.Broken.desktop" => Err(PortalError::Failed),
+                    _ => Ok(()),
+                }
+            }
+        }
+
+        async_std::task::block_on(async {
+            let Some(server) = crate::proxy::mock_desktop().await else {
+                return;
+            };
+            let uninstalled = Arc::new(Mutex::new(Vec::new()));
+            server
+                .object_server()
+                .at(DESKTOP_PATH, MockDynamicLauncher(uninstalled.clone()))
+                .await
+                .unwrap();
+
+            let proxy = DynamicLauncherProxy::new().await.unwrap();
+            let ids = [
+                "org.example.App.First.desktop",
+                "org.example.App.Broken.desktop",
+                "org.example.App.Missing.desktop",
+                "org.example.App.Last.desktop",
+            ];
+            let errors = proxy.uninstall_many(&ids).await.unwrap_err();
+            assert_eq!(*uninstalled.lock().unwrap(), ids);
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].0, "org.example.App.Broken.desktop");
+            assert!(matches!(errors[0].1, Error::Portal(PortalError::Failed)));
+
+            assert!(proxy.uninstall_many(&[ids[0], ids[2]]).await.is_ok());
+            assert!(proxy.uninstall_many(&[]).await.is_ok());
+        });
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn test_supports() {
+        use std::sync::{Arc, Mutex};
+
+        use zbus::dbus_interface;
+
+        use crate::proxy::DESKTOP_PATH;
+
+        struct MockDynamicLauncher(Arc<Mutex<u32>>);
+
+        #[dbus_interface(name = "org.freedesktop.portal.DynamicLauncher")]
+        impl MockDynamicLauncher {
+            #[dbus_interface(property)]
+            fn supported_launcher_types(&self) -> u32 {
+                *self.0.lock().unwrap()
+            }
+        }
+
+        async_std::task::block_on(async {
+            let Some(server) = crate::proxy::mock_desktop().await else {
+                return;
+            };
+            let types = Arc::new(Mutex::new(0));
+            server
+                .object_server()
+                .at(DESKTOP_PATH, MockDynamicLauncher(types.clone()))
+                .await
+                .unwrap();
+
+            for (value, application, web_application) in [
+                (0, false, false),
+                (1, true, false),
+                (2, false, true),
+                (3, true, true),
+            ] {
+                *types.lock().unwrap() = value;
+                let proxy = DynamicLauncherProxy::new().await.unwrap();
+                assert_eq!(
+                    proxy.supports(LauncherType::Application).await.unwrap(),
+                    application
+                );
+                assert_eq!(
+                    proxy.supports(LauncherType::WebApplication).await.unwrap(),
+                    web_application
+                );
+            }
+
+            let proxy = DynamicLauncherProxy::new().await.unwrap();
+            assert_eq!(
+                proxy.supported_launcher_types_cached().await.unwrap(),
+                LauncherType::Application | LauncherType::WebApplication
+            );
+            *types.lock().unwrap() = 1;
+            assert!(proxy.supports(LauncherType::WebApplication).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn test_dry_run_validation() {
+        assert!(validate_desktop_file_id("org.example.App.Web.desktop", None).is_ok());
+        assert!(
+            validate_desktop_file_id("org.example.App.Web.desktop", Some("org.example.App"))
+                .is_ok()
+        );
+        assert!(validate_desktop_file_id("org.example.App.Web", None).is_err());
+        assert!(validate_desktop_file_id("../org.example.App.Web.desktop", None).is_err());
+        assert!(
+            validate_desktop_file_id("org.other.App.Web.desktop", Some("org.example.App")).is_err()
+        );
+
+        let entry = DesktopEntry::default().comment("My Web App").to_string();
+        assert!(validate_desktop_entry(&entry).is_ok());
+        assert!(validate_desktop_entry("# A comment\n[Desktop Entry]\nType=Link\n").is_ok());
+        assert!(validate_desktop_entry("Type=Link\n").is_err());
+        assert!(validate_desktop_entry("[Desktop Entry]\nType\n").is_err());
+        assert!(validate_desktop_entry("[Other]\n[Desktop Entry]\n").is_err());
+        assert!(validate_desktop_entry("").is_err());
+    }
 }
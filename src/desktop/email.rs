@@ -9,12 +9,12 @@
 //! ```rust,no_run
 //! use std::fs::File;
 //!
-//! use ashpd::desktop::email::EmailRequest;
+//! use ashpd::desktop::email::{EmailAddress, EmailRequest};
 //!
 //! async fn run() -> ashpd::Result<()> {
 //!     let file = File::open("/home/bilelmoussaoui/Downloads/adwaita-night.jpg").unwrap();
 //!     EmailRequest::default()
-//!         .address("test@gmail.com")
+//!         .address(EmailAddress::new("test@gmail.com")?)
 //!         .subject("email subject")
 //!         .body("the pre-filled email body")
 //!         .attach(&file)
@@ -23,8 +23,26 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! Check the portal capabilities before composing the email
+//!
+//! ```rust,no_run
+//! use ashpd::desktop::email::{self, EmailAddress, EmailRequest};
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let capabilities = email::capabilities().await?;
+//!     let request = EmailRequest::default().address(EmailAddress::new("test@gmail.com")?);
+//!     let request = if capabilities.multiple_recipients() {
+//!         request.cc([EmailAddress::new("other@gmail.com")?])
+//!     } else {
+//!         request
+//!     };
+//!     request.send().await?;
+//!     Ok(())
+//! }
+//! ```
 
-use std::os::unix::prelude::AsRawFd;
+use std::{fmt, os::unix::prelude::AsRawFd, str::FromStr};
 
 use serde::Serialize;
 use zbus::zvariant::{Fd, SerializeDict, Type};
@@ -32,19 +50,110 @@ use zbus::zvariant::{Fd, SerializeDict, Type};
 use super::{HandleToken, Request};
 use crate::{proxy::Proxy, Error, WindowIdentifier};
 
+/// A validated email address.
+///
+/// Only the overall `local@domain` shape of the address is checked.
+#[derive(Debug, Serialize, Type, PartialEq, Eq, Hash, Clone)]
+pub struct EmailAddress(String);
+
+impl EmailAddress {
+    /// Create a new email address.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] if `address` is not a valid email address.
+    pub fn new(address: &str) -> Result<Self, Error> {
+        address.parse()
+    }
+}
+
+impl FromStr for EmailAddress {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let is_valid = s
+            .rsplit_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && is_valid_domain(domain))
+            && !s.contains(|c: char| c.is_whitespace() || c.is_control());
+        if is_valid {
+            Ok(Self(s.to_owned()))
+        } else {
+            Err(Error::ParseError(
+                "Failed to parse email address, invalid value",
+            ))
+        }
+    }
+}
+
+impl TryFrom<&str> for EmailAddress {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl AsRef<str> for EmailAddress {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for EmailAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn is_valid_domain(domain: &str) -> bool {
+    !domain.is_empty()
+        && domain
+            .split('.')
+            .all(|label| !label.is_empty() && !label.contains('@'))
+}
+
 #[derive(SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
 struct EmailOptions {
     handle_token: HandleToken,
-    address: Option<String>,
-    addresses: Option<Vec<String>>,
-    cc: Option<Vec<String>>,
-    bcc: Option<Vec<String>>,
+    address: Option<EmailAddress>,
+    addresses: Option<Vec<EmailAddress>>,
+    cc: Option<Vec<EmailAddress>>,
+    bcc: Option<Vec<EmailAddress>>,
     subject: Option<String>,
     body: Option<String>,
     attachment_fds: Option<Vec<Fd>>,
 }
 
+impl EmailOptions {
+    fn has_multiple_recipients(&self) -> bool {
+        self.addresses.is_some() || self.cc.is_some() || self.bcc.is_some()
+    }
+}
+
+/// The features supported by the running Email portal.
+///
+/// **Note** very old portals don't report their version, in which case all
+/// the features are assumed to be supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmailCapabilities {
+    version: Option<u32>,
+}
+
+impl EmailCapabilities {
+    /// The version of the interface, if reported by the portal.
+    pub fn version(&self) -> Option<u32> {
+        self.version
+    }
+
+    /// Whether sending to multiple addresses, CC & BCC is supported.
+    ///
+    /// Added in version 2 of the interface.
+    pub fn multiple_recipients(&self) -> bool {
+        self.version.is_none_or(|version| version >= 2)
+    }
+}
+
 #[derive(Debug)]
 #[doc(alias = "org.freedesktop.portal.Email")]
 struct EmailProxy<'a>(Proxy<'a>);
@@ -56,11 +165,22 @@ impl<'a> EmailProxy<'a> {
         Ok(Self(proxy))
     }
 
+    /// The features supported by the portal.
+    pub async fn capabilities(&self) -> Result<EmailCapabilities, Error> {
+        Ok(EmailCapabilities {
+            version: self.0.version().await.ok(),
+        })
+    }
+
     /// Presents a window that lets the user compose an email.
     ///
     /// **Note** the default email client for the host will need to support
     /// `mailto:` URIs following RFC 2368.
     ///
+    /// Returns [`Error::NotSupported`] if the options make use of features
+    /// not supported by the portal, like multiple recipients with version 1
+    /// of the interface. See [`EmailCapabilities`].
+    ///
     /// # Arguments
     ///
     /// * `identifier` - Identifier for the application window.
@@ -75,6 +195,12 @@ impl<'a> EmailProxy<'a> {
         identifier: &WindowIdentifier,
         options: EmailOptions,
     ) -> Result<Request<()>, Error> {
+        if options.has_multiple_recipients() && !self.capabilities().await?.multiple_recipients() {
+            return Err(Error::NotSupported {
+                interface: self.0.interface().to_string(),
+                member: "ComposeEmail".to_owned(),
+            });
+        }
         self.0
             .empty_request(
                 &options.handle_token,
@@ -105,44 +231,32 @@ impl EmailRequest {
 
     /// Sets the email address to send the email to.
     #[must_use]
-    pub fn address<'a>(mut self, address: impl Into<Option<&'a str>>) -> Self {
-        self.options.address = address.into().map(ToOwned::to_owned);
+    pub fn address(mut self, address: impl Into<Option<EmailAddress>>) -> Self {
+        self.options.address = address.into();
         self
     }
 
     /// Sets a list of email addresses to send the email to.
     #[must_use]
-    pub fn addresses<P: IntoIterator<Item = I>, I: AsRef<str> + Type + Serialize>(
+    pub fn addresses<P: IntoIterator<Item = EmailAddress>>(
         mut self,
         addresses: impl Into<Option<P>>,
     ) -> Self {
-        self.options.addresses = addresses
-            .into()
-            .map(|a| a.into_iter().map(|s| s.as_ref().to_owned()).collect());
+        self.options.addresses = addresses.into().map(|a| a.into_iter().collect());
         self
     }
 
     /// Sets a list of email addresses to BCC.
     #[must_use]
-    pub fn bcc<P: IntoIterator<Item = I>, I: AsRef<str> + Type + Serialize>(
-        mut self,
-        bcc: impl Into<Option<P>>,
-    ) -> Self {
-        self.options.bcc = bcc
-            .into()
-            .map(|a| a.into_iter().map(|s| s.as_ref().to_owned()).collect());
+    pub fn bcc<P: IntoIterator<Item = EmailAddress>>(mut self, bcc: impl Into<Option<P>>) -> Self {
+        self.options.bcc = bcc.into().map(|a| a.into_iter().collect());
         self
     }
 
     /// Sets a list of email addresses to CC.
     #[must_use]
-    pub fn cc<P: IntoIterator<Item = I>, I: AsRef<str> + Type + Serialize>(
-        mut self,
-        cc: impl Into<Option<P>>,
-    ) -> Self {
-        self.options.cc = cc
-            .into()
-            .map(|a| a.into_iter().map(|s| s.as_ref().to_owned()).collect());
+    pub fn cc<P: IntoIterator<Item = EmailAddress>>(mut self, cc: impl Into<Option<P>>) -> Self {
+        self.options.cc = cc.into().map(|a| a.into_iter().collect());
         self
     }
 
@@ -179,8 +293,44 @@ impl EmailRequest {
     }
 
     /// Send the request.
+    ///
+    /// Returns [`Error::NotSupported`] if the request makes use of features
+    /// not supported by the portal, see [`capabilities`].
     pub async fn send(self) -> Result<Request<()>, Error> {
         let proxy = EmailProxy::new().await?;
         proxy.compose(&self.identifier, self.options).await
     }
 }
+
+/// Retrieve the features supported by the running Email portal.
+///
+/// **Note** very old portals don't report any capability information, in
+/// which case every feature is reported as supported and composing an email
+/// proceeds optimistically.
+pub async fn capabilities() -> Result<EmailCapabilities, Error> {
+    let proxy = EmailProxy::new().await?;
+    proxy.capabilities().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EmailAddress;
+
+    #[test]
+    fn email_address() {
+        for address in ["test@gmail.com", "first.last+tag@sub.example.org", "a@b"] {
+            let email = EmailAddress::new(address).unwrap();
+            assert_eq!(email.to_string(), address);
+        }
+        for address in [
+            "",
+            "test",
+            "@gmail.com",
+            "test@",
+            "te st@gmail.com",
+            "test@gmail..com",
+        ] {
+            assert!(EmailAddress::new(address).is_err(), "{address}");
+        }
+    }
+}
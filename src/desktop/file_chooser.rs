@@ -35,10 +35,28 @@
 //! }
 //! ```
 //!
+//! #### Picking a folder
+//!
+//! ```rust,no_run
+//! use ashpd::desktop::file_chooser::SelectedFiles;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let folder = SelectedFiles::open_file()
+//!         .title("pick a folder to import")
+//!         .accept_label("import")
+//!         .pick_folder()
+//!         .await?;
+//!
+//!     println!("{}", folder);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
 //! #### Ask to save a file
 //!
 //! ```rust,no_run
-//! use ashpd::desktop::file_chooser::{FileFilter, SelectedFiles};
+//! use ashpd::desktop::file_chooser::{Choice, FileFilter, SelectedFiles};
 //!
 //! async fn run() -> ashpd::Result<()> {
 //!     let files = SelectedFiles::save_file()
@@ -47,11 +65,17 @@
 //!         .current_name("image.jpg")
 //!         .modal(true)
 //!         .filter(FileFilter::new("JPEG Image").glob("*.jpg"))
+//!         .choice(
+//!             Choice::new("quality", "Quality", "high")
+//!                 .insert("high", "High")
+//!                 .insert("low", "Low"),
+//!         )
 //!         .send()
 //!         .await?
 //!         .response()?;
 //!
 //!     println!("{:#?}", files);
+//!     println!("{:?}", files.choice("quality"));
 //!
 //!     Ok(())
 //! }
@@ -79,13 +103,18 @@
 //! }
 //! ```
 
-use std::path::Path;
+use std::{fs, io, path::Path};
+
+#[cfg(feature = "async-std")]
+use async_std::fs::read;
+#[cfg(feature = "tokio")]
+use tokio::fs::read;
 
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
 
-use super::{HandleToken, Request};
+use super::{HandleToken, Request, ResponseError};
 use crate::{proxy::Proxy, Error, FilePath, WindowIdentifier};
 
 #[derive(Clone, Serialize, Deserialize, Type, Debug, PartialEq)]
@@ -135,8 +164,58 @@ impl FileFilter {
         self.1.push((FilterType::GlobPattern, pattern.to_owned()));
         self
     }
+
+    /// Create a file filter matching the given file extensions.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - user-visible name of the file filter.
+    /// * `extensions` - the extensions to match, like `png` or `.png`.
+    pub fn from_extensions(label: &str, extensions: &[&str]) -> Self {
+        extensions
+            .iter()
+            .fold(Self::new(label), |filter, extension| {
+                filter.glob(&format!("*.{}", extension.trim_start_matches('.')))
+            })
+    }
+
+    /// A file filter matching image files.
+    pub fn image() -> Self {
+        Self::new("Images").mimetype("image/*")
+    }
+
+    /// A file filter matching audio files.
+    pub fn audio() -> Self {
+        Self::new("Audio").mimetype("audio/*")
+    }
+
+    /// A file filter matching video files.
+    pub fn video() -> Self {
+        Self::new("Videos").mimetype("video/*")
+    }
+
+    /// A file filter matching the image formats [`OpenFileRequest::pick_image`]
+    /// can decode.
+    #[cfg(feature = "image")]
+    fn decodable_image() -> Self {
+        DECODABLE_IMAGE_TYPES
+            .iter()
+            .fold(Self::new("Images"), |filter, mimetype| {
+                filter.mimetype(mimetype)
+            })
+    }
 }
 
+/// The MIME types of the image formats enabled on the `image` crate.
+#[cfg(feature = "image")]
+const DECODABLE_IMAGE_TYPES: &[&str] = &[
+    "image/bmp",
+    "image/gif",
+    "image/jpeg",
+    "image/png",
+    "image/webp",
+];
+
 impl FileFilter {
     /// The label of the filter.
     pub fn label(&self) -> &str {
@@ -236,6 +315,18 @@ struct OpenFileOptions {
     choices: Vec<Choice>,
 }
 
+impl OpenFileOptions {
+    /// Drops the file filters when selecting directories, as they only apply
+    /// to files.
+    fn normalize(mut self) -> Self {
+        if self.directory == Some(true) {
+            self.filters.clear();
+            self.current_filter = None;
+        }
+        self
+    }
+}
+
 #[derive(SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
 struct SaveFileOptions {
@@ -291,10 +382,94 @@ impl SelectedFiles {
         self.uris.as_slice()
     }
 
+    /// The selected file uri, for requests not allowing
+    /// [multiple](OpenFileRequest::multiple) files.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ResponseError::Cancelled`] if no file was selected and
+    /// [`Error::InvalidState`] if several files were.
+    pub fn single(&self) -> Result<url::Url, Error> {
+        match self.uris.as_slice() {
+            [] => Err(ResponseError::Cancelled.into()),
+            [uri] => Ok(uri.clone()),
+            _ => Err(Error::InvalidState("More than one file was selected")),
+        }
+    }
+
     /// The selected value of each choice as a tuple of (key, value)
     pub fn choices(&self) -> &[(String, String)] {
         self.choices.as_deref().unwrap_or_default()
     }
+
+    /// The selected value of the choice with the given id, as set with
+    /// [`OpenFileRequest::choice`], [`SaveFileRequest::choice`] or
+    /// [`SaveFilesRequest::choice`].
+    ///
+    /// Boolean choices, see [`Choice::boolean`], are either `"true"` or
+    /// `"false"`.
+    pub fn choice(&self, id: &str) -> Option<&str> {
+        self.choices()
+            .iter()
+            .find(|(key, _)| key == id)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the URI points to a directory.
+    ///
+    /// Returns `None` for non `file://` URIs or if the file metadata can't be
+    /// retrieved, for example if it doesn't exist anymore.
+    pub fn is_directory(uri: &url::Url) -> Option<bool> {
+        if uri.scheme() != "file" {
+            return None;
+        }
+        let path = uri.to_file_path().ok()?;
+        std::fs::metadata(path)
+            .ok()
+            .map(|metadata| metadata.is_dir())
+    }
+
+    /// Open the selected files for reading.
+    ///
+    /// # Errors
+    ///
+    /// An [`io::ErrorKind::InvalidInput`] error is returned if one of the URIs
+    /// is not a `file://` URI, for example a remote location.
+    pub fn open(&self) -> io::Result<Vec<fs::File>> {
+        self.open_with(fs::OpenOptions::new().read(true))
+    }
+
+    /// Open the selected files for writing, creating them if they don't exist
+    /// and truncating them otherwise. Meant for the files selected by a
+    /// [`SaveFileRequest`] or a [`SaveFilesRequest`].
+    ///
+    /// # Errors
+    ///
+    /// An [`io::ErrorKind::InvalidInput`] error is returned if one of the URIs
+    /// is not a `file://` URI, for example a remote location.
+    pub fn open_writable(&self) -> io::Result<Vec<fs::File>> {
+        self.open_with(
+            fs::OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true),
+        )
+    }
+
+    fn open_with(&self, options: &fs::OpenOptions) -> io::Result<Vec<fs::File>> {
+        self.uris
+            .iter()
+            .map(|uri| {
+                let path = uri.to_file_path().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("{uri} is not a local file"),
+                    )
+                })?;
+                options.open(path)
+            })
+            .collect()
+    }
 }
 
 #[doc(alias = "org.freedesktop.portal.FileChooser")]
@@ -313,6 +488,7 @@ impl<'a> FileChooserProxy<'a> {
         title: &str,
         options: OpenFileOptions,
     ) -> Result<Request<SelectedFiles>, Error> {
+        let options = options.normalize();
         self.0
             .request(
                 &options.handle_token,
@@ -322,6 +498,91 @@ impl<'a> FileChooserProxy<'a> {
             .await
     }
 
+    pub async fn pick_folder(
+        &self,
+        identifier: &WindowIdentifier,
+        title: &str,
+        mut options: OpenFileOptions,
+    ) -> Result<url::Url, Error> {
+        options.directory = Some(true);
+        options.multiple = Some(false);
+        let files = self
+            .open_file(identifier, title, options)
+            .await?
+            .response()?;
+        files
+            .uris
+            .into_iter()
+            .next()
+            .ok_or_else(|| ResponseError::Cancelled.into())
+    }
+
+    pub async fn pick_folders(
+        &self,
+        identifier: &WindowIdentifier,
+        title: &str,
+        mut options: OpenFileOptions,
+    ) -> Result<Vec<url::Url>, Error> {
+        options.directory = Some(true);
+        options.multiple = Some(true);
+        let files = self
+            .open_file(identifier, title, options)
+            .await?
+            .response()?;
+        if files.uris.is_empty() {
+            return Err(ResponseError::Cancelled.into());
+        }
+        Ok(files.uris)
+    }
+
+    #[cfg(feature = "image")]
+    pub async fn pick_image(
+        &self,
+        identifier: &WindowIdentifier,
+        title: &str,
+        mut options: OpenFileOptions,
+    ) -> Result<(url::Url, image::DynamicImage), Error> {
+        options.directory = Some(false);
+        options.multiple = Some(false);
+        if options.filters.is_empty() {
+            let filter = FileFilter::decodable_image();
+            options.filters.push(filter.clone());
+            options.current_filter = Some(filter);
+        }
+        let (uri, bytes) = self
+            .open_file_contents(identifier, title, options)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::from(ResponseError::Cancelled))?;
+        let image = image::load_from_memory(&bytes)?;
+        Ok((uri, image))
+    }
+
+    pub async fn open_file_contents(
+        &self,
+        identifier: &WindowIdentifier,
+        title: &str,
+        options: OpenFileOptions,
+    ) -> Result<Vec<(url::Url, Vec<u8>)>, Error> {
+        let files = self
+            .open_file(identifier, title, options)
+            .await?
+            .response()?;
+        let mut contents = Vec::with_capacity(files.uris.len());
+        for uri in files.uris {
+            let path = uri.to_file_path().map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{uri} is not a local file"),
+                )
+            })?;
+            let bytes = read(path).await?;
+            contents.push((uri, bytes));
+        }
+        Ok(contents)
+    }
+
     pub async fn save_file(
         &self,
         identifier: &WindowIdentifier,
@@ -357,6 +618,9 @@ impl<'a> FileChooserProxy<'a> {
 #[doc(alias = "xdp_portal_open_file")]
 /// A [builder-pattern] type to open a file.
 ///
+/// It can be stored with serde, for example to let users configure the
+/// dialog, the window identifier isn't part of it.
+///
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct OpenFileRequest {
     identifier: WindowIdentifier,
@@ -401,6 +665,11 @@ impl OpenFileRequest {
     }
 
     /// Sets whether to select directories or not.
+    ///
+    /// Combined with [`multiple`](Self::multiple), several directories can
+    /// be selected, see also [`pick_folders`](Self::pick_folders). The file
+    /// filters don't apply to directories and aren't sent to the portal in
+    /// that case.
     #[must_use]
     pub fn directory(mut self, directory: impl Into<Option<bool>>) -> Self {
         self.options.directory = directory.into();
@@ -449,12 +718,87 @@ impl OpenFileRequest {
             .open_file(&self.identifier, &self.title, self.options)
             .await
     }
+
+    /// Send the request to select a single directory and wait for the
+    /// selected one.
+    ///
+    /// The [`directory`](Self::directory) and [`multiple`](Self::multiple)
+    /// options are overridden.
+    ///
+    /// Returns [`ResponseError::Cancelled`] if no directory was selected.
+    pub async fn pick_folder(self) -> Result<url::Url, Error> {
+        let proxy = FileChooserProxy::new().await?;
+        proxy
+            .pick_folder(&self.identifier, &self.title, self.options)
+            .await
+    }
+
+    /// Send the request to select several directories and wait for the
+    /// selected ones.
+    ///
+    /// The [`directory`](Self::directory) and [`multiple`](Self::multiple)
+    /// options are overridden.
+    ///
+    /// Returns [`ResponseError::Cancelled`] if no directory was selected.
+    pub async fn pick_folders(self) -> Result<Vec<url::Url>, Error> {
+        let proxy = FileChooserProxy::new().await?;
+        proxy
+            .pick_folders(&self.identifier, &self.title, self.options)
+            .await
+    }
+
+    /// Send the request to select a single image file, and decode it.
+    ///
+    /// The [`directory`](Self::directory) and [`multiple`](Self::multiple)
+    /// options are overridden. Unless filters were added, the dialog only
+    /// shows the image formats that can be decoded: BMP, GIF, JPEG, PNG and
+    /// WebP. The file is read from its local path, like
+    /// [`Self::contents`], use [`image::DynamicImage::to_rgba8`] to access
+    /// its pixels.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ResponseError::Cancelled`] if no file was selected, an
+    /// [`Error::IO`] error if it can't be read or an [`Error::Image`] error
+    /// if it can't be decoded.
+    #[cfg(feature = "image")]
+    pub async fn pick_image(self) -> Result<(url::Url, image::DynamicImage), Error> {
+        let proxy = FileChooserProxy::new().await?;
+        proxy
+            .pick_image(&self.identifier, &self.title, self.options)
+            .await
+    }
+
+    /// Send the request and read the content of the selected files.
+    ///
+    /// The files are read from the local paths of the returned URIs. Inside
+    /// a sandbox, the portal returns paths in the document portal mount
+    /// point, so this works even for files the sandbox can't otherwise
+    /// access.
+    ///
+    /// **Note** all the selected files are loaded in memory at once. For
+    /// large files, prefer reading them as a stream with
+    /// [`SelectedFiles::open`] instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::IO`] error if one of the selected files is not a
+    /// local file, for example a remote location, or can't be read.
+    pub async fn contents(self) -> Result<Vec<(url::Url, Vec<u8>)>, Error> {
+        let proxy = FileChooserProxy::new().await?;
+        proxy
+            .open_file_contents(&self.identifier, &self.title, self.options)
+            .await
+    }
 }
 
 #[derive(Debug, Default)]
 #[doc(alias = "xdp_portal_save_files")]
 /// A [builder-pattern] type to save multiple files.
 ///
+/// It can be stored with serde, for example to let users configure the
+/// dialog, the window identifier isn't part of it.
+///
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct SaveFilesRequest {
     identifier: WindowIdentifier,
@@ -542,6 +886,9 @@ impl SaveFilesRequest {
 #[doc(alias = "xdp_portal_save_file")]
 /// A [builder-pattern] type to save a file.
 ///
+/// It can be stored with serde, for example to let users configure the
+/// dialog, the window identifier isn't part of it.
+///
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct SaveFileRequest {
     identifier: WindowIdentifier,
@@ -649,3 +996,334 @@ impl SaveFileRequest {
             .await
     }
 }
+
+// The option builders can be stored, e.g. as JSON, to let users configure the
+// dialogs. The window identifier and the handle token are specific to a
+// request and are left out.
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct OpenFileConfig {
+    title: String,
+    accept_label: Option<String>,
+    modal: Option<bool>,
+    multiple: Option<bool>,
+    directory: Option<bool>,
+    filters: Vec<FileFilter>,
+    current_filter: Option<FileFilter>,
+    choices: Vec<Choice>,
+}
+
+impl Serialize for OpenFileRequest {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let options = &self.options;
+        OpenFileConfig {
+            title: self.title.clone(),
+            accept_label: options.accept_label.clone(),
+            modal: options.modal,
+            multiple: options.multiple,
+            directory: options.directory,
+            filters: options.filters.clone(),
+            current_filter: options.current_filter.clone(),
+            choices: options.choices.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for OpenFileRequest {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = OpenFileConfig::deserialize(deserializer)?;
+        Ok(Self {
+            identifier: WindowIdentifier::default(),
+            title: config.title,
+            options: OpenFileOptions {
+                handle_token: HandleToken::default(),
+                accept_label: config.accept_label,
+                modal: config.modal,
+                multiple: config.multiple,
+                directory: config.directory,
+                filters: config.filters,
+                current_filter: config.current_filter,
+                choices: config.choices,
+            },
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct SaveFileConfig {
+    title: String,
+    accept_label: Option<String>,
+    modal: Option<bool>,
+    current_name: Option<String>,
+    current_folder: Option<FilePath>,
+    current_file: Option<FilePath>,
+    filters: Vec<FileFilter>,
+    current_filter: Option<FileFilter>,
+    choices: Vec<Choice>,
+}
+
+impl Serialize for SaveFileRequest {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let options = &self.options;
+        SaveFileConfig {
+            title: self.title.clone(),
+            accept_label: options.accept_label.clone(),
+            modal: options.modal,
+            current_name: options.current_name.clone(),
+            current_folder: options.current_folder.clone(),
+            current_file: options.current_file.clone(),
+            filters: options.filters.clone(),
+            current_filter: options.current_filter.clone(),
+            choices: options.choices.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SaveFileRequest {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = SaveFileConfig::deserialize(deserializer)?;
+        Ok(Self {
+            identifier: WindowIdentifier::default(),
+            title: config.title,
+            options: SaveFileOptions {
+                handle_token: HandleToken::default(),
+                accept_label: config.accept_label,
+                modal: config.modal,
+                current_name: config.current_name,
+                current_folder: config.current_folder,
+                current_file: config.current_file,
+                filters: config.filters,
+                current_filter: config.current_filter,
+                choices: config.choices,
+            },
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct SaveFilesConfig {
+    title: String,
+    accept_label: Option<String>,
+    modal: Option<bool>,
+    choices: Vec<Choice>,
+    current_folder: Option<FilePath>,
+    files: Option<Vec<FilePath>>,
+}
+
+impl Serialize for SaveFilesRequest {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let options = &self.options;
+        SaveFilesConfig {
+            title: self.title.clone(),
+            accept_label: options.accept_label.clone(),
+            modal: options.modal,
+            choices: options.choices.clone(),
+            current_folder: options.current_folder.clone(),
+            files: options.files.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SaveFilesRequest {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = SaveFilesConfig::deserialize(deserializer)?;
+        Ok(Self {
+            identifier: WindowIdentifier::default(),
+            title: config.title,
+            options: SaveFilesOptions {
+                handle_token: HandleToken::default(),
+                accept_label: config.accept_label,
+                modal: config.modal,
+                choices: config.choices,
+                current_folder: config.current_folder,
+                files: config.files,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use byteorder::LE;
+    use zbus::zvariant::{from_slice, to_bytes, EncodingContext as Context, OwnedValue};
+
+    use super::*;
+
+    fn to_dict(options: impl Serialize + Type) -> HashMap<String, OwnedValue> {
+        let ctxt = Context::<LE>::new_dbus(0);
+        let encoded = to_bytes(ctxt, &options).unwrap();
+        from_slice(&encoded, ctxt).unwrap()
+    }
+
+    fn assert_dialog_options(dict: HashMap<String, OwnedValue>) {
+        assert_eq!(
+            String::try_from(dict["accept_label"].clone()).unwrap(),
+            "Import"
+        );
+        assert!(!bool::try_from(dict["modal"].clone()).unwrap());
+    }
+
+    #[test]
+    fn dialog_options() {
+        let request = OpenFileRequest::default()
+            .accept_label("Import")
+            .modal(false);
+        assert_dialog_options(to_dict(request.options));
+
+        let request = SaveFileRequest::default()
+            .accept_label("Import")
+            .modal(false);
+        assert_dialog_options(to_dict(request.options));
+
+        let request = SaveFilesRequest::default()
+            .accept_label("Import")
+            .modal(false);
+        assert_dialog_options(to_dict(request.options));
+
+        let dict = to_dict(OpenFileRequest::default().options);
+        assert!(!dict.contains_key("accept_label"));
+        assert!(!dict.contains_key("modal"));
+    }
+
+    #[test]
+    fn file_filter_presets() {
+        let filter = FileFilter::from_extensions("Pictures", &["png", ".jpg"]);
+        assert_eq!(filter.label(), "Pictures");
+        assert_eq!(filter.pattern_filters(), vec!["*.png", "*.jpg"]);
+        assert!(filter.mimetype_filters().is_empty());
+
+        let filter = FileFilter::image();
+        assert_eq!(filter.mimetype_filters(), vec!["image/*"]);
+        assert!(filter.pattern_filters().is_empty());
+    }
+
+    #[test]
+    fn single_selected_file() {
+        let uri = url::Url::parse("file:///tmp/file.txt").unwrap();
+        let files = |uris: Vec<url::Url>| SelectedFiles {
+            uris,
+            choices: None,
+        };
+        assert_eq!(files(vec![uri.clone()]).single().unwrap(), uri);
+        assert!(matches!(
+            files(vec![]).single(),
+            Err(Error::Response {
+                code: ResponseError::Cancelled,
+                ..
+            })
+        ));
+        assert!(matches!(
+            files(vec![uri.clone(), uri]).single(),
+            Err(Error::InvalidState(_))
+        ));
+    }
+
+    #[test]
+    fn open_selected_files() {
+        let path = std::env::temp_dir().join("ashpd-selected-file");
+        fs::write(&path, "content").unwrap();
+
+        let files = SelectedFiles {
+            uris: vec![url::Url::from_file_path(&path).unwrap()],
+            choices: None,
+        };
+        let content = io::read_to_string(&files.open().unwrap()[0]).unwrap();
+        assert_eq!(content, "content");
+        fs::remove_file(&path).unwrap();
+
+        let files = SelectedFiles {
+            uris: vec![url::Url::parse("https://example.org/file.txt").unwrap()],
+            choices: None,
+        };
+        let err = files.open().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn directory_filters() {
+        let request = OpenFileRequest::default()
+            .filter(FileFilter::image())
+            .current_filter(FileFilter::image());
+        let dict = to_dict(request.options.normalize());
+        assert!(dict.contains_key("filters"));
+        assert!(dict.contains_key("current_filter"));
+
+        let request = OpenFileRequest::default()
+            .directory(true)
+            .multiple(true)
+            .filter(FileFilter::image())
+            .current_filter(FileFilter::image());
+        let dict = to_dict(request.options.normalize());
+        assert!(bool::try_from(dict["directory"].clone()).unwrap());
+        assert!(bool::try_from(dict["multiple"].clone()).unwrap());
+        assert!(!dict.contains_key("current_filter"));
+        assert!(zbus::zvariant::Array::try_from(dict["filters"].clone())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn save_file_choices() {
+        let quality = Choice::new("quality", "Export Quality", "high")
+            .insert("high", "High")
+            .insert("low", "Low");
+        let open = to_dict(OpenFileRequest::default().choice(quality.clone()).options);
+        let save = to_dict(SaveFileRequest::default().choice(quality.clone()).options);
+        let save_files = to_dict(SaveFilesRequest::default().choices([quality]).options);
+        assert_eq!(open["choices"], save["choices"]);
+        assert_eq!(open["choices"], save_files["choices"]);
+        assert_eq!(save["choices"].value_signature(), "a(ssa(ss)s)");
+
+        let files = SelectedFiles {
+            uris: vec![],
+            choices: Some(vec![
+                ("quality".to_owned(), "low".to_owned()),
+                ("re-encode".to_owned(), "true".to_owned()),
+            ]),
+        };
+        assert_eq!(files.choice("quality"), Some("low"));
+        assert_eq!(files.choice("re-encode"), Some("true"));
+        assert_eq!(files.choice("encoding"), None);
+    }
+
+    #[test]
+    fn stored_request() {
+        let filter = FileFilter::new("Images").mimetype("image/png");
+        let request = OpenFileRequest::default()
+            .title("Open an image")
+            .modal(false)
+            .filter(filter.clone());
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["title"], "Open an image");
+        assert!(json.get("handle_token").is_none());
+
+        let request: OpenFileRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(request.title, "Open an image");
+        assert_eq!(request.options.modal, Some(false));
+        assert_eq!(request.options.filters, vec![filter]);
+
+        let request: SaveFileRequest =
+            serde_json::from_str(r#"{"current_name": "image.png"}"#).unwrap();
+        assert_eq!(request.options.current_name.as_deref(), Some("image.png"));
+        assert!(request.options.filters.is_empty());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn decodable_image() {
+        let filter = FileFilter::decodable_image();
+        assert_eq!(filter.mimetype_filters(), DECODABLE_IMAGE_TYPES);
+        for mimetype in DECODABLE_IMAGE_TYPES {
+            let format = image::ImageFormat::from_mime_type(mimetype).unwrap();
+            assert!(format.reading_enabled(), "{mimetype} can't be decoded");
+        }
+    }
+}
@@ -20,7 +20,11 @@ use std::{fmt::Debug, os::unix::io::AsRawFd};
 use serde_repr::Deserialize_repr;
 use zbus::zvariant::{Fd, Type};
 
-use crate::{error::PortalError, proxy::Proxy, Error};
+use crate::{
+    error::PortalError,
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
 #[derive(Deserialize_repr, PartialEq, Eq, Debug, Type)]
 /// The status of the game mode.
@@ -81,6 +85,13 @@ impl<'a> GameMode<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`GameMode::new`], failing with [`Error::NotSupported`] if the
+    /// portal doesn't provide `org.freedesktop.portal.GameMode`.
+    pub async fn new_checked() -> Result<GameMode<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.GameMode").await?;
+        Ok(Self(proxy))
+    }
+
     /// Query the GameMode status for a process.
     /// If the caller is running inside a sandbox with pid namespace isolation,
     /// the pid will be translated to the respective host pid.
@@ -295,3 +306,11 @@ impl<'a> GameMode<'a> {
         }
     }
 }
+
+impl AsProxy for GameMode<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for GameMode<'_> {}
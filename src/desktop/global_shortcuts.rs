@@ -1,15 +1,56 @@
 //! Register global shortcuts
-
-use std::{collections::HashMap, fmt::Debug, time::Duration};
-
-use futures_util::TryFutureExt;
+//!
+//! The signal streams are `Send` and `Unpin`, they can be raced against each
+//! other or against the session being closed, e.g. with
+//! [`futures_util::select!`](https://docs.rs/futures/latest/futures/macro.select.html).
+//!
+//! ```rust,no_run
+//! use ashpd::{
+//!     desktop::global_shortcuts::{GlobalShortcuts, NewShortcut},
+//!     WindowIdentifier,
+//! };
+//! use futures_util::{select, FutureExt, StreamExt};
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let proxy = GlobalShortcuts::new().await?;
+//!     let session = proxy.create_session().await?;
+//!     let shortcuts = [NewShortcut::new("screenshot", "Take a screenshot")];
+//!     proxy
+//!         .bind_shortcuts(&session, &shortcuts, &WindowIdentifier::default())
+//!         .await?
+//!         .response()?;
+//!
+//!     let mut activated = proxy.receive_activated_stream().await?.fuse();
+//!     let mut closed = Box::pin(session.receive_closed().fuse());
+//!     loop {
+//!         select! {
+//!             shortcut = activated.next() => match shortcut {
+//!                 Some(shortcut) => println!("{} activated", shortcut.shortcut_id()),
+//!                 None => break,
+//!             },
+//!             _ = closed => {
+//!                 println!("The session was closed");
+//!                 break;
+//!             }
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::{collections::HashMap, fmt, fmt::Debug, str::FromStr, time::Duration};
+
+use futures_util::{Stream, TryFutureExt};
 use serde::{Deserialize, Serialize};
 use zbus::zvariant::{
     DeserializeDict, ObjectPath, OwnedObjectPath, OwnedValue, SerializeDict, Type,
 };
 
 use super::{HandleToken, Request, Session};
-use crate::{proxy::Proxy, Error, WindowIdentifier};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalError, PortalProxy, WindowIdentifier,
+};
 
 #[derive(Clone, SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
@@ -21,6 +62,130 @@ struct NewShortcutInfo {
     preferred_trigger: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A modifier of a shortcut [`Trigger`].
+pub enum Modifier {
+    /// The shift key.
+    Shift,
+    /// The control key.
+    Ctrl,
+    /// The alt key.
+    Alt,
+    /// The num lock key.
+    Num,
+    /// The logo key, also known as super.
+    Logo,
+}
+
+impl fmt::Display for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl AsRef<str> for Modifier {
+    fn as_ref(&self) -> &str {
+        (*self).into()
+    }
+}
+
+impl From<Modifier> for &'static str {
+    fn from(m: Modifier) -> Self {
+        match m {
+            Modifier::Shift => "SHIFT",
+            Modifier::Ctrl => "CTRL",
+            Modifier::Alt => "ALT",
+            Modifier::Num => "NUM",
+            Modifier::Logo => "LOGO",
+        }
+    }
+}
+
+impl FromStr for Modifier {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "SHIFT" | "shift" => Ok(Modifier::Shift),
+            "CTRL" | "ctrl" => Ok(Modifier::Ctrl),
+            "ALT" | "alt" => Ok(Modifier::Alt),
+            "NUM" | "num" => Ok(Modifier::Num),
+            "LOGO" | "logo" => Ok(Modifier::Logo),
+            _ => Err(Error::ParseError("Failed to parse modifier, invalid value")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A shortcut trigger, defined as described by the "shortcuts" XDG
+/// specification.
+///
+/// A trigger is made of a list of modifiers followed by a key name, separated
+/// by a `+`, for example `CTRL+a`, `LOGO+SHIFT+Return` or `Launch1`.
+pub struct Trigger {
+    modifiers: Vec<Modifier>,
+    key: String,
+}
+
+impl Trigger {
+    /// The modifiers that have to be held.
+    pub fn modifiers(&self) -> &[Modifier] {
+        &self.modifiers
+    }
+
+    /// The key name.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{modifier}+")?;
+        }
+        f.write_str(&self.key)
+    }
+}
+
+impl FromStr for Trigger {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| {
+            Error::Portal(PortalError::InvalidArgument(format!(
+                "Invalid shortcut trigger `{s}`: {reason}"
+            )))
+        };
+
+        let (modifiers, key) = match s.rsplit_once('+') {
+            Some(("", _)) => return Err(invalid("empty modifier")),
+            Some((modifiers, key)) => (Some(modifiers), key),
+            None => (None, s),
+        };
+        if key.is_empty() {
+            return Err(invalid("missing key"));
+        }
+        if !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(invalid("invalid key name"));
+        }
+
+        let mut parsed_modifiers = Vec::new();
+        for modifier in modifiers.into_iter().flat_map(|m| m.split('+')) {
+            let modifier = Modifier::from_str(modifier).map_err(|_| invalid("unknown modifier"))?;
+            if parsed_modifiers.contains(&modifier) {
+                return Err(invalid("duplicated modifier"));
+            }
+            parsed_modifiers.push(modifier);
+        }
+
+        Ok(Self {
+            modifiers: parsed_modifiers,
+            key: key.to_owned(),
+        })
+    }
+}
+
 /// Shortcut descriptor used to bind new shortcuts in
 /// [`GlobalShortcuts::bind_shortcuts`]
 #[derive(Clone, Serialize, Type, Debug)]
@@ -39,12 +204,33 @@ impl NewShortcut {
     }
 
     /// Sets the preferred shortcut trigger, defined as described by the
-    /// "shortcuts" XDG specification.
+    /// "shortcuts" XDG specification. See [`Trigger`].
+    ///
+    /// An empty trigger is treated as unset. The trigger is validated by
+    /// [`GlobalShortcuts::bind_shortcuts`].
     #[must_use]
     pub fn preferred_trigger<'a>(mut self, preferred_trigger: impl Into<Option<&'a str>>) -> Self {
-        self.1.preferred_trigger = preferred_trigger.into().map(ToOwned::to_owned);
+        self.1.preferred_trigger = preferred_trigger
+            .into()
+            .filter(|trigger| !trigger.is_empty())
+            .map(ToOwned::to_owned);
         self
     }
+
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(trigger) = &self.1.preferred_trigger {
+            Trigger::from_str(trigger)?;
+        }
+        Ok(())
+    }
+
+    /// The preferred shortcut trigger, if any.
+    pub fn trigger(&self) -> Option<Trigger> {
+        self.1
+            .preferred_trigger
+            .as_deref()
+            .and_then(|trigger| Trigger::from_str(trigger).ok())
+    }
 }
 
 #[derive(Clone, DeserializeDict, Type, Debug, Default)]
@@ -163,6 +349,19 @@ impl Activated {
     pub fn timestamp(&self) -> Duration {
         Duration::from_millis(self.2)
     }
+
+    /// Optional information.
+    pub fn options(&self) -> &HashMap<String, OwnedValue> {
+        &self.3
+    }
+
+    /// The token to pass to the toolkit or compositor to focus a window
+    /// presented in response to the shortcut, if the portal provided one.
+    pub fn activation_token(&self) -> Option<String> {
+        self.3
+            .get("activation_token")
+            .and_then(|token| String::try_from(token.clone()).ok())
+    }
 }
 
 /// Notifies that a shortcut is not active anymore.
@@ -184,6 +383,11 @@ impl Deactivated {
     pub fn timestamp(&self) -> Duration {
         Duration::from_millis(self.2)
     }
+
+    /// Optional information.
+    pub fn options(&self) -> &HashMap<String, OwnedValue> {
+        &self.3
+    }
 }
 
 /// Indicates that the information associated with some of the shortcuts has
@@ -215,6 +419,13 @@ impl<'a> GlobalShortcuts<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`GlobalShortcuts::new`], failing with [`Error::NotSupported`]
+    /// if the portal doesn't provide `org.freedesktop.portal.GlobalShortcuts`.
+    pub async fn new_checked() -> Result<GlobalShortcuts<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.GlobalShortcuts").await?;
+        Ok(Self(proxy))
+    }
+
     /// Create a global shortcuts session.
     ///
     /// # Specifications
@@ -227,7 +438,7 @@ impl<'a> GlobalShortcuts<'a> {
             self.0
                 .request::<CreateSession>(&options.handle_token, "CreateSession", &options)
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future(),
+            Session::from_unique_name(&self.0, &options.session_handle_token).into_future(),
         )?;
         assert_eq!(proxy.path(), &request.response()?.session_handle.as_ref());
         Ok(proxy)
@@ -235,6 +446,11 @@ impl<'a> GlobalShortcuts<'a> {
 
     /// Bind the shortcuts.
     ///
+    /// # Errors
+    ///
+    /// Returns [`PortalError::InvalidArgument`] if a preferred trigger is
+    /// malformed, see [`Trigger`].
+    ///
     /// # Specifications
     ///
     /// See also [`BindShortcuts`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-GlobalShortcuts.BindShortcuts).
@@ -245,6 +461,9 @@ impl<'a> GlobalShortcuts<'a> {
         shortcuts: &[NewShortcut],
         parent_window: &WindowIdentifier,
     ) -> Result<Request<BindShortcuts>, Error> {
+        for shortcut in shortcuts {
+            shortcut.validate()?;
+        }
         let options = BindShortcutsOptions::default();
         self.0
             .request(
@@ -255,6 +474,68 @@ impl<'a> GlobalShortcuts<'a> {
             .await
     }
 
+    /// Replace the shortcuts bound to a session.
+    ///
+    /// The portal doesn't allow unbinding shortcuts, nor binding them again
+    /// in the same session. Instead the shortcuts are bound to a new session,
+    /// which replaces `session` once the binding succeeded. Pass the
+    /// currently bound shortcuts without the ones to remove to unbind them.
+    ///
+    /// `session` is only closed on success, it keeps its shortcuts if any
+    /// step fails.
+    ///
+    /// # Returns
+    ///
+    /// The new session along with the bind request.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if the backend ended the binding of the
+    /// new session, for example because it doesn't allow several sessions.
+    /// Cancelling the dialog fails with
+    /// [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled)
+    /// as usual.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`BindShortcuts`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-GlobalShortcuts.BindShortcuts).
+    pub async fn rebind_shortcuts(
+        &self,
+        session: &Session<'_>,
+        shortcuts: &[NewShortcut],
+        parent_window: &WindowIdentifier,
+    ) -> Result<(Session<'a>, Request<BindShortcuts>), Error> {
+        let new_session = self.create_session().await?;
+        let request = match self
+            .bind_shortcuts(&new_session, shortcuts, parent_window)
+            .await
+        {
+            Ok(request) => match request.response_raw() {
+                Ok(_) => Ok(request),
+                Err(err) if err.is_ended() => Err(Error::NotSupported {
+                    interface: self.0.interface().to_string(),
+                    member: "BindShortcuts".to_owned(),
+                }),
+                Err(err) => Err(err),
+            },
+            Err(err) => Err(err),
+        };
+        match request {
+            Ok(request) => {
+                // The shortcuts are bound to the new session already
+                if let Err(_err) = session.close().await {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!("Failed to close the replaced session: {}", _err);
+                }
+                Ok((new_session, request))
+            }
+            Err(err) => {
+                let _ = new_session.close().await;
+                Err(err)
+            }
+        }
+    }
+
     /// Lists all shortcuts.
     ///
     /// # Specifications
@@ -281,6 +562,18 @@ impl<'a> GlobalShortcuts<'a> {
         self.0.signal("Activated").await
     }
 
+    /// A stream of the shortcuts becoming active.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Activated`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-signal-org-freedesktop-portal-GlobalShortcuts.Activated).
+    #[doc(alias = "Activated")]
+    pub async fn receive_activated_stream(
+        &self,
+    ) -> Result<impl Stream<Item = Activated> + Send + Unpin + 'a, Error> {
+        self.0.signals("Activated").await
+    }
+
     /// Signal emitted when shortcut is not active anymore.
     ///
     /// # Specifications
@@ -291,6 +584,18 @@ impl<'a> GlobalShortcuts<'a> {
         self.0.signal("Deactivated").await
     }
 
+    /// A stream of the shortcuts that are not active anymore.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Deactivated`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-signal-org-freedesktop-portal-GlobalShortcuts.Deactivated).
+    #[doc(alias = "Deactivated")]
+    pub async fn receive_deactivated_stream(
+        &self,
+    ) -> Result<impl Stream<Item = Deactivated> + Send + Unpin + 'a, Error> {
+        self.0.signals("Deactivated").await
+    }
+
     /// Signal emitted when information associated with some of the shortcuts
     /// has changed.
     ///
@@ -301,4 +606,88 @@ impl<'a> GlobalShortcuts<'a> {
     pub async fn receive_shortcuts_changed(&self) -> Result<ShortcutsChanged, Error> {
         self.0.signal("ShortcutsChanged").await
     }
+
+    /// A stream of the changes to the information associated with some of the
+    /// shortcuts.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`ShortcutsChanged`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-signal-org-freedesktop-portal-GlobalShortcuts.ShortcutsChanged).
+    #[doc(alias = "ShortcutsChanged")]
+    pub async fn receive_shortcuts_changed_stream(
+        &self,
+    ) -> Result<impl Stream<Item = ShortcutsChanged> + Send + Unpin + 'a, Error> {
+        self.0.signals("ShortcutsChanged").await
+    }
+}
+
+impl AsProxy for GlobalShortcuts<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for GlobalShortcuts<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_trigger() {
+        let trigger = Trigger::from_str("CTRL+a").unwrap();
+        assert_eq!(trigger.modifiers(), &[Modifier::Ctrl]);
+        assert_eq!(trigger.key(), "a");
+
+        let trigger = Trigger::from_str("LOGO+SHIFT+Return").unwrap();
+        assert_eq!(trigger.modifiers(), &[Modifier::Logo, Modifier::Shift]);
+        assert_eq!(trigger.key(), "Return");
+        assert_eq!(trigger.to_string(), "LOGO+SHIFT+Return");
+
+        let trigger = Trigger::from_str("Launch1").unwrap();
+        assert!(trigger.modifiers().is_empty());
+        assert_eq!(trigger.key(), "Launch1");
+
+        for invalid in [
+            "",
+            "CTRL+",
+            "+a",
+            "CTRL++a",
+            "HYPER+a",
+            "CTRL+CTRL+a",
+            "CTRL+a b",
+        ] {
+            assert!(Trigger::from_str(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn activated_activation_token() {
+        let path =
+            OwnedObjectPath::try_from("/org/freedesktop/portal/desktop/session/1_1/t").unwrap();
+        let mut options = HashMap::new();
+        let activated = Activated(path.clone(), "id".to_owned(), 0, options.clone());
+        assert!(activated.activation_token().is_none());
+
+        options.insert(
+            "activation_token".to_owned(),
+            OwnedValue::from(zbus::zvariant::Value::from("token")),
+        );
+        let activated = Activated(path, "id".to_owned(), 0, options);
+        assert_eq!(activated.activation_token().as_deref(), Some("token"));
+    }
+
+    #[test]
+    fn new_shortcut_trigger() {
+        let shortcut = NewShortcut::new("id", "description").preferred_trigger("CTRL+a");
+        assert!(shortcut.validate().is_ok());
+        assert_eq!(shortcut.trigger().unwrap().key(), "a");
+
+        let shortcut = NewShortcut::new("id", "description").preferred_trigger("");
+        assert!(shortcut.validate().is_ok());
+        assert!(shortcut.trigger().is_none());
+
+        let shortcut = NewShortcut::new("id", "description").preferred_trigger("CTRL+");
+        assert!(shortcut.validate().is_err());
+    }
 }
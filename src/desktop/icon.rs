@@ -5,13 +5,16 @@ use serde::{
 };
 use zbus::zvariant::{self, OwnedValue, Type};
 
-use crate::Error;
+use crate::{Error, PortalError};
 
-#[derive(Debug, PartialEq, Eq, Type)]
+#[derive(Debug, Clone, PartialEq, Eq, Type)]
 #[zvariant(signature = "(sv)")]
 /// A representation of an icon.
 ///
-/// Used by both the Notification & Dynamic launcher portals.
+/// Used by both the Notification & Dynamic launcher portals, and serialized
+/// the same way as a `GIcon`, e.g. `('bytes', <[byte 0x89, ...]>)`, by all of
+/// them. The methods taking an icon accept anything converting into one, like
+/// a [`url::Url`] or the icon bytes.
 pub enum Icon {
     /// An icon URI.
     Uri(url::Url),
@@ -22,6 +25,9 @@ pub enum Icon {
 }
 
 impl Icon {
+    /// The largest icon data accepted by the dynamic launcher portal, 4 MiB.
+    pub const MAX_LAUNCHER_BYTES: usize = 4 * 1024 * 1024;
+
     /// Create an icon from a list of names.
     pub fn with_names<N>(names: impl IntoIterator<Item = N>) -> Self
     where
@@ -29,6 +35,71 @@ impl Icon {
     {
         Self::Names(names.into_iter().map(|name| name.to_string()).collect())
     }
+
+    /// Create an icon from its bytes, for example the content of a PNG file.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self::Bytes(bytes)
+    }
+
+    /// Checks the icon can be used by the dynamic launcher portal, which
+    /// would otherwise reject it with a vague error.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortalError::InvalidArgument`] for empty icon bytes, or
+    /// bytes larger than [`Icon::MAX_LAUNCHER_BYTES`].
+    pub fn validate_for_launcher(&self) -> Result<(), Error> {
+        match self {
+            Self::Bytes(bytes) if bytes.is_empty() => {
+                Err(PortalError::InvalidArgument("The icon is empty".to_owned()).into())
+            }
+            Self::Bytes(bytes) if bytes.len() > Self::MAX_LAUNCHER_BYTES => {
+                Err(PortalError::InvalidArgument(format!(
+                    "The icon is {} bytes, larger than the {} bytes limit",
+                    bytes.len(),
+                    Self::MAX_LAUNCHER_BYTES
+                ))
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The icon type and data, as in the `(sv)` serialized form of a
+    /// `GIcon`.
+    fn inner(&self) -> (&'static str, zvariant::Value<'_>) {
+        match self {
+            Self::Uri(uri) => ("file", zvariant::Value::from(uri.as_str())),
+            Self::Names(names) => (
+                "themed",
+                zvariant::Value::from(names.iter().map(String::as_str).collect::<Vec<_>>()),
+            ),
+            Self::Bytes(bytes) => ("bytes", zvariant::Value::from(bytes)),
+        }
+    }
+
+    /// The icon wrapped in a variant, for the methods taking a `v` argument.
+    pub(crate) fn as_value(&self) -> zvariant::Value<'_> {
+        zvariant::Value::from(self.inner())
+    }
+}
+
+impl From<url::Url> for Icon {
+    fn from(uri: url::Url) -> Self {
+        Self::Uri(uri)
+    }
+}
+
+impl From<Vec<u8>> for Icon {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+impl From<&[u8]> for Icon {
+    fn from(bytes: &[u8]) -> Self {
+        Self::Bytes(bytes.to_vec())
+    }
 }
 
 impl Serialize for Icon {
@@ -36,31 +107,10 @@ impl Serialize for Icon {
     where
         S: serde::Serializer,
     {
+        let (type_, data) = self.inner();
         let mut tuple = serializer.serialize_tuple(2)?;
-        match self {
-            Self::Uri(uri) => {
-                tuple.serialize_element("file")?;
-                tuple.serialize_element(&zvariant::Value::from(uri.as_str()))?;
-            }
-            Self::Names(names) => {
-                tuple.serialize_element("themed")?;
-                let mut array = zvariant::Array::new(String::signature());
-                for name in names.iter() {
-                    // Safe to unwrap because we are sure it is of the correct type
-                    array.append(zvariant::Value::from(name)).unwrap();
-                }
-                tuple.serialize_element(&zvariant::Value::from(array))?;
-            }
-            Self::Bytes(bytes) => {
-                tuple.serialize_element("bytes")?;
-                let mut array = zvariant::Array::new(u8::signature());
-                for byte in bytes.iter() {
-                    // Safe to unwrap because we are sure it is of the correct type
-                    array.append(zvariant::Value::from(*byte)).unwrap();
-                }
-                tuple.serialize_element(&zvariant::Value::from(array))?;
-            }
-        }
+        tuple.serialize_element(type_)?;
+        tuple.serialize_element(&data)?;
         tuple.end()
     }
 }
@@ -112,8 +162,7 @@ impl TryFrom<OwnedValue> for Icon {
                     .downcast_ref::<zvariant::Str>()
                     .unwrap()
                     .to_owned();
-                let uri = url::Url::parse(uri_str.as_str())
-                    .map_err(|_| crate::Error::ParseError("Failed to parse uri"))?;
+                let uri = url::Url::parse(uri_str.as_str())?;
                 Ok(Self::Uri(uri))
             }
             "bytes" => {
@@ -154,7 +203,7 @@ mod test {
     fn serialize_deserialize() {
         let ctxt = Context::<LE>::new_dbus(0);
 
-        let icon = Icon::with_names(&["dialog-symbolic"]);
+        let icon = Icon::with_names(["dialog-symbolic"]);
 
         let encoded = to_bytes(ctxt, &icon).unwrap();
         let decoded: Icon = from_slice(&encoded, ctxt).unwrap();
@@ -170,4 +219,55 @@ mod test {
         let decoded: Icon = from_slice(&encoded, ctxt).unwrap();
         assert_eq!(decoded, icon);
     }
+
+    #[test]
+    fn conversions() {
+        let uri = url::Url::parse("file:///icon.png").unwrap();
+        assert_eq!(Icon::from(uri.clone()), Icon::Uri(uri));
+        assert_eq!(Icon::from(vec![1, 2]), Icon::Bytes(vec![1, 2]));
+        assert_eq!(Icon::from(&[1u8, 2][..]), Icon::Bytes(vec![1, 2]));
+    }
+
+    #[test]
+    fn bytes_icon() {
+        let icon = Icon::from_bytes(vec![0x89, b'P', b'N', b'G']);
+        assert_eq!(icon, Icon::Bytes(vec![0x89, b'P', b'N', b'G']));
+
+        let zvariant::Value::Structure(structure) = icon.as_value() else {
+            panic!("The icon isn't serialized as a structure");
+        };
+        assert_eq!(structure.signature(), "(sv)");
+        let fields = structure.fields();
+        assert_eq!(fields[0], zvariant::Value::from("bytes"));
+        let zvariant::Value::Value(data) = &fields[1] else {
+            panic!("The icon data isn't a variant");
+        };
+        assert_eq!(data.value_signature(), "ay");
+
+        let value = OwnedValue::from(icon.as_value());
+        assert_eq!(Icon::try_from(value).unwrap(), icon);
+    }
+
+    #[test]
+    fn validate_for_launcher() {
+        assert!(Icon::with_names(["web-browser"])
+            .validate_for_launcher()
+            .is_ok());
+        assert!(Icon::from_bytes(vec![0x89, b'P', b'N', b'G'])
+            .validate_for_launcher()
+            .is_ok());
+        assert!(Icon::from_bytes(vec![0; Icon::MAX_LAUNCHER_BYTES])
+            .validate_for_launcher()
+            .is_ok());
+
+        for icon in [
+            Icon::from_bytes(Vec::new()),
+            Icon::from_bytes(vec![0; Icon::MAX_LAUNCHER_BYTES + 1]),
+        ] {
+            assert!(matches!(
+                icon.validate_for_launcher(),
+                Err(Error::Portal(PortalError::InvalidArgument(_)))
+            ));
+        }
+    }
 }
@@ -42,10 +42,19 @@ use enumflags2::{bitflags, BitFlags};
 use futures_util::TryFutureExt;
 use serde::Deserialize;
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{DeserializeDict, OwnedObjectPath, SerializeDict, Type};
+use zbus::zvariant::{DeserializeDict, ObjectPath, OwnedObjectPath, SerializeDict, Type};
 
 use super::{HandleToken, Request, Session};
-use crate::{proxy::Proxy, Error, WindowIdentifier};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalError, PortalProxy, WindowIdentifier,
+};
+
+/// The reason used when none is passed to [`InhibitProxy::inhibit`].
+///
+/// It isn't translated, applications should pass a localized reason instead
+/// whenever possible.
+pub const DEFAULT_REASON: &str = "Application is busy";
 
 #[derive(SerializeDict, Type, Debug, Default)]
 /// Specified options for a [`InhibitProxy::create_monitor`] request.
@@ -110,6 +119,11 @@ struct State {
 pub struct InhibitState(OwnedObjectPath, State);
 
 impl InhibitState {
+    /// The session that the state belongs to.
+    pub fn session_handle(&self) -> ObjectPath<'_> {
+        self.0.as_ref()
+    }
+
     /// Whether screensaver is active or not.
     pub fn screensaver_active(&self) -> bool {
         self.1.screensaver_active
@@ -137,6 +151,31 @@ pub enum SessionState {
     Ending = 3,
 }
 
+/// An inhibition that is removed once dropped, see
+/// [`InhibitProxy::inhibit_guarded`].
+///
+/// **Note** when dropped, the inhibition is removed in the background without
+/// waiting for the portal to handle it, as `Drop` can't be async. Use
+/// [`InhibitGuard::close`] to know when it is done.
+#[derive(Debug)]
+pub struct InhibitGuard(Option<Request<()>>);
+
+impl InhibitGuard {
+    /// Removes the inhibition.
+    pub async fn close(mut self) -> Result<(), Error> {
+        // The request is only taken out when consuming the guard
+        self.0.take().unwrap().close().await
+    }
+}
+
+impl Drop for InhibitGuard {
+    fn drop(&mut self) {
+        if let Some(request) = self.0.take() {
+            request.close_in_background();
+        }
+    }
+}
+
 /// The interface lets sandboxed applications inhibit the user session from
 /// ending, suspending, idling or getting switched away.
 ///
@@ -152,6 +191,13 @@ impl<'a> InhibitProxy<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`InhibitProxy::new`], failing with [`Error::NotSupported`] if
+    /// the portal doesn't provide `org.freedesktop.portal.Inhibit`.
+    pub async fn new_checked() -> Result<InhibitProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Inhibit").await?;
+        Ok(Self(proxy))
+    }
+
     /// Creates a monitoring session.
     /// While this session is active, the caller will receive `state_changed`
     /// signals with updates on the session state.
@@ -175,7 +221,7 @@ impl<'a> InhibitProxy<'a> {
             self.0
                 .request::<CreateMonitor>(&options.handle_token, "CreateMonitor", body)
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future(),
+            Session::from_unique_name(&self.0, &options.session_handle_token).into_future(),
         )?;
         assert_eq!(proxy.path().as_str(), &monitor.response()?.session_handle);
         Ok(proxy)
@@ -187,21 +233,27 @@ impl<'a> InhibitProxy<'a> {
     ///
     /// * `identifier` - The application window identifier.
     /// * `flags` - The flags determine what changes are inhibited.
-    /// * `reason` - User-visible reason for the inhibition.
+    /// * `reason` - User-visible reason for the inhibition, or [`None`] for
+    ///   [`DEFAULT_REASON`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PortalError::InvalidArgument`] if the reason is empty, as
+    /// some backends would display a blank line.
     ///
     /// # Specifications
     ///
     /// See also [`Inhibit`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-Inhibit.Inhibit).
     #[doc(alias = "Inhibit")]
     #[doc(alias = "xdp_portal_session_inhibit")]
-    pub async fn inhibit(
+    pub async fn inhibit<'r>(
         &self,
         identifier: &WindowIdentifier,
         flags: BitFlags<InhibitFlags>,
-        reason: &str,
+        reason: impl Into<Option<&'r str>>,
     ) -> Result<Request<()>, Error> {
         let options = InhibitOptions {
-            reason: Some(reason.to_owned()),
+            reason: Some(validate_reason(reason.into())?),
             handle_token: Default::default(),
         };
         self.0
@@ -213,6 +265,39 @@ impl<'a> InhibitProxy<'a> {
             .await
     }
 
+    /// Same as [`InhibitProxy::inhibit`], but the inhibition is removed once
+    /// the returned [`InhibitGuard`] is dropped.
+    pub async fn inhibit_guarded<'r>(
+        &self,
+        identifier: &WindowIdentifier,
+        flags: BitFlags<InhibitFlags>,
+        reason: impl Into<Option<&'r str>>,
+    ) -> Result<InhibitGuard, Error> {
+        let request = self.inhibit(identifier, flags, reason).await?;
+        Ok(InhibitGuard(Some(request)))
+    }
+
+    /// Prevents the session from becoming idle, for example to keep the
+    /// screen on during a video playback, until the returned [`InhibitGuard`]
+    /// is dropped.
+    ///
+    /// Same as [`InhibitProxy::inhibit_guarded`] with only
+    /// [`InhibitFlags::Idle`].
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - The application window identifier.
+    /// * `reason` - User-visible reason for the inhibition, or [`None`] for
+    ///   [`DEFAULT_REASON`].
+    pub async fn inhibit_idle<'r>(
+        &self,
+        identifier: &WindowIdentifier,
+        reason: impl Into<Option<&'r str>>,
+    ) -> Result<InhibitGuard, Error> {
+        self.inhibit_guarded(identifier, InhibitFlags::Idle.into(), reason)
+            .await
+    }
+
     /// Signal emitted when the session state changes.
     ///
     /// # Specifications
@@ -243,3 +328,37 @@ impl<'a> InhibitProxy<'a> {
         self.0.call("QueryEndResponse", &(session)).await
     }
 }
+
+impl AsProxy for InhibitProxy<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for InhibitProxy<'_> {}
+
+fn validate_reason(reason: Option<&str>) -> Result<String, PortalError> {
+    match reason {
+        Some(reason) if reason.trim().is_empty() => Err(PortalError::InvalidArgument(
+            "The inhibition reason is empty".to_owned(),
+        )),
+        Some(reason) => Ok(reason.to_owned()),
+        None => Ok(DEFAULT_REASON.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reason() {
+        assert_eq!(validate_reason(None).unwrap(), DEFAULT_REASON);
+        assert_eq!(
+            validate_reason(Some("Playing a video")).unwrap(),
+            "Playing a video"
+        );
+        assert!(validate_reason(Some("")).is_err());
+        assert!(validate_reason(Some("  ")).is_err());
+    }
+}
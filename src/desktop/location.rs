@@ -33,13 +33,17 @@ use std::fmt::Debug;
 
 use futures_util::TryFutureExt;
 use serde::Deserialize;
-use serde_repr::Serialize_repr;
-use zbus::zvariant::{DeserializeDict, OwnedObjectPath, SerializeDict, Type};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use zbus::zvariant::{DeserializeDict, ObjectPath, OwnedObjectPath, SerializeDict, Type};
 
-use super::{HandleToken, Request, Session};
-use crate::{proxy::Proxy, Error, WindowIdentifier};
+use super::{HandleToken, Request, ResponseError, Session};
+use crate::{
+    documents::PermissionStore,
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalError, PortalProxy, WindowIdentifier,
+};
 
-#[derive(Serialize_repr, PartialEq, Eq, Clone, Copy, Debug, Type)]
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Clone, Copy, Debug, Type)]
 #[doc(alias = "XdpLocationAccuracy")]
 #[repr(u32)]
 /// The accuracy of the location.
@@ -88,11 +92,23 @@ struct SessionStartOptions {
     handle_token: HandleToken,
 }
 
+#[derive(DeserializeDict, Type, Debug)]
+/// A response to a [`LocationProxy::start`] request.
+#[zvariant(signature = "dict")]
+struct SessionStart {
+    accuracy: Option<Accuracy>,
+}
+
 #[derive(Deserialize, Type)]
 /// The response received on a `location_updated` signal.
 pub struct Location(OwnedObjectPath, LocationInner);
 
 impl Location {
+    /// The session that the location update belongs to.
+    pub fn session_handle(&self) -> ObjectPath<'_> {
+        self.0.as_ref()
+    }
+
     /// The accuracy, in meters.
     pub fn accuracy(&self) -> f64 {
         self.1.accuracy
@@ -202,6 +218,13 @@ impl<'a> LocationProxy<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`LocationProxy::new`], failing with [`Error::NotSupported`] if
+    /// the portal doesn't provide `org.freedesktop.portal.Location`.
+    pub async fn new_checked() -> Result<LocationProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Location").await?;
+        Ok(Self(proxy))
+    }
+
     /// Signal emitted when the user location is updated.
     ///
     /// # Specifications
@@ -213,6 +236,24 @@ impl<'a> LocationProxy<'a> {
         self.0.signal("LocationUpdated").await
     }
 
+    /// Whether the location can be requested by the application.
+    ///
+    /// Returns `false` if the running portal doesn't provide the interface,
+    /// or if the access was previously denied to the application, as
+    /// recorded in the permission store. The permission store isn't always
+    /// reachable from the sandbox, the access is assumed not to be denied in
+    /// that case.
+    ///
+    /// **Note** the availability can change at runtime, for example when the
+    /// user resets the permissions of the application. It is only a hint to
+    /// hide the location features,
+    /// [`start_granted()`][`LocationProxy::start_granted`] can still fail with
+    /// [`PortalError::NotAllowed`], for example if the location services are
+    /// disabled globally.
+    pub async fn is_available(&self) -> Result<bool, Error> {
+        Ok(self.0.is_exported().await? && !is_denied().await)
+    }
+
     /// Create a location session.
     ///
     /// # Arguments
@@ -223,6 +264,12 @@ impl<'a> LocationProxy<'a> {
     /// * `accuracy` - Sets the location accuracy, default to
     ///   [`Accuracy::Exact`].
     ///
+    /// # Errors
+    ///
+    /// Returns [`PortalError::NotAllowed`] instead of the error of the portal
+    /// if the access to the location was previously denied to the
+    /// application, see [`is_available()`][`LocationProxy::is_available`].
+    ///
     /// # Specifications
     ///
     /// See also [`CreateSession`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-Location.CreateSession).
@@ -239,12 +286,18 @@ impl<'a> LocationProxy<'a> {
             accuracy,
             ..Default::default()
         };
-        let (path, proxy) = futures_util::try_join!(
+        let result = futures_util::try_join!(
             self.0
                 .call::<OwnedObjectPath>("CreateSession", &(options))
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future(),
-        )?;
+            Session::from_unique_name(&self.0, &options.session_handle_token).into_future(),
+        );
+        let (path, proxy) = match result {
+            Ok(result) => result,
+            // The error of a denied access isn't specified, look it up
+            Err(_) if is_denied().await => return Err(denied()),
+            Err(err) => return Err(err),
+        };
         assert_eq!(proxy.path(), &path.into_inner());
         Ok(proxy)
     }
@@ -277,4 +330,87 @@ impl<'a> LocationProxy<'a> {
             )
             .await
     }
+
+    /// Same as [`start()`][`LocationProxy::start`], waiting for the user to
+    /// grant the access to their location.
+    ///
+    /// Once started, the location updates are received with
+    /// [`receive_location_updated()`][`LocationProxy::receive_location_updated`],
+    /// the first one is only emitted once a location fix is available.
+    ///
+    /// # Returns
+    ///
+    /// The accuracy granted by the user, if reported by the portal.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PortalError::NotAllowed`] if the user denied the access to
+    /// their location. A dismissed dialog fails with
+    /// [`ResponseError::Cancelled`] instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - A [`Session`], created with
+    ///   [`create_session()`][`LocationProxy::create_session`].
+    /// * `identifier` - Identifier for the application window.
+    #[doc(alias = "Start")]
+    pub async fn start_granted(
+        &self,
+        session: &Session<'_>,
+        identifier: &WindowIdentifier,
+    ) -> Result<Option<Accuracy>, Error> {
+        let options = SessionStartOptions::default();
+        let response = self
+            .0
+            .request::<SessionStart>(
+                &options.handle_token,
+                "Start",
+                &(session, &identifier, &options),
+            )
+            .await?
+            .response();
+        match response {
+            // The portal ends the request when the access isn't granted
+            Ok(SessionStart {
+                accuracy: Some(Accuracy::None),
+            })
+            | Err(Error::Response {
+                code: ResponseError::Other,
+                ..
+            }) => Err(denied()),
+            Ok(SessionStart { accuracy }) => Ok(accuracy),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl AsProxy for LocationProxy<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for LocationProxy<'_> {}
+
+fn denied() -> Error {
+    PortalError::NotAllowed("The access to the location was denied".to_owned()).into()
+}
+
+/// Whether the user denied the access to their location to the application,
+/// the portal stores the granted accuracy as the first permission.
+async fn is_denied() -> bool {
+    let Some(app_id) = crate::sandbox::app_id().await else {
+        return false;
+    };
+    let Ok(store) = PermissionStore::new().await else {
+        return false;
+    };
+    store
+        .permission("location", "location", app_id.as_ref())
+        .await
+        .is_ok_and(|permissions| {
+            permissions
+                .first()
+                .is_some_and(|accuracy| accuracy == "NONE")
+        })
 }
@@ -11,7 +11,10 @@
 //! }
 //! ```
 
-use crate::{proxy::Proxy, Error};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
 /// The interface provides information about low system memory to sandboxed
 /// applications.
@@ -31,6 +34,13 @@ impl<'a> MemoryMonitor<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`MemoryMonitor::new`], failing with [`Error::NotSupported`] if
+    /// the portal doesn't provide `org.freedesktop.portal.MemoryMonitor`.
+    pub async fn new_checked() -> Result<MemoryMonitor<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.MemoryMonitor").await?;
+        Ok(Self(proxy))
+    }
+
     /// Signal emitted when a particular low memory situation happens
     /// with 0 being the lowest level of memory availability warning, and 255
     /// being the highest.
@@ -43,3 +53,11 @@ impl<'a> MemoryMonitor<'a> {
         self.0.signal("LowMemoryWarning").await
     }
 }
+
+impl AsProxy for MemoryMonitor<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for MemoryMonitor<'_> {}
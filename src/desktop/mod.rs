@@ -3,11 +3,15 @@ pub(crate) mod request;
 mod session;
 pub(crate) use self::handle_token::HandleToken;
 pub use self::{
-    request::{Request, Response, ResponseError},
-    session::Session,
+    request::{responses_for, PendingRequest, Request, Response, ResponseError},
+    session::{PortalSession, Session, SessionGuard},
 };
+mod color;
+pub use color::Color;
 mod icon;
 pub use icon::Icon;
+#[cfg(feature = "wayland")]
+mod outputs;
 
 pub mod account;
 pub mod background;
@@ -39,6 +43,8 @@ pub mod print;
 /// Proxy information.
 pub mod proxy_resolver;
 pub mod realtime;
+/// Register the application ID of a host application.
+pub mod registry;
 /// Start a remote desktop session and interact with it.
 pub mod remote_desktop;
 pub mod screencast;
@@ -22,9 +22,12 @@ use std::fmt;
 use serde_repr::Deserialize_repr;
 use zbus::zvariant::{DeserializeDict, Type};
 
-use crate::{proxy::Proxy, Error};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
-#[derive(DeserializeDict, Type, Debug)]
+#[derive(DeserializeDict, Type, Debug, Clone, Copy, PartialEq, Eq)]
 /// The network status, composed of the availability, metered & connectivity
 #[zvariant(signature = "dict")]
 pub struct NetworkStatus {
@@ -97,6 +100,13 @@ impl<'a> NetworkMonitor<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`NetworkMonitor::new`], failing with [`Error::NotSupported`] if
+    /// the portal doesn't provide `org.freedesktop.portal.NetworkMonitor`.
+    pub async fn new_checked() -> Result<NetworkMonitor<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.NetworkMonitor").await?;
+        Ok(Self(proxy))
+    }
+
     /// Returns whether the given hostname is believed to be reachable.
     ///
     /// # Arguments
@@ -151,6 +161,13 @@ impl<'a> NetworkMonitor<'a> {
 
     /// Returns the three values all at once.
     ///
+    /// Prefer it over [`Self::is_available`], [`Self::is_metered`] and
+    /// [`Self::connectivity`] when more than one of them is needed, as the
+    /// values are read in a single call and can't be observed in an
+    /// inconsistent state while the network changes.
+    ///
+    /// Added in version 3 of the interface, see [`Error::NotSupported`].
+    ///
     /// # Specifications
     ///
     /// See also [`GetStatus`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-NetworkMonitor.GetStatus).
@@ -169,3 +186,11 @@ impl<'a> NetworkMonitor<'a> {
         self.0.signal("changed").await
     }
 }
+
+impl AsProxy for NetworkMonitor<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for NetworkMonitor<'_> {}
@@ -44,13 +44,16 @@
 //! }
 //! ```
 
-use std::{fmt, str::FromStr};
+use std::{collections::HashMap, fmt, str::FromStr, time::Duration};
 
 use serde::{self, Deserialize, Serialize};
 use zbus::zvariant::{OwnedValue, SerializeDict, Type, Value};
 
 use super::Icon;
-use crate::{proxy::Proxy, Error};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq, Type)]
 #[zvariant(signature = "s")]
@@ -122,6 +125,9 @@ pub struct Notification {
     title: String,
     /// User-visible string to display as the body.
     body: Option<String>,
+    /// User-visible string to display as the body, with markup.
+    #[zvariant(rename = "markup-body")]
+    markup_body: Option<String>,
     /// Serialized icon (e.g using gio::Icon::serialize).
     icon: Option<Icon>,
     /// The priority for the notification.
@@ -135,6 +141,11 @@ pub struct Notification {
     default_action_target: Option<OwnedValue>,
     /// Array of buttons to add to the notification.
     buttons: Option<Vec<Button>>,
+    /// The progress of the operation the notification is about, in percent.
+    progress: Option<u32>,
+    /// The time after which the notification is dismissed, in milliseconds.
+    #[zvariant(rename = "expire-timeout")]
+    expire_timeout: Option<u32>,
 }
 
 impl Notification {
@@ -147,25 +158,62 @@ impl Notification {
         Self {
             title: title.to_owned(),
             body: None,
+            markup_body: None,
             priority: None,
             icon: None,
             default_action: None,
             default_action_target: None,
             buttons: None,
+            progress: None,
+            expire_timeout: None,
         }
     }
 
     /// Sets the notification body.
     #[must_use]
     pub fn body<'a>(mut self, body: impl Into<Option<&'a str>>) -> Self {
-        self.body = body.into().map(ToOwned::to_owned);
+        let body = body.into().map(ToOwned::to_owned);
+        if self.markup_body.is_some() {
+            self.markup_body = Some(body.unwrap_or_default());
+        } else {
+            self.body = body;
+        }
+        self
+    }
+
+    /// Sets whether the notification body contains markup.
+    ///
+    /// The markup is only supported starting with version 2 of the portal, it
+    /// is stripped from the body when sending the notification to older
+    /// versions.
+    #[must_use]
+    pub fn markup_body(mut self, markup_body: bool) -> Self {
+        if markup_body {
+            self.markup_body = Some(self.body.take().unwrap_or_default());
+        } else if let Some(body) = self.markup_body.take() {
+            self.body = Some(body).filter(|body| !body.is_empty());
+        }
+        self
+    }
+
+    fn for_version(mut self, version: u32) -> Self {
+        if let Some(markup_body) = self.markup_body.take() {
+            if markup_body.is_empty() {
+                return self;
+            }
+            if version >= 2 {
+                self.markup_body = Some(markup_body);
+            } else {
+                self.body = Some(strip_markup(&markup_body));
+            }
+        }
         self
     }
 
     /// Sets an icon to the notification.
     #[must_use]
-    pub fn icon(mut self, icon: impl Into<Option<Icon>>) -> Self {
-        self.icon = icon.into();
+    pub fn icon(mut self, icon: impl Into<Icon>) -> Self {
+        self.icon = Some(icon.into());
         self
     }
 
@@ -193,6 +241,44 @@ impl Notification {
         self
     }
 
+    /// Sets the progress, in percent, of the operation the notification is
+    /// about, values above 100 are clamped.
+    ///
+    /// Sending again a notification with the same ID and an updated progress
+    /// updates the displayed one, see [`NotificationProxy::add_notification`].
+    ///
+    /// **Note** the `progress` key isn't part of the portal specification,
+    /// only some backends handle it. It is only sent if the portal advertises
+    /// it in its [`NotificationProxy::supported_options`], otherwise it is
+    /// dropped with a warning and the notification is displayed without a
+    /// progress bar.
+    #[must_use]
+    pub fn progress(mut self, progress: impl Into<Option<u8>>) -> Self {
+        self.progress = progress.into().map(|progress| u32::from(progress.min(100)));
+        self
+    }
+
+    /// Sets the time after which the notification is dismissed, for short
+    /// lived status notifications. Durations above `u32::MAX` milliseconds
+    /// are clamped.
+    ///
+    /// It only controls how long the notification is kept around, a
+    /// notification with a short timeout still plays the sound and shows the
+    /// banner the backend uses for its [`Priority`].
+    ///
+    /// **Note** the `expire-timeout` key isn't part of the portal
+    /// specification, only some backends handle it. It is only sent if the
+    /// portal advertises it in its [`NotificationProxy::supported_options`],
+    /// otherwise it is dropped with a warning and the backend keeps the
+    /// notification according to its own policy.
+    #[must_use]
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.expire_timeout = timeout
+            .into()
+            .map(|timeout| u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX));
+        self
+    }
+
     /// Adds a new button to the notification.
     #[must_use]
     pub fn button(mut self, button: Button) -> Self {
@@ -206,6 +292,25 @@ impl Notification {
     }
 }
 
+/// Removes the tags from a markup text & replaces the entities.
+fn strip_markup(markup: &str) -> String {
+    let mut text = String::with_capacity(markup.len());
+    let mut in_tag = false;
+    for c in markup.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
 #[derive(SerializeDict, Type, Debug)]
 /// A notification button
 #[zvariant(signature = "dict")]
@@ -293,6 +398,13 @@ impl<'a> NotificationProxy<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`NotificationProxy::new`], failing with [`Error::NotSupported`]
+    /// if the portal doesn't provide `org.freedesktop.portal.Notification`.
+    pub async fn new_checked() -> Result<NotificationProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Notification").await?;
+        Ok(Self(proxy))
+    }
+
     /// Signal emitted when a particular action is invoked.
     ///
     /// # Specifications
@@ -304,12 +416,32 @@ impl<'a> NotificationProxy<'a> {
         self.0.signal("ActionInvoked").await
     }
 
+    /// The options supported by the portal, like the supported buttons
+    /// purposes or categories.
+    ///
+    /// Added in version 2 of the interface.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`SupportedOptions`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-property-org-freedesktop-portal-Notification.SupportedOptions).
+    #[doc(alias = "SupportedOptions")]
+    pub async fn supported_options(&self) -> Result<HashMap<String, OwnedValue>, Error> {
+        self.0.property("SupportedOptions").await
+    }
+
     /// Sends a notification.
     ///
-    /// The ID can be used to later withdraw the notification.
+    /// The ID can be used to later withdraw the notification, see
+    /// [`Self::remove_notification`].
     /// If the application reuses the same ID without withdrawing, the
     /// notification is replaced by the new one.
     ///
+    /// A body containing markup, see [`Notification::markup_body`], is sent
+    /// as a plain text one to portals older than version 2. The progress and
+    /// the timeout, see [`Notification::progress`] and
+    /// [`Notification::timeout`], are backend specific and dropped if the
+    /// portal doesn't advertise them.
+    ///
     /// # Arguments
     ///
     /// * `id` - Application-provided ID for this notification.
@@ -325,11 +457,38 @@ impl<'a> NotificationProxy<'a> {
         id: &str,
         notification: Notification,
     ) -> Result<(), Error> {
+        let mut notification = if notification.markup_body.is_some() {
+            let version = self.0.version().await.unwrap_or(1);
+            notification.for_version(version)
+        } else {
+            notification
+        };
+        if notification.progress.is_some() || notification.expire_timeout.is_some() {
+            let options = self.supported_options().await.unwrap_or_default();
+            if !options.contains_key("progress") && notification.progress.is_some() {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("Progress isn't supported, dropping it from the notification");
+                notification.progress = None;
+            }
+            if !options.contains_key("expire-timeout") && notification.expire_timeout.is_some() {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("Timeout isn't supported, dropping it from the notification");
+                notification.expire_timeout = None;
+            }
+        }
         self.0.call("AddNotification", &(id, notification)).await
     }
 
     /// Withdraws a notification.
     ///
+    /// Notification IDs are scoped per application, so an application can
+    /// only withdraw the notifications it has sent. Withdrawing an unknown ID
+    /// is not an error.
+    ///
+    /// **Note** the portal doesn't provide a way to list the notifications
+    /// that are currently displayed, applications have to keep track of the
+    /// IDs they have used themselves.
+    ///
     /// # Arguments
     ///
     /// * `id` - Application-provided ID for this notification.
@@ -343,3 +502,111 @@ impl<'a> NotificationProxy<'a> {
         self.0.call("RemoveNotification", &(id)).await
     }
 }
+
+impl AsProxy for NotificationProxy<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for NotificationProxy<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zbus::zvariant;
+
+    use super::*;
+
+    #[test]
+    fn icon() {
+        let ctxt = zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        let icon = Icon::from_bytes(vec![1, 2, 3]);
+        // Converted like the icons passed to the other portals
+        let notification = Notification::new("Title").icon(vec![1, 2, 3]);
+
+        let encoded = zvariant::to_bytes(ctxt, &notification).unwrap();
+        let mut decoded: HashMap<String, OwnedValue> =
+            zvariant::from_slice(&encoded, ctxt).unwrap();
+        let decoded = decoded.remove("icon").unwrap();
+        assert_eq!(decoded.value_signature(), "(sv)");
+        // Serialized the same way as the icons passed to the other portals
+        assert_eq!(decoded, OwnedValue::from(icon.as_value()));
+        assert_eq!(Icon::try_from(decoded).unwrap(), icon);
+    }
+
+    #[test]
+    fn markup_body() {
+        assert_eq!(
+            strip_markup("<b>Bold</b> &amp; <i>italic</i> &lt;3"),
+            "Bold & italic <3"
+        );
+
+        let notification = Notification::new("Title")
+            .body("<b>Body</b>")
+            .markup_body(true);
+        assert_eq!(notification.markup_body.as_deref(), Some("<b>Body</b>"));
+        assert!(notification.body.is_none());
+
+        let notification = Notification::new("Title")
+            .markup_body(true)
+            .body("<b>Body</b>");
+        assert_eq!(notification.markup_body.as_deref(), Some("<b>Body</b>"));
+
+        let v2 = Notification::new("Title")
+            .markup_body(true)
+            .body("<b>Body</b>")
+            .for_version(2);
+        assert_eq!(v2.markup_body.as_deref(), Some("<b>Body</b>"));
+        assert!(v2.body.is_none());
+
+        let v1 = Notification::new("Title")
+            .markup_body(true)
+            .body("<b>Body</b>")
+            .for_version(1);
+        assert!(v1.markup_body.is_none());
+        assert_eq!(v1.body.as_deref(), Some("Body"));
+
+        let plain = Notification::new("Title")
+            .body("<b>Body</b>")
+            .markup_body(true)
+            .markup_body(false);
+        assert_eq!(plain.body.as_deref(), Some("<b>Body</b>"));
+        assert!(plain.markup_body.is_none());
+    }
+
+    #[test]
+    fn progress() {
+        let ctxt = zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        let notification = Notification::new("Downloading").progress(42);
+
+        let encoded = zvariant::to_bytes(ctxt, &notification).unwrap();
+        let decoded: HashMap<String, OwnedValue> = zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(u32::try_from(decoded["progress"].clone()).unwrap(), 42);
+
+        let notification = Notification::new("Downloading").progress(200);
+        assert_eq!(notification.progress, Some(100));
+
+        let notification = Notification::new("Downloaded").progress(None);
+        let encoded = zvariant::to_bytes(ctxt, &notification).unwrap();
+        let decoded: HashMap<String, OwnedValue> = zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert!(!decoded.contains_key("progress"));
+    }
+
+    #[test]
+    fn timeout() {
+        let ctxt = zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        let notification = Notification::new("Saved").timeout(Duration::from_secs(3));
+
+        let encoded = zvariant::to_bytes(ctxt, &notification).unwrap();
+        let decoded: HashMap<String, OwnedValue> = zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(
+            u32::try_from(decoded["expire-timeout"].clone()).unwrap(),
+            3000
+        );
+
+        let notification = Notification::new("Saved").timeout(Duration::MAX);
+        assert_eq!(notification.expire_timeout, Some(u32::MAX));
+    }
+}
@@ -68,6 +68,7 @@ struct OpenDirOptions {
 #[zvariant(signature = "dict")]
 struct OpenFileOptions {
     handle_token: HandleToken,
+    #[zvariant(rename = "writable")]
     writeable: Option<bool>,
     ask: Option<bool>,
     activation_token: Option<String>,
@@ -148,7 +149,15 @@ impl OpenFileRequest {
     }
 
     #[must_use]
-    /// Whether the file should be writeable or not.
+    /// Whether the chosen application can write to the file or not.
+    ///
+    /// The portal exports the file through the document portal, with the
+    /// write permission if set, when the chosen application is sandboxed.
+    /// The changes made by the application are written to the original
+    /// file, allowing "open in an external editor" workflows.
+    ///
+    /// **Note** this only applies to local files, and the ID of the exported
+    /// document isn't returned by the portal.
     pub fn writeable(mut self, writeable: impl Into<Option<bool>>) -> Self {
         self.options.writeable = writeable.into();
         self
@@ -161,6 +170,29 @@ impl OpenFileRequest {
         self
     }
 
+    #[must_use]
+    /// Sets the token that can be used to activate the chosen application.
+    ///
+    /// Pass the activation token, like the `XDG_ACTIVATION_TOKEN` or the
+    /// startup notification id, the application received from the user
+    /// interaction so that the opened application is focused.
+    pub fn activation_token<'a>(mut self, activation_token: impl Into<Option<&'a str>>) -> Self {
+        self.options.activation_token = activation_token.into().map(ToOwned::to_owned);
+        self
+    }
+
+    #[must_use]
+    #[doc(alias = "DESKTOP_STARTUP_ID")]
+    /// Sets the X11 startup notification id of the launch.
+    ///
+    /// The portal has a single field for both display servers, the token is
+    /// passed to the launched application as `DESKTOP_STARTUP_ID` and
+    /// `XDG_ACTIVATION_TOKEN`, which gives it the startup feedback on X11.
+    /// This is the same as [`Self::activation_token`] and overrides it.
+    pub fn startup_id<'a>(self, startup_id: impl Into<Option<&'a str>>) -> Self {
+        self.activation_token(startup_id)
+    }
+
     /// Send the request for a file.
     pub async fn send_file(self, file: &impl AsRawFd) -> Result<Request<()>, Error> {
         let proxy = OpenURIProxy::new().await?;
@@ -193,7 +225,29 @@ impl OpenDirectoryRequest {
         self
     }
 
+    #[must_use]
+    /// Sets the token that can be used to activate the file manager.
+    ///
+    /// See [`OpenFileRequest::activation_token`].
+    pub fn activation_token<'a>(mut self, activation_token: impl Into<Option<&'a str>>) -> Self {
+        self.options.activation_token = activation_token.into().map(ToOwned::to_owned);
+        self
+    }
+
+    #[must_use]
+    #[doc(alias = "DESKTOP_STARTUP_ID")]
+    /// Sets the X11 startup notification id of the launch.
+    ///
+    /// See [`OpenFileRequest::startup_id`].
+    pub fn startup_id<'a>(self, startup_id: impl Into<Option<&'a str>>) -> Self {
+        self.activation_token(startup_id)
+    }
+
     /// Send the request.
+    ///
+    /// Fails with [`Error::NotSupported`] without calling the portal if the
+    /// running interface is older than version 3, which introduced
+    /// `OpenDirectory`.
     pub async fn send(self, directory: &impl AsRawFd) -> Result<Request<()>, Error> {
         let proxy = OpenURIProxy::new().await?;
         proxy
@@ -201,3 +255,58 @@ impl OpenDirectoryRequest {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use byteorder::LE;
+    use serde::Serialize;
+    use zbus::zvariant::{from_slice, to_bytes, EncodingContext as Context, OwnedValue};
+
+    use super::*;
+
+    fn to_dict(options: impl Serialize + Type) -> HashMap<String, OwnedValue> {
+        let ctxt = Context::<LE>::new_dbus(0);
+        let encoded = to_bytes(ctxt, &options).unwrap();
+        from_slice(&encoded, ctxt).unwrap()
+    }
+
+    #[test]
+    fn activation_token() {
+        let request = OpenFileRequest::default().activation_token("token");
+        let dict = to_dict(request.options);
+        assert_eq!(
+            String::try_from(dict["activation_token"].clone()).unwrap(),
+            "token"
+        );
+
+        let request = OpenDirectoryRequest::default().activation_token("token");
+        let dict = to_dict(request.options);
+        assert_eq!(
+            String::try_from(dict["activation_token"].clone()).unwrap(),
+            "token"
+        );
+
+        let dict = to_dict(OpenFileRequest::default().options);
+        assert!(!dict.contains_key("activation_token"));
+
+        let request = OpenFileRequest::default().startup_id("startup-id");
+        let dict = to_dict(request.options);
+        assert_eq!(
+            String::try_from(dict["activation_token"].clone()).unwrap(),
+            "startup-id"
+        );
+    }
+
+    #[test]
+    fn writable() {
+        let request = OpenFileRequest::default().writeable(true);
+        let dict = to_dict(request.options);
+        assert!(bool::try_from(dict["writable"].clone()).unwrap());
+        assert!(!dict.contains_key("writeable"));
+
+        let dict = to_dict(OpenFileRequest::default().options);
+        assert!(!dict.contains_key("writable"));
+    }
+}
@@ -0,0 +1,173 @@
+use std::io;
+
+use wayland_client::{
+    protocol::{wl_output, wl_registry},
+    Connection, Dispatch, Proxy, QueueHandle, WEnum,
+};
+
+use crate::Error;
+
+// Supported version, name and description were added in v4.
+const WL_OUTPUT: u32 = 4;
+
+/// A monitor as advertised by the compositor, see [`list_outputs`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputInfo {
+    name: Option<String>,
+    description: Option<String>,
+    make: String,
+    model: String,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    scale: i32,
+}
+
+impl OutputInfo {
+    /// The connector name, e.g. `DP-1`, if the compositor supports `wl_output`
+    /// v4.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// A human readable description of the output, if the compositor
+    /// supports `wl_output` v4.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// The manufacturer of the monitor.
+    pub fn make(&self) -> &str {
+        &self.make
+    }
+
+    /// The model of the monitor.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// The position of the output in the global compositor space.
+    pub fn position(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    /// The size of the current mode, in physical pixels.
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// The scale factor of the output.
+    pub fn scale(&self) -> i32 {
+        self.scale
+    }
+}
+
+/// List the outputs of the running Wayland compositor.
+///
+/// This is advisory data meant for a pre-selection UI, e.g. "record monitor
+/// X". The portal doesn't expose the monitors, the user still makes the final
+/// selection in the dialog shown by
+/// [`Screencast::select_sources`](super::screencast::Screencast::select_sources), which
+/// may differ from what was shown.
+///
+/// **Note** Only Wayland is supported.
+///
+/// # Errors
+///
+/// An [`Error::IO`] is returned if the compositor couldn't be reached, for
+/// example when not running a Wayland session.
+pub async fn list_outputs() -> Result<Vec<OutputInfo>, Error> {
+    let (sender, receiver) = futures_channel::oneshot::channel();
+    // The round trips are blocking
+    std::thread::spawn(move || {
+        let _ = sender.send(wayland_list_outputs());
+    });
+    receiver.await.map_err(|_| Error::NoResponse)?
+}
+
+#[derive(Default, Debug)]
+struct State {
+    outputs: Vec<OutputInfo>,
+}
+
+impl Dispatch<wl_registry::WlRegistry, ()> for State {
+    fn event(
+        state: &mut Self,
+        registry: &wl_registry::WlRegistry,
+        event: wl_registry::Event,
+        _: &(),
+        _: &Connection,
+        qhandle: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            if interface == wl_output::WlOutput::interface().name {
+                let index = state.outputs.len();
+                state.outputs.push(OutputInfo {
+                    scale: 1,
+                    ..Default::default()
+                });
+                registry.bind::<wl_output::WlOutput, usize, State>(
+                    name,
+                    version.min(WL_OUTPUT),
+                    qhandle,
+                    index,
+                );
+            }
+        }
+    }
+}
+
+impl Dispatch<wl_output::WlOutput, usize> for State {
+    fn event(
+        state: &mut Self,
+        _proxy: &wl_output::WlOutput,
+        event: wl_output::Event,
+        index: &usize,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let output = &mut state.outputs[*index];
+        match event {
+            wl_output::Event::Geometry {
+                x, y, make, model, ..
+            } => {
+                output.x = x;
+                output.y = y;
+                output.make = make;
+                output.model = model;
+            }
+            wl_output::Event::Mode {
+                flags: WEnum::Value(flags),
+                width,
+                height,
+                ..
+            } if flags.contains(wl_output::Mode::Current) => {
+                output.width = width;
+                output.height = height;
+            }
+            wl_output::Event::Scale { factor } => output.scale = factor,
+            wl_output::Event::Name { name } => output.name = Some(name),
+            wl_output::Event::Description { description } => output.description = Some(description),
+            _ => (),
+        }
+    }
+}
+
+fn wayland_list_outputs() -> Result<Vec<OutputInfo>, Error> {
+    let conn = Connection::connect_to_env().map_err(|err| io::Error::other(err.to_string()))?;
+    let dispatch_error = |err: wayland_client::DispatchError| io::Error::other(err.to_string());
+    let mut event_queue = conn.new_event_queue();
+    let qhandle = event_queue.handle();
+    let mut state = State::default();
+    conn.display().get_registry(&qhandle, ());
+    // Once for the globals, then for the events of the bound outputs
+    event_queue.roundtrip(&mut state).map_err(dispatch_error)?;
+    event_queue.roundtrip(&mut state).map_err(dispatch_error)?;
+    Ok(state.outputs)
+}
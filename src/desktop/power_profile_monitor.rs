@@ -1,4 +1,9 @@
-use crate::{proxy::Proxy, Error};
+use futures_util::Stream;
+
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
 /// The interface provides information about the user-selected system-wide power
 /// profile, to sandboxed applications.
@@ -21,9 +26,32 @@ impl<'a> PowerProfileMonitor<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`PowerProfileMonitor::new`], failing with
+    /// [`Error::NotSupported`] if the portal doesn't provide
+    /// `org.freedesktop.portal.PowerProfileMonitor`.
+    pub async fn new_checked() -> Result<PowerProfileMonitor<'a>, Error> {
+        let proxy =
+            Proxy::new_desktop_checked("org.freedesktop.portal.PowerProfileMonitor").await?;
+        Ok(Self(proxy))
+    }
+
     /// Whether the power saver is enabled.
     #[doc(alias = "power-saver-enabled")]
     pub async fn is_enabled(&self) -> Result<bool, Error> {
         self.0.property("power-saver-enabled").await
     }
+
+    /// A stream of the power saver state, emitted every time it changes.
+    #[doc(alias = "power-saver-enabled")]
+    pub async fn receive_is_enabled_changed(&self) -> Result<impl Stream<Item = bool> + 'a, Error> {
+        self.0.receive_property_changed("power-saver-enabled").await
+    }
 }
+
+impl AsProxy for PowerProfileMonitor<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for PowerProfileMonitor<'_> {}
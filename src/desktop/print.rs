@@ -25,19 +25,23 @@
 //!         .response()?;
 //!     proxy
 //!         .print(&identifier, "test", &file, Some(pre_print.token), true)
-//!         .await?;
+//!         .await?
+//!         .response()?;
 //!
 //!     Ok(())
 //! }
 //! ```
 
-use std::{fmt, os::unix::prelude::AsRawFd, str::FromStr};
+use std::{collections::HashMap, fmt, os::unix::prelude::AsRawFd, str::FromStr};
 
-use serde::{Deserialize, Serialize};
-use zbus::zvariant::{DeserializeDict, Fd, SerializeDict, Type};
+use serde::{de, ser::SerializeMap, Deserialize, Serialize};
+use zbus::zvariant::{DeserializeDict, Fd, OwnedValue, SerializeDict, SerializeValue, Type};
 
 use super::{HandleToken, Request};
-use crate::{proxy::Proxy, Error, WindowIdentifier};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy, WindowIdentifier,
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Type)]
 #[zvariant(signature = "s")]
@@ -54,6 +58,19 @@ pub enum Orientation {
     ReversePortrait,
 }
 
+impl Orientation {
+    /// The nick of the matching `GtkPageOrientation`, as used by
+    /// [`PageSetup`].
+    fn nick(&self) -> &'static str {
+        match self {
+            Self::Landscape => "landscape",
+            Self::Portrait => "portrait",
+            Self::ReverseLandscape => "reverse-landscape",
+            Self::ReversePortrait => "reverse-portrait",
+        }
+    }
+}
+
 impl fmt::Display for Orientation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -94,8 +111,12 @@ impl FromStr for Orientation {
         match s {
             "Landscape" | "landscape" => Ok(Orientation::Landscape),
             "Portrait" | "portrait" => Ok(Orientation::Portrait),
-            "ReverseLandscape" | "reverse_landscape" => Ok(Orientation::ReverseLandscape),
-            "ReversePortrait" | "reverse_portrait" => Ok(Orientation::ReversePortrait),
+            "ReverseLandscape" | "reverse_landscape" | "reverse-landscape" => {
+                Ok(Orientation::ReverseLandscape)
+            }
+            "ReversePortrait" | "reverse_portrait" | "reverse-portrait" => {
+                Ok(Orientation::ReversePortrait)
+            }
             _ => Err(Error::ParseError(
                 "Failed to parse orientation, invalid value",
             )),
@@ -449,12 +470,67 @@ impl Settings {
     }
 }
 
-#[derive(SerializeDict, DeserializeDict, Type, Debug, Default)]
+#[derive(Debug, Clone, PartialEq)]
+/// The paper size of a [`PageSetup`].
+pub enum PaperSize {
+    /// A paper size known by its name, e.g. `iso_a4`, see the PWG 5101.1
+    /// self-describing media names, along with its size in millimeters.
+    Named {
+        /// The name of the paper size.
+        name: String,
+        /// The paper width in millimeters.
+        width: f64,
+        /// The paper height in millimeters.
+        height: f64,
+    },
+    /// A custom paper size, in millimeters.
+    Custom {
+        /// The paper width in millimeters.
+        width: f64,
+        /// The paper height in millimeters.
+        height: f64,
+    },
+}
+
+impl PaperSize {
+    /// The ISO A4 paper size.
+    pub fn a4() -> Self {
+        Self::Named {
+            name: "iso_a4".to_owned(),
+            width: 210.0,
+            height: 297.0,
+        }
+    }
+
+    /// The US Letter paper size.
+    pub fn letter() -> Self {
+        Self::Named {
+            name: "na_letter".to_owned(),
+            width: 215.9,
+            height: 279.4,
+        }
+    }
+
+    /// The paper width in millimeters.
+    pub fn width(&self) -> f64 {
+        match self {
+            Self::Named { width, .. } | Self::Custom { width, .. } => *width,
+        }
+    }
+
+    /// The paper height in millimeters.
+    pub fn height(&self) -> f64 {
+        match self {
+            Self::Named { height, .. } | Self::Custom { height, .. } => *height,
+        }
+    }
+}
+
+#[derive(Type, Debug, Default, Clone, PartialEq)]
 /// Setup the printed pages.
 #[zvariant(signature = "dict")]
 pub struct PageSetup {
     /// the PPD name. It's the name to select a given driver.
-    #[zvariant(rename = "PPDName")]
     pub ppdname: Option<String>,
     /// The name of the page setup.
     pub name: Option<String>,
@@ -476,6 +552,9 @@ pub struct PageSetup {
     pub orientation: Option<Orientation>,
 }
 
+/// The name prefix of a [`PaperSize::Custom`].
+const CUSTOM_PAPER_SIZE: &str = "custom_";
+
 impl PageSetup {
     /// Sets the ppdname.
     #[must_use]
@@ -505,6 +584,34 @@ impl PageSetup {
         self
     }
 
+    /// Sets the paper size, replacing the name, width and height of the page
+    /// setup.
+    #[must_use]
+    pub fn paper_size(mut self, paper_size: PaperSize) -> Self {
+        self.width = Some(paper_size.width());
+        self.height = Some(paper_size.height());
+        self.name = Some(match paper_size {
+            PaperSize::Named { name, .. } => name,
+            PaperSize::Custom { width, height } => {
+                format!("{CUSTOM_PAPER_SIZE}{width}x{height}mm")
+            }
+        });
+        self
+    }
+
+    /// The paper size, if the page setup has a width and a height.
+    pub fn paper(&self) -> Option<PaperSize> {
+        let (width, height) = (self.width?, self.height?);
+        match self.name.as_deref() {
+            Some(name) if !name.starts_with(CUSTOM_PAPER_SIZE) => Some(PaperSize::Named {
+                name: name.to_owned(),
+                width,
+                height,
+            }),
+            _ => Some(PaperSize::Custom { width, height }),
+        }
+    }
+
     /// Sets the page width.
     #[must_use]
     pub fn width(mut self, width: impl Into<Option<f64>>) -> Self {
@@ -546,6 +653,93 @@ impl PageSetup {
         self.margin_left = margin_left.into();
         self
     }
+
+    /// Sets the four page margins.
+    #[must_use]
+    pub fn margins(self, top: f64, bottom: f64, right: f64, left: f64) -> Self {
+        self.margin_top(top)
+            .margin_bottom(bottom)
+            .margin_right(right)
+            .margin_left(left)
+    }
+}
+
+// The page setup uses the keys of `GtkPageSetup`, and the orientation nicks of
+// `GtkPageOrientation`, unlike the print settings
+impl Serialize for PageSetup {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        let strings = [
+            ("PPDName", &self.ppdname),
+            ("Name", &self.name),
+            ("DisplayName", &self.display_name),
+        ];
+        for (key, value) in strings {
+            if let Some(value) = value {
+                map.serialize_entry(key, &SerializeValue(value))?;
+            }
+        }
+        let numbers = [
+            ("Width", self.width),
+            ("Height", self.height),
+            ("MarginTop", self.margin_top),
+            ("MarginBottom", self.margin_bottom),
+            ("MarginRight", self.margin_right),
+            ("MarginLeft", self.margin_left),
+        ];
+        for (key, value) in numbers {
+            if let Some(value) = value {
+                map.serialize_entry(key, &SerializeValue(&value))?;
+            }
+        }
+        if let Some(orientation) = &self.orientation {
+            map.serialize_entry("Orientation", &SerializeValue(&orientation.nick()))?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for PageSetup {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut dict = HashMap::<String, OwnedValue>::deserialize(deserializer)?;
+        let mut string = |key: &str| {
+            dict.remove(key)
+                .map(String::try_from)
+                .transpose()
+                .map_err(|_| de::Error::custom(format!("Invalid type for {key}")))
+        };
+        let ppdname = string("PPDName")?;
+        let name = string("Name")?;
+        let display_name = string("DisplayName")?;
+        let orientation = string("Orientation")?
+            .map(|orientation| orientation.parse::<Orientation>())
+            .transpose()
+            .map_err(de::Error::custom)?;
+        let mut number = |key: &str| {
+            dict.remove(key)
+                .map(f64::try_from)
+                .transpose()
+                .map_err(|_| de::Error::custom(format!("Invalid type for {key}")))
+        };
+        Ok(Self {
+            ppdname,
+            name,
+            display_name,
+            width: number("Width")?,
+            height: number("Height")?,
+            margin_top: number("MarginTop")?,
+            margin_bottom: number("MarginBottom")?,
+            margin_right: number("MarginRight")?,
+            margin_left: number("MarginLeft")?,
+            orientation,
+        })
+    }
 }
 
 #[derive(SerializeDict, Type, Debug, Default)]
@@ -606,6 +800,26 @@ pub struct PreparePrint {
     pub token: u32,
 }
 
+#[derive(DeserializeDict, Type, Debug, Default)]
+/// A response to a [`PrintProxy::print`] request.
+///
+/// A successful response means the backend accepted the print job, a user
+/// dismissing the dialog gives an error instead, see [`Error::is_cancelled`].
+///
+/// **Note** the specification defines no results, what is reported depends
+/// on the backend, most of them return none.
+#[zvariant(signature = "dict")]
+pub struct PrintResult {
+    token: Option<u32>,
+}
+
+impl PrintResult {
+    /// The token of the print request, if echoed back by the backend.
+    pub fn token(&self) -> Option<u32> {
+        self.token
+    }
+}
+
 /// The interface lets sandboxed applications print.
 ///
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.Print`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.portal.Print).
@@ -620,6 +834,13 @@ impl<'a> PrintProxy<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`PrintProxy::new`], failing with [`Error::NotSupported`] if the
+    /// portal doesn't provide `org.freedesktop.portal.Print`.
+    pub async fn new_checked() -> Result<PrintProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Print").await?;
+        Ok(Self(proxy))
+    }
+
     /// Presents a print dialog to the user and returns print settings and page
     /// setup.
     ///
@@ -668,6 +889,11 @@ impl<'a> PrintProxy<'a> {
     ///   [`prepare_print()`][`PrintProxy::prepare_print`].
     /// * `modal` - Whether the dialog should be a modal.
     ///
+    /// # Returns
+    ///
+    /// A [`Request`] whose response tells whether the print job was
+    /// accepted, see [`PrintResult`].
+    ///
     /// # Specifications
     ///
     /// See also [`Print`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-Print.Print).
@@ -680,12 +906,12 @@ impl<'a> PrintProxy<'a> {
         fd: &impl AsRawFd,
         token: Option<u32>,
         modal: bool,
-    ) -> Result<Request<()>, Error> {
+    ) -> Result<Request<PrintResult>, Error> {
         let options = PrintOptions::default()
             .token(token.unwrap_or(0))
             .modal(modal);
         self.0
-            .empty_request(
+            .request(
                 &options.handle_token,
                 "Print",
                 &(&identifier, title, Fd::from(fd.as_raw_fd()), &options),
@@ -693,3 +919,74 @@ impl<'a> PrintProxy<'a> {
             .await
     }
 }
+
+impl AsProxy for PrintProxy<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for PrintProxy<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use byteorder::LE;
+    use zbus::zvariant::{from_slice, to_bytes, EncodingContext as Context, Value};
+
+    use super::*;
+
+    #[test]
+    fn page_setup() {
+        let ctxt = Context::<LE>::new_dbus(0);
+        let page_setup = PageSetup::default()
+            .paper_size(PaperSize::a4())
+            .display_name("A4")
+            .orientation(Orientation::ReverseLandscape)
+            .margins(10.0, 10.0, 5.0, 5.0);
+
+        let encoded = to_bytes(ctxt, &page_setup).unwrap();
+        let dict: HashMap<String, OwnedValue> = from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(String::try_from(dict["Name"].clone()).unwrap(), "iso_a4");
+        assert_eq!(f64::try_from(dict["Width"].clone()).unwrap(), 210.0);
+        assert_eq!(f64::try_from(dict["MarginLeft"].clone()).unwrap(), 5.0);
+        assert_eq!(
+            String::try_from(dict["Orientation"].clone()).unwrap(),
+            "reverse-landscape"
+        );
+        assert!(!dict.contains_key("PPDName"));
+
+        let decoded: PageSetup = from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(decoded, page_setup);
+        assert_eq!(decoded.paper(), Some(PaperSize::a4()));
+    }
+
+    #[test]
+    fn print_result() {
+        let ctxt = Context::<LE>::new_dbus(0);
+        let encoded = to_bytes(ctxt, &HashMap::<String, OwnedValue>::new()).unwrap();
+        let result: PrintResult = from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(result.token(), None);
+
+        let mut results = HashMap::new();
+        results.insert("token", Value::from(3u32));
+        let encoded = to_bytes(ctxt, &results).unwrap();
+        let result: PrintResult = from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(result.token(), Some(3));
+    }
+
+    #[test]
+    fn custom_paper_size() {
+        let ctxt = Context::<LE>::new_dbus(0);
+        let paper_size = PaperSize::Custom {
+            width: 100.0,
+            height: 150.0,
+        };
+        let page_setup = PageSetup::default().paper_size(paper_size.clone());
+        assert_eq!(page_setup.name.as_deref(), Some("custom_100x150mm"));
+
+        let encoded = to_bytes(ctxt, &page_setup).unwrap();
+        let decoded: PageSetup = from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(decoded.paper(), Some(paper_size));
+        assert_eq!(PageSetup::default().paper(), None);
+    }
+}
@@ -14,7 +14,10 @@
 //! }
 //! ```
 
-use crate::{proxy::Proxy, Error};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
 /// The interface provides network proxy information to sandboxed applications.
 ///
@@ -34,6 +37,13 @@ impl<'a> ProxyResolver<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`ProxyResolver::new`], failing with [`Error::NotSupported`] if
+    /// the portal doesn't provide `org.freedesktop.portal.ProxyResolver`.
+    pub async fn new_checked() -> Result<ProxyResolver<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.ProxyResolver").await?;
+        Ok(Self(proxy))
+    }
+
     /// Looks up which proxy to use to connect to `uri`.
     ///
     /// # Returns
@@ -50,3 +60,11 @@ impl<'a> ProxyResolver<'a> {
         self.0.call("Lookup", &(uri)).await
     }
 }
+
+impl AsProxy for ProxyResolver<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for ProxyResolver<'_> {}
@@ -2,7 +2,10 @@
 //!
 //! Wrapper of the DBus interface: [`org.freedesktop.portal.Realtime`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.portal.Realtime).
 
-use crate::{proxy::Proxy, Error};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
 /// Interface for setting a thread to realtime from within the sandbox.
 ///
@@ -18,9 +21,27 @@ impl<'a> Realtime<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`Realtime::new`], failing with [`Error::NotSupported`] if the
+    /// portal doesn't provide `org.freedesktop.portal.Realtime`.
+    pub async fn new_checked() -> Result<Realtime<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Realtime").await?;
+        Ok(Self(proxy))
+    }
+
+    /// Makes a thread realtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `process` - The process ID, as seen from the sandbox.
+    /// * `thread` - The thread ID, as seen from the sandbox.
+    /// * `priority` - The realtime priority, up to
+    ///   [`max_realtime_priority()`][`Realtime::max_realtime_priority`].
+    ///
+    /// # Specifications
+    ///
+    /// See also [`MakeThreadRealtimeWithPID`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-Realtime.MakeThreadRealtimeWithPID).
     #[doc(alias = "MakeThreadRealtimeWithPID")]
-    #[allow(missing_docs)]
-    pub async fn max_thread_realtime_with_pid(
+    pub async fn make_thread_realtime(
         &self,
         process: u64,
         thread: u64,
@@ -31,9 +52,20 @@ impl<'a> Realtime<'a> {
             .await
     }
 
+    /// Gives a thread a higher priority, by lowering its nice level.
+    ///
+    /// # Arguments
+    ///
+    /// * `process` - The process ID, as seen from the sandbox.
+    /// * `thread` - The thread ID, as seen from the sandbox.
+    /// * `priority` - The nice level, down to
+    ///   [`min_nice_level()`][`Realtime::min_nice_level`].
+    ///
+    /// # Specifications
+    ///
+    /// See also [`MakeThreadHighPriorityWithPID`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-Realtime.MakeThreadHighPriorityWithPID).
     #[doc(alias = "MakeThreadHighPriorityWithPID")]
-    #[allow(missing_docs)]
-    pub async fn max_thread_high_priority_with_pid(
+    pub async fn make_thread_high_priority(
         &self,
         process: u64,
         thread: u64,
@@ -47,21 +79,113 @@ impl<'a> Realtime<'a> {
             .await
     }
 
-    #[doc(alias = "MaxRealtimePriority")]
     #[allow(missing_docs)]
-    pub async fn max_realtime_priority(&self) -> Result<i64, Error> {
+    #[deprecated = "Use `make_thread_realtime` instead"]
+    pub async fn max_thread_realtime_with_pid(
+        &self,
+        process: u64,
+        thread: u64,
+        priority: u32,
+    ) -> Result<(), Error> {
+        self.make_thread_realtime(process, thread, priority).await
+    }
+
+    #[allow(missing_docs)]
+    #[deprecated = "Use `make_thread_high_priority` instead"]
+    pub async fn max_thread_high_priority_with_pid(
+        &self,
+        process: u64,
+        thread: u64,
+        priority: i32,
+    ) -> Result<(), Error> {
+        self.make_thread_high_priority(process, thread, priority)
+            .await
+    }
+
+    /// The maximum realtime priority that can be requested.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`MaxRealtimePriority`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-property-org-freedesktop-portal-Realtime.MaxRealtimePriority).
+    #[doc(alias = "MaxRealtimePriority")]
+    pub async fn max_realtime_priority(&self) -> Result<i32, Error> {
         self.0.property("MaxRealtimePriority").await
     }
 
+    /// The minimum nice level that can be requested.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`MinNiceLevel`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-property-org-freedesktop-portal-Realtime.MinNiceLevel).
     #[doc(alias = "MinNiceLevel")]
-    #[allow(missing_docs)]
-    pub async fn min_nice_level(&self) -> Result<u32, Error> {
+    pub async fn min_nice_level(&self) -> Result<i32, Error> {
         self.0.property("MinNiceLevel").await
     }
 
+    /// The maximum CPU time, in microseconds, a realtime thread can consume
+    /// without making a blocking system call.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`RTTimeUSecMax`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-property-org-freedesktop-portal-Realtime.RTTimeUSecMax).
     #[doc(alias = "RTTimeUSecMax")]
-    #[allow(missing_docs)]
-    pub async fn rt_time_usec_max(&self) -> Result<u32, Error> {
+    pub async fn rt_time_usec_max(&self) -> Result<i64, Error> {
         self.0.property("RTTimeUSecMax").await
     }
 }
+
+impl AsProxy for Realtime<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for Realtime<'_> {}
+
+#[cfg(all(test, feature = "async-std"))]
+mod tests {
+    use zbus::dbus_interface;
+
+    use super::Realtime;
+    use crate::proxy::DESKTOP_PATH;
+
+    struct MockRealtime;
+
+    // Same property types as the real portal
+    #[dbus_interface(name = "org.freedesktop.portal.Realtime")]
+    impl MockRealtime {
+        #[dbus_interface(property)]
+        fn max_realtime_priority(&self) -> i32 {
+            20
+        }
+
+        #[dbus_interface(property)]
+        fn min_nice_level(&self) -> i32 {
+            -15
+        }
+
+        #[dbus_interface(property, name = "RTTimeUSecMax")]
+        fn rt_time_usec_max(&self) -> i64 {
+            200_000
+        }
+    }
+
+    #[test]
+    fn properties() {
+        async_std::task::block_on(async {
+            let Some(server) = crate::proxy::mock_desktop().await else {
+                return;
+            };
+            server
+                .object_server()
+                .at(DESKTOP_PATH, MockRealtime)
+                .await
+                .unwrap();
+
+            let proxy = Realtime::new().await.unwrap();
+            assert_eq!(proxy.max_realtime_priority().await.unwrap(), 20);
+            assert_eq!(proxy.min_nice_level().await.unwrap(), -15);
+            assert_eq!(proxy.rt_time_usec_max().await.unwrap(), 200_000);
+        });
+    }
+}
@@ -0,0 +1,79 @@
+//! Wrapper of the DBus interface: [`org.freedesktop.host.portal.Registry`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.host.portal.Registry).
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ashpd::desktop::registry::Registry;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let proxy = Registry::new().await?;
+//!     proxy.register("org.gnome.design.Contrast").await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use zbus::zvariant::Value;
+
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    AppID, Error, PortalProxy,
+};
+
+/// The interface lets host applications, which are not sandboxed, register
+/// their application ID so that the other portals can attribute their
+/// requests, like notifications or background apps, to the correct
+/// application.
+///
+/// The registration has to happen before any other portal call, and sandboxed
+/// applications can't use it.
+///
+/// **Note** the interface is only available with recent versions of
+/// `xdg-desktop-portal`, [`Error::NotSupported`] is returned otherwise.
+///
+/// Wrapper of the DBus interface: [`org.freedesktop.host.portal.Registry`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.host.portal.Registry).
+#[derive(Debug)]
+#[doc(alias = "org.freedesktop.host.portal.Registry")]
+pub struct Registry<'a>(Proxy<'a>);
+
+impl<'a> Registry<'a> {
+    /// Create a new instance of [`Registry`].
+    pub async fn new() -> Result<Registry<'a>, Error> {
+        let proxy = Proxy::new_desktop("org.freedesktop.host.portal.Registry").await?;
+        Ok(Self(proxy))
+    }
+
+    /// Same as [`Registry::new`], failing with [`Error::NotSupported`] if the
+    /// portal doesn't provide `org.freedesktop.host.portal.Registry`.
+    pub async fn new_checked() -> Result<Registry<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.host.portal.Registry").await?;
+        Ok(Self(proxy))
+    }
+
+    /// Registers the application ID of the calling application.
+    ///
+    /// # Arguments
+    ///
+    /// * `app_id` - The application ID.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Register`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-host-portal-Registry.Register).
+    #[doc(alias = "Register")]
+    pub async fn register(&self, app_id: impl TryInto<AppID, Error = Error>) -> Result<(), Error> {
+        // No supported options for now
+        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        self.0
+            .call("Register", &(app_id.try_into()?, options))
+            .await
+    }
+}
+
+impl AsProxy for Registry<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for Registry<'_> {}
@@ -2,7 +2,7 @@
 //!
 //! ```rust,no_run
 //! use ashpd::{
-//!     desktop::remote_desktop::{DeviceType, KeyState, RemoteDesktop},
+//!     desktop::remote_desktop::{DeviceType, Key, KeyState, RemoteDesktop},
 //!     WindowIdentifier,
 //! };
 //!
@@ -19,9 +19,9 @@
 //!         .response()?;
 //!     println!("{:#?}", response.devices());
 //!
-//!     // 13 for Enter key code
+//!     // 28 for the Enter key code
 //!     proxy
-//!         .notify_keyboard_keycode(&session, 13, KeyState::Pressed)
+//!         .notify_key(&session, Key::Code(28), KeyState::Pressed)
 //!         .await?;
 //!
 //!     Ok(())
@@ -37,7 +37,7 @@
 //! ```rust,no_run
 //! use ashpd::{
 //!     desktop::{
-//!         remote_desktop::{DeviceType, KeyState, RemoteDesktop},
+//!         remote_desktop::{DeviceType, Key, KeyState, RemoteDesktop},
 //!         screencast::{CursorMode, PersistMode, Screencast, SourceType},
 //!     },
 //!     WindowIdentifier,
@@ -70,14 +70,43 @@
 //!     println!("{:#?}", response.devices());
 //!     println!("{:#?}", response.streams());
 //!
-//!     // 13 for Enter key code
+//!     // 28 for the Enter key code
 //!     remote_desktop
-//!         .notify_keyboard_keycode(&session, 13, KeyState::Pressed)
+//!         .notify_key(&session, Key::Code(28), KeyState::Pressed)
 //!         .await?;
 //!
 //!     Ok(())
 //! }
 //! ```
+//! Or let [`RemoteDesktop::connect()`][connect] do the session set up
+//!
+//! ```rust,no_run
+//! use ashpd::{
+//!     desktop::remote_desktop::{DeviceType, Key, KeyState, RemoteDesktop},
+//!     WindowIdentifier,
+//! };
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let proxy = RemoteDesktop::new().await?;
+//!     let remote = proxy
+//!         .connect(
+//!             &WindowIdentifier::default(),
+//!             DeviceType::Keyboard | DeviceType::Pointer,
+//!         )
+//!         .await?;
+//!     println!("{:#?}", remote.devices());
+//!
+//!     // 28 for the Enter key code
+//!     proxy
+//!         .notify_key(remote.session(), Key::Code(28), KeyState::Pressed)
+//!         .await?;
+//!     // The session is closed once `remote` is dropped
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [connect]: crate::desktop::remote_desktop::RemoteDesktop::connect
 //! [select_sources]: crate::desktop::screencast::Screencast::select_sources
 //! [create_session]: crate::desktop::remote_desktop::RemoteDesktop::create_session
 
@@ -88,8 +117,11 @@ use futures_util::TryFutureExt;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{DeserializeDict, SerializeDict, Type, Value};
 
-use super::{screencast::Stream, HandleToken, Request, Session};
-use crate::{proxy::Proxy, Error, WindowIdentifier};
+use super::{screencast::Stream, HandleToken, PortalSession, Request, Session, SessionGuard};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy, WindowIdentifier,
+};
 
 #[derive(Serialize_repr, Deserialize_repr, Copy, Clone, PartialEq, Eq, Debug, Type)]
 #[doc(alias = "XdpKeyState")]
@@ -104,6 +136,25 @@ pub enum KeyState {
     Released = 0,
 }
 
+/// A keyboard key, either as a hardware key code or as a logical key symbol.
+///
+/// Key codes are evdev codes and map to a physical key regardless of the
+/// keyboard layout, while key symbols are XKB keysyms and are translated by
+/// the compositor using the active layout. Mutter (GNOME) and KWin handle
+/// both natively, whereas wlroots based backends translate keysyms through
+/// a virtual keymap and should preferably be fed key codes.
+///
+/// See [`RemoteDesktop::notify_key`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Key {
+    /// A keyboard code, see
+    /// [`notify_keyboard_keycode()`](RemoteDesktop::notify_keyboard_keycode).
+    Code(i32),
+    /// A keyboard symbol, see
+    /// [`notify_keyboard_keysym()`](RemoteDesktop::notify_keyboard_keysym).
+    Sym(i32),
+}
+
 #[bitflags]
 #[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Type)]
 #[repr(u32)]
@@ -200,6 +251,39 @@ impl SelectedDevices {
     }
 }
 
+/// A started remote desktop session that gets closed once dropped, see
+/// [`RemoteDesktop::connect`].
+///
+/// **Note** when dropped, the session is closed in the background without
+/// waiting for the portal to handle it, as `Drop` can't be async.
+#[derive(Debug)]
+pub struct RemoteDesktopGuard<'a> {
+    session: SessionGuard<Session<'a>>,
+    selected: SelectedDevices,
+}
+
+impl<'a> RemoteDesktopGuard<'a> {
+    /// The remote desktop session.
+    pub fn session(&self) -> &Session<'a> {
+        &self.session
+    }
+
+    /// The devices granted by the user.
+    pub fn devices(&self) -> BitFlags<DeviceType> {
+        self.selected.devices()
+    }
+
+    /// The selected streams if a ScreenCast portal is used on the same session
+    pub fn streams(&self) -> Option<&[Stream]> {
+        self.selected.streams()
+    }
+
+    /// Retrieve the session back, without closing it.
+    pub fn into_inner(self) -> (Session<'a>, SelectedDevices) {
+        (self.session.into_inner(), self.selected)
+    }
+}
+
 /// The interface lets sandboxed applications create remote desktop sessions.
 ///
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.RemoteDesktop`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.portal.RemoteDesktop).
@@ -214,6 +298,13 @@ impl<'a> RemoteDesktop<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`RemoteDesktop::new`], failing with [`Error::NotSupported`] if
+    /// the portal doesn't provide `org.freedesktop.portal.RemoteDesktop`.
+    pub async fn new_checked() -> Result<RemoteDesktop<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.RemoteDesktop").await?;
+        Ok(Self(proxy))
+    }
+
     /// Create a remote desktop session.
     /// A remote desktop session is used to allow remote controlling a desktop
     /// session. It can also be used together with a screen cast session.
@@ -229,7 +320,7 @@ impl<'a> RemoteDesktop<'a> {
             self.0
                 .request::<CreateSession>(&options.handle_token, "CreateSession", &options)
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future()
+            Session::from_unique_name(&self.0, &options.session_handle_token).into_future()
         )?;
         assert_eq!(proxy.path().as_str(), &request.response()?.session_handle);
         Ok(proxy)
@@ -270,6 +361,12 @@ impl<'a> RemoteDesktop<'a> {
     ///   [`create_session()`][`RemoteDesktop::create_session`].
     /// * `identifier` - The application window identifier.
     ///
+    /// The dialog can be dismissed on behalf of the user, for example after a
+    /// timeout, by passing the handle token of the request listed in
+    /// [`PortalProxy::pending_requests`] to [`PortalProxy::cancel_request`]
+    /// on this proxy. The method then fails with
+    /// [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    ///
     /// # Specifications
     ///
     /// See also [`Start`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-RemoteDesktop.Start).
@@ -289,6 +386,41 @@ impl<'a> RemoteDesktop<'a> {
             .await
     }
 
+    /// Create a session, select the devices to remote control and start it.
+    ///
+    /// The session is closed if any of those steps fails, including the user
+    /// or [`PortalProxy::cancel_request`] cancelling the request, if the returned
+    /// future is dropped before completing, or once the returned
+    /// [`RemoteDesktopGuard`] is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - The application window identifier.
+    /// * `devices` - The device types to request remote controlling of.
+    ///
+    /// # Returns
+    ///
+    /// The started session along with the devices granted by the user.
+    pub async fn connect(
+        &self,
+        identifier: &WindowIdentifier,
+        devices: BitFlags<DeviceType>,
+    ) -> Result<RemoteDesktopGuard<'a>, Error> {
+        let session = self.create_session().await?.close_on_drop();
+        let selected = async {
+            self.select_devices(&session, devices).await?.response()?;
+            self.start(&session, identifier).await?.response()
+        }
+        .await;
+        match selected {
+            Ok(selected) => Ok(RemoteDesktopGuard { session, selected }),
+            Err(err) => {
+                let _ = session.into_inner().close().await;
+                Err(err)
+            }
+        }
+    }
+
     /// Notify keyboard code.
     ///
     /// **Note** only works if [`DeviceType::Keyboard`] access was provided
@@ -349,6 +481,34 @@ impl<'a> RemoteDesktop<'a> {
             .await
     }
 
+    /// Notify a keyboard key event, either by key code or by key symbol.
+    ///
+    /// Dispatches to
+    /// [`notify_keyboard_keycode()`][`RemoteDesktop::notify_keyboard_keycode`]
+    /// or [`notify_keyboard_keysym()`][`RemoteDesktop::notify_keyboard_keysym`]
+    /// depending on the [`Key`] variant.
+    ///
+    /// **Note** only works if [`DeviceType::Keyboard`] access was provided
+    /// after starting the session.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - A [`Session`], created with
+    ///   [`create_session()`][`RemoteDesktop::create_session`].
+    /// * `key` - The key that was pressed or released.
+    /// * `state` - The new state of the key.
+    pub async fn notify_key(
+        &self,
+        session: &Session<'_>,
+        key: Key,
+        state: KeyState,
+    ) -> Result<(), Error> {
+        match key {
+            Key::Code(keycode) => self.notify_keyboard_keycode(session, keycode, state).await,
+            Key::Sym(keysym) => self.notify_keyboard_keysym(session, keysym, state).await,
+        }
+    }
+
     /// Notify about a new touch up event.
     ///
     /// **Note** only works if [`DeviceType::Touchscreen`] access was provided
@@ -618,3 +778,11 @@ impl<'a> RemoteDesktop<'a> {
         self.0.property("AvailableDeviceTypes").await
     }
 }
+
+impl AsProxy for RemoteDesktop<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for RemoteDesktop<'_> {}
@@ -1,22 +1,31 @@
 use std::{
     collections::HashMap,
     fmt::{self, Debug},
+    future::IntoFuture,
     marker::PhantomData,
     sync::Mutex,
+    time::{Duration, Instant},
 };
 
-use futures_util::StreamExt;
+use futures_channel::oneshot;
+use futures_util::{future, Stream, StreamExt};
 use serde::{
     de::{self, Error as SeError, Visitor},
     ser::SerializeTuple,
     Deserialize, Deserializer, Serialize,
 };
 use zbus::{
-    zvariant::{ObjectPath, Signature, Type, Value},
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Signature, Type, Value},
     SignalStream,
 };
 
-use crate::{desktop::HandleToken, proxy::Proxy, Error};
+use crate::{
+    desktop::HandleToken,
+    proxy::{Handles, Proxy},
+    Error,
+};
+
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
 
 /// A typical response returned by the [`Request::response`].
 /// of a [`Request`].
@@ -31,24 +40,6 @@ where
     Err(ResponseError),
 }
 
-#[cfg(feature = "backend")]
-impl<T> Response<T>
-where
-    T: for<'de> Deserialize<'de> + Type,
-{
-    pub fn ok(inner: T) -> Self {
-        Self::Ok(inner)
-    }
-
-    pub fn cancelled() -> Self {
-        Self::Err(ResponseError::Cancelled)
-    }
-
-    pub fn other() -> Self {
-        Self::Err(ResponseError::Other)
-    }
-}
-
 impl<T> Type for Response<T>
 where
     T: for<'de> Deserialize<'de> + Type,
@@ -70,9 +61,9 @@ where
 
         impl<'de, T> Visitor<'de> for ResponseVisitor<T>
         where
-            T: Deserialize<'de>,
+            T: for<'d> Deserialize<'d> + Type,
         {
-            type Value = (ResponseType, Option<T>);
+            type Value = Response<T>;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
                 write!(
@@ -88,20 +79,21 @@ where
                 let type_: ResponseType = seq.next_element()?.ok_or_else(|| A::Error::custom(
                     "Failed to deserialize the response. Expected a numeric (u) value as the first item of the returned tuple",
                 ))?;
-                if type_ == ResponseType::Success {
-                    let data: T = seq.next_element()?.ok_or_else(|| A::Error::custom(
-                        "Failed to deserialize the response. Expected a vardict (a{sv}) with the returned results",
-                    ))?;
-                    Ok((type_, Some(data)))
-                } else {
-                    Ok((type_, None))
+                match type_ {
+                    ResponseType::Success => {
+                        let data: T = seq.next_element()?.ok_or_else(|| A::Error::custom(
+                            "Failed to deserialize the response. Expected a vardict (a{sv}) with the returned results",
+                        ))?;
+                        Ok(Response::Ok(data))
+                    }
+                    ResponseType::Cancelled => Ok(Response::Err(ResponseError::Cancelled)),
+                    ResponseType::Other => Ok(Response::Err(ResponseError::Other)),
                 }
             }
         }
 
         let visitor = ResponseVisitor::<T>(PhantomData);
-        let response: (ResponseType, Option<T>) = deserializer.deserialize_tuple(2, visitor)?;
-        Ok(response.into())
+        deserializer.deserialize_tuple(2, visitor)
     }
 }
 
@@ -128,29 +120,19 @@ where
     }
 }
 
-#[doc(hidden)]
-impl<T> From<(ResponseType, Option<T>)> for Response<T>
-where
-    T: for<'de> Deserialize<'de> + Type,
-{
-    fn from(f: (ResponseType, Option<T>)) -> Self {
-        match f.0 {
-            ResponseType::Success => {
-                Response::Ok(f.1.expect("Expected a valid response, found nothing."))
-            }
-            ResponseType::Cancelled => Response::Err(ResponseError::Cancelled),
-            ResponseType::Other => Response::Err(ResponseError::Other),
-        }
-    }
-}
-
 #[derive(Debug, Copy, PartialEq, Eq, Hash, Clone)]
 /// An error returned a portal request caused by either the user cancelling the
 /// request or something else.
+///
+/// A [`ResponseError::Cancelled`] request was dismissed by the user, retrying
+/// it would show the same dialog again. A [`ResponseError::Other`] request was
+/// ended by the portal instead, for example because the session was revoked or
+/// the backend failed, see [`Error::is_ended`].
 pub enum ResponseError {
-    /// The user canceled the request.
+    /// The user canceled the request, response code `1`.
     Cancelled,
-    /// Something else happened.
+    /// The interaction was ended in some other way than by the user, response
+    /// code `2`.
     Other,
 }
 
@@ -186,6 +168,29 @@ impl From<ResponseError> for ResponseType {
     }
 }
 
+/// Listen to the raw `Response` signals of the request identified by
+/// `handle_token`.
+///
+/// This is meant for building custom request types on top of the crate, the
+/// handle token has to be passed as the `handle_token` option of the portal
+/// method call, after calling this function so that no response is missed.
+///
+/// Each item is the `(response, results)` tuple of a signal, where the response
+/// code is:
+///
+/// * `0` - Success, the request is carried out.
+/// * `1` - The user cancelled the interaction.
+/// * `2` - The user interaction was ended in some other way.
+///
+/// # Specifications
+///
+/// See also [`Response`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-signal-org-freedesktop-portal-Request.Response).
+pub async fn responses_for(
+    handle_token: &HandleToken,
+) -> Result<impl Stream<Item = (u32, HashMap<String, OwnedValue>)>, Error> {
+    Proxy::responses_for(handle_token).await
+}
+
 /// The Request interface is shared by all portal interfaces.
 /// When a portal method is called, the reply includes a handle (i.e. object
 /// path) for a Request object, which will stay alive for the duration of the
@@ -197,6 +202,35 @@ impl From<ResponseError> for ResponseType {
 /// The application can abort the interaction calling
 /// [`close()`][`Request::close`] on the Request object.
 ///
+/// A [`Request`] can also be awaited to get its response, the same as calling
+/// [`response()`][`Request::response`]:
+///
+/// ```rust,no_run
+/// use ashpd::desktop::screenshot::Screenshot;
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let screenshot = Screenshot::request().send().await?.await?;
+///     println!("URI: {}", screenshot.uri());
+///     Ok(())
+/// }
+/// ```
+///
+/// # Cancellation
+///
+/// The methods returning a [`Request`] only resolve once the user interaction
+/// is over. Dropping such a future before it resolves, for example as a losing
+/// branch of a `select!`, closes the underlying portal request in the
+/// background, which also dismisses the related dialogs. A request that has
+/// already received its response is not closed when dropped.
+///
+/// # Timeout
+///
+/// The timeout set with
+/// [`PortalProxy::set_call_timeout`](crate::PortalProxy::set_call_timeout)
+/// only applies to the method call creating the request, which replies right
+/// away. The response comes later through a signal, after the time the user
+/// spends in a dialog, and is not covered by it.
+///
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.Request`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.portal.Request).
 #[doc(alias = "org.freedesktop.portal.Request")]
 pub struct Request<T>(
@@ -204,6 +238,9 @@ pub struct Request<T>(
     SignalStream<'static>,
     Mutex<Option<Result<T, Error>>>,
     PhantomData<T>,
+    Option<Result<HashMap<String, OwnedValue>, ResponseError>>,
+    Option<oneshot::Receiver<()>>,
+    Handles,
 )
 where
     T: for<'de> Deserialize<'de> + Type + Debug;
@@ -212,45 +249,109 @@ impl<T> Request<T>
 where
     T: for<'de> Deserialize<'de> + Type + Debug,
 {
-    pub(crate) async fn new<P>(path: P) -> Result<Request<T>, Error>
+    /// Creates the request object at `path` and starts listening for its
+    /// response, the method creating it has to be called on `connection`.
+    /// The request is tracked in `handles` until it is closed.
+    ///
+    /// Waiting for the response fails with [`ResponseError::Cancelled`] once
+    /// the sender of `cancelled` is dropped.
+    pub(crate) async fn with_connection<P>(
+        connection: &zbus::Connection,
+        path: P,
+        cancelled: oneshot::Receiver<()>,
+        handles: Handles,
+    ) -> Result<Request<T>, Error>
     where
         P: TryInto<ObjectPath<'static>>,
         P::Error: Into<zbus::Error>,
     {
-        let proxy = Proxy::new_desktop_with_path("org.freedesktop.portal.Request", path).await?;
+        let proxy =
+            Proxy::from_connection_with_path(connection, "org.freedesktop.portal.Request", path)
+                .await?;
         // Start listening for a response signal the moment request is created
         let stream = proxy.receive_signal("Response").await?;
-        Ok(Self(proxy, stream, Default::default(), PhantomData))
+        handles.insert(
+            REQUEST_INTERFACE,
+            proxy.path().to_owned().into(),
+            connection,
+        );
+        Ok(Self(
+            proxy,
+            stream,
+            Default::default(),
+            PhantomData,
+            None,
+            Some(cancelled),
+            handles,
+        ))
     }
 
-    pub(crate) async fn from_unique_name(handle_token: &HandleToken) -> Result<Request<T>, Error> {
-        let path =
-            Proxy::unique_name("/org/freedesktop/portal/desktop/request", handle_token).await?;
-        #[cfg(feature = "tracing")]
-        tracing::info!("Creating a org.freedesktop.portal.Request {}", path);
-        Self::new(path).await
+    /// Waits for the response, unless it was already received.
+    ///
+    /// Cancelled with `Proxy::cancel_request` or `Proxy::close_all`.
+    pub(crate) async fn wait_response(&mut self) -> Result<(), Error> {
+        let Some(cancelled) = self.5.take() else {
+            return Ok(());
+        };
+        let response = match future::select(Box::pin(self.prepare_response()), cancelled).await {
+            future::Either::Left((response, _)) => Some(response),
+            future::Either::Right(_) => None,
+        };
+        response.unwrap_or_else(|| Err(self.response_error(ResponseError::Cancelled)))
     }
 
     pub(crate) async fn prepare_response(&mut self) -> Result<(), Error> {
         let message = self.1.next().await.ok_or(Error::NoResponse)?;
+        // The request object is removed by the portal once it responded
+        self.6.remove(&self.path().to_owned().into());
         #[cfg(feature = "tracing")]
         tracing::info!("Received signal 'Response' on '{}'", self.0.interface());
         let response = match message.body::<Response<T>>()? {
-            Response::Err(e) => Err(e.into()),
+            Response::Err(e) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    "Request {} on '{}' didn't succeed: {}",
+                    self.handle_token(),
+                    self.0.interface(),
+                    e
+                );
+                Err(self.response_error(e))
+            }
             Response::Ok(r) => Ok(r),
         };
         #[cfg(feature = "tracing")]
         tracing::debug!("Received response {:#?}", response);
         let r = response as Result<T, Error>;
         *self.2.get_mut().unwrap() = Some(r);
+        let raw = match message.body::<Response<HashMap<String, OwnedValue>>>()? {
+            Response::Err(e) => Err(e),
+            Response::Ok(r) => Ok(r),
+        };
+        self.4 = Some(raw);
         Ok(())
     }
 
     /// The corresponding response if the request was successful.
     pub fn response(&self) -> Result<T, Error> {
-        // It should be safe to unwrap here as we are sure we have received a response
-        // by the time the user calls response
-        self.2.lock().unwrap().take().unwrap()
+        self.2
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or(Err(Error::NoResponse))
+    }
+
+    /// The untyped results vardict of the response if the request was
+    /// successful.
+    ///
+    /// This gives access to results not yet supported by [`Self::response`],
+    /// for example ones added by a newer version of the portal. It bypasses
+    /// any type checking, it is up to the caller to convert the values to the
+    /// expected types.
+    pub fn response_raw(&self) -> Result<HashMap<String, OwnedValue>, Error> {
+        self.4
+            .clone()
+            .ok_or(Error::NoResponse)?
+            .map_err(|e| self.response_error(e))
     }
 
     /// Closes the portal request to which this object refers and ends all
@@ -262,12 +363,165 @@ where
     /// See also [`Close`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-Request.Close).
     #[doc(alias = "Close")]
     pub async fn close(&self) -> Result<(), Error> {
-        self.0.call("Close", &()).await
+        self.0.call::<()>("Close", &()).await?;
+        self.6.remove(&self.path().to_owned().into());
+        Ok(())
+    }
+
+    /// Waits for the response for at most `timeout`, closing the request
+    /// once it elapses, which dismisses the related dialogs.
+    ///
+    /// This is meant for the requests returned before their response, e.g.
+    /// by
+    /// [`DynamicLauncherProxy::send_prepare_install`](crate::desktop::dynamic_launcher::DynamicLauncherProxy::send_prepare_install),
+    /// the other ones already received it.
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use ashpd::{
+    ///     desktop::{dynamic_launcher::DynamicLauncherProxy, Icon},
+    ///     WindowIdentifier,
+    /// };
+    ///
+    /// async fn run() -> ashpd::Result<()> {
+    ///     let proxy = DynamicLauncherProxy::new().await?;
+    ///     let response = proxy
+    ///         .send_prepare_install(
+    ///             &WindowIdentifier::default(),
+    ///             "My App",
+    ///             Icon::with_names(["dialog-symbolic"]),
+    ///             (),
+    ///         )
+    ///         .await?
+    ///         .response_with_timeout(Duration::from_secs(60))
+    ///         .await?;
+    ///     println!("Chosen name: {}", response.name());
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ResponseError::Cancelled`] if the timeout elapsed, see
+    /// [`Error::is_cancelled`].
+    pub async fn response_with_timeout(mut self, timeout: Duration) -> Result<T, Error> {
+        let timed_out = {
+            let response = Box::pin(self.wait_response());
+            let timeout = Box::pin(crate::proxy::sleep(timeout));
+            match future::select(response, timeout).await {
+                future::Either::Left((response, _)) => {
+                    response?;
+                    false
+                }
+                future::Either::Right(_) => true,
+            }
+        };
+        if timed_out {
+            #[cfg(feature = "tracing")]
+            tracing::info!("Request {} timed out", self.handle_token());
+            self.close().await?;
+            return Err(self.response_error(ResponseError::Cancelled));
+        }
+        self.response()
+    }
+
+    /// Closes the request without waiting for the portal to handle it, for
+    /// usage in `Drop` implementations.
+    pub(crate) fn close_in_background(&self) {
+        let path = OwnedObjectPath::from(self.path().to_owned());
+        self.6.remove(&path);
+        let connection = self.0.connection().clone();
+        Proxy::close_in_background(connection, REQUEST_INTERFACE, path);
     }
 
     pub(crate) fn path(&self) -> &ObjectPath<'_> {
         self.0.path()
     }
+
+    /// The handle token of the request, the last element of its path.
+    fn handle_token(&self) -> &str {
+        let path = self.path().as_str();
+        path.rsplit('/').next().unwrap_or(path)
+    }
+
+    fn response_error(&self, code: ResponseError) -> Error {
+        Error::Response {
+            token: Some(self.handle_token().to_owned()),
+            code,
+        }
+    }
+}
+
+/// A portal request waiting for its response, see
+/// [`PortalProxy::pending_requests`](crate::PortalProxy::pending_requests).
+#[derive(Debug, Clone)]
+pub struct PendingRequest {
+    handle_token: String,
+    interface: String,
+    method: String,
+    created: Instant,
+}
+
+impl PendingRequest {
+    pub(crate) fn new(handle_token: &HandleToken, interface: &str, method: &str) -> Self {
+        Self {
+            handle_token: handle_token.to_string(),
+            interface: interface.to_owned(),
+            method: method.to_owned(),
+            created: Instant::now(),
+        }
+    }
+
+    /// The handle token of the request, see
+    /// [`PortalProxy::cancel_request`](crate::PortalProxy::cancel_request).
+    pub fn handle_token(&self) -> &str {
+        &self.handle_token
+    }
+
+    /// The portal interface, e.g. `org.freedesktop.portal.FileChooser`.
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    /// The method that created the request, e.g. `OpenFile`.
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// When the request was created.
+    pub fn created(&self) -> Instant {
+        self.created
+    }
+}
+
+impl<T> IntoFuture for Request<T>
+where
+    T: for<'de> Deserialize<'de> + Type + Debug + Send + 'static,
+{
+    type Output = Result<T, Error>;
+    type IntoFuture = future::BoxFuture<'static, Result<T, Error>>;
+
+    /// Waits for the response, unless it was already received.
+    fn into_future(mut self) -> Self::IntoFuture {
+        Box::pin(async move {
+            self.wait_response().await?;
+            self.response()
+        })
+    }
+}
+
+impl<T> Drop for Request<T>
+where
+    T: for<'de> Deserialize<'de> + Type + Debug,
+{
+    fn drop(&mut self) {
+        // The response was never received, the future was dropped mid-flight,
+        // unless the request was already closed, e.g. with `cancel_request`
+        if self.4.is_none() && self.6.contains(&self.path().to_owned().into()) {
+            self.close_in_background();
+        }
+    }
 }
 
 impl<T> Debug for Request<T>
@@ -280,3 +534,384 @@ where
             .finish()
     }
 }
+
+#[cfg(all(test, feature = "async-std"))]
+mod tests {
+    use std::{collections::HashMap, future::Future, pin::Pin, time::Duration};
+
+    use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+    use futures_util::{future, StreamExt};
+    use zbus::{
+        dbus_interface,
+        zvariant::{OwnedObjectPath, OwnedValue, Value},
+        MessageHeader, ObjectServer,
+    };
+
+    use super::{Request, Response, ResponseError};
+    use crate::{
+        desktop::HandleToken,
+        proxy::{Proxy, DESKTOP_PATH},
+        Error,
+    };
+
+    struct MockPortal {
+        started: UnboundedSender<()>,
+        closed: UnboundedSender<()>,
+    }
+
+    #[dbus_interface(name = "org.freedesktop.ashpd.Test")]
+    impl MockPortal {
+        // Never emits a response, like a dialog left open by the user
+        async fn start(
+            &self,
+            #[zbus(header)] header: MessageHeader<'_>,
+            #[zbus(object_server)] server: &ObjectServer,
+            options: HashMap<String, OwnedValue>,
+        ) -> zbus::fdo::Result<OwnedObjectPath> {
+            let sender = header.sender().ok().flatten().unwrap();
+            let token = String::try_from(options["handle_token"].clone()).unwrap();
+            let path = OwnedObjectPath::try_from(format!(
+                "/org/freedesktop/portal/desktop/request/{}/{token}",
+                sender.trim_start_matches(':').replace('.', "_")
+            ))
+            .unwrap();
+            let request = MockRequest {
+                owner: sender.to_string(),
+                closed: self.closed.clone(),
+            };
+            server.at(path.clone(), request).await?;
+            self.started.unbounded_send(()).unwrap();
+            Ok(path)
+        }
+    }
+
+    struct MockRequest {
+        owner: String,
+        closed: UnboundedSender<()>,
+    }
+
+    #[dbus_interface(name = "org.freedesktop.portal.Request")]
+    impl MockRequest {
+        // Like the portal, only lets the caller that created the request close it
+        fn close(&self, #[zbus(header)] header: MessageHeader<'_>) -> zbus::fdo::Result<()> {
+            let sender = header.sender().ok().flatten().unwrap();
+            if sender.as_str() != self.owner {
+                return Err(zbus::fdo::Error::AccessDenied(sender.to_string()));
+            }
+            self.closed.unbounded_send(()).unwrap();
+            Ok(())
+        }
+    }
+
+    /// Serves [`MockPortal`], returns `None` if the test is skipped.
+    async fn mock_portal() -> Option<(
+        zbus::Connection,
+        UnboundedReceiver<()>,
+        UnboundedReceiver<()>,
+    )> {
+        let (started, started_receiver) = unbounded();
+        let (closed, closed_receiver) = unbounded();
+        let server = crate::proxy::mock_desktop().await?;
+        server
+            .object_server()
+            .at(DESKTOP_PATH, MockPortal { started, closed })
+            .await
+            .unwrap();
+        Some((server, started_receiver, closed_receiver))
+    }
+
+    /// Sends a request to [`MockPortal`], once the portal started it.
+    async fn start<'a>(
+        proxy: &'a Proxy<'a>,
+        handle_token: &'a HandleToken,
+        started: &mut UnboundedReceiver<()>,
+    ) -> Pin<Box<dyn Future<Output = Result<Request<()>, Error>> + 'a>> {
+        let options = HashMap::from([("handle_token", Value::from(handle_token.to_string()))]);
+        let mut request = Box::pin(proxy.empty_request(handle_token, "Start", options));
+        match future::select(request.as_mut(), started.next()).await {
+            future::Either::Left(_) => panic!("The request shouldn't have a response"),
+            future::Either::Right(_) => request,
+        }
+    }
+
+    /// Whether the portal request got closed, within a few seconds.
+    async fn is_closed(closed: &mut UnboundedReceiver<()>) -> bool {
+        let timeout = async_std::task::sleep(Duration::from_secs(5));
+        futures_util::pin_mut!(timeout);
+        matches!(
+            future::select(closed.next(), timeout).await,
+            future::Either::Left((Some(()), _))
+        )
+    }
+
+    #[test]
+    fn response_without_results() {
+        let ctxt = zbus::zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        let encoded = zbus::zvariant::to_bytes(ctxt, &(0u32,)).unwrap();
+        let response =
+            zbus::zvariant::from_slice::<_, Response<HashMap<String, OwnedValue>>>(&encoded, ctxt);
+        assert!(response.is_err());
+
+        let encoded =
+            zbus::zvariant::to_bytes(ctxt, &(1u32, HashMap::<&str, Value<'_>>::new())).unwrap();
+        let response: Response<HashMap<String, OwnedValue>> =
+            zbus::zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert!(matches!(response, Response::Err(ResponseError::Cancelled)));
+    }
+
+    #[test]
+    fn dropped_request_is_closed() {
+        async_std::task::block_on(async {
+            let Some((_server, mut started, mut closed)) = mock_portal().await else {
+                return;
+            };
+            let proxy = Proxy::new_desktop("org.freedesktop.ashpd.Test")
+                .await
+                .unwrap();
+
+            let handle_token = HandleToken::default();
+            drop(start(&proxy, &handle_token, &mut started).await);
+            assert!(is_closed(&mut closed).await, "The request wasn't closed");
+            assert!(proxy.pending_requests().is_empty());
+        });
+    }
+
+    #[test]
+    fn close_all_cancels_requests() {
+        async_std::task::block_on(async {
+            let Some((_server, mut started, mut closed)) = mock_portal().await else {
+                return;
+            };
+            let proxy = Proxy::new_desktop("org.freedesktop.ashpd.Test")
+                .await
+                .unwrap();
+
+            let other = Proxy::new_desktop("org.freedesktop.ashpd.Test")
+                .await
+                .unwrap();
+
+            let handle_token = HandleToken::default();
+            let request = start(&proxy, &handle_token, &mut started).await;
+            let other_token = HandleToken::default();
+            let other_request = start(&other, &other_token, &mut started).await;
+            proxy.close_all().await.unwrap();
+            assert!(closed.try_recv().is_ok());
+            assert!(request.await.unwrap_err().is_cancelled());
+            // The requests of the other proxies are left alone
+            assert!(closed.try_recv().is_err());
+            assert!(proxy.pending_requests().is_empty());
+            assert_eq!(other.pending_requests().len(), 1);
+            drop(other_request);
+            assert!(is_closed(&mut closed).await, "The request wasn't closed");
+            assert!(other.pending_requests().is_empty());
+        });
+    }
+
+    #[test]
+    fn close_all_on_own_connection() {
+        async_std::task::block_on(async {
+            let Some((_server, mut started, mut closed)) = mock_portal().await else {
+                return;
+            };
+            let connection = zbus::Connection::session().await.unwrap();
+            let proxy = Proxy::from_connection(&connection, "org.freedesktop.ashpd.Test")
+                .await
+                .unwrap();
+
+            let handle_token = HandleToken::default();
+            let request = start(&proxy, &handle_token, &mut started).await;
+            // Closed through the connection that created it
+            proxy.close_all().await.unwrap();
+            assert!(closed.try_recv().is_ok());
+            assert!(request.await.unwrap_err().is_cancelled());
+
+            let handle_token = HandleToken::default();
+            let request = start(&proxy, &handle_token, &mut started).await;
+            assert!(proxy
+                .cancel_request(&handle_token.to_string())
+                .await
+                .unwrap());
+            assert!(closed.try_recv().is_ok());
+            assert!(request.await.unwrap_err().is_cancelled());
+        });
+    }
+
+    #[test]
+    fn lost_connection_forgets_its_requests() {
+        async_std::task::block_on(async {
+            let Some((_server, mut started, _closed)) = mock_portal().await else {
+                return;
+            };
+            let shared = Proxy::new_desktop("org.freedesktop.ashpd.Test")
+                .await
+                .unwrap();
+            let connection = zbus::Connection::session().await.unwrap();
+            let owned = Proxy::from_connection(&connection, "org.freedesktop.ashpd.Test")
+                .await
+                .unwrap();
+
+            let (lost_token, kept_token) = (HandleToken::default(), HandleToken::default());
+            let lost = start(&shared, &lost_token, &mut started).await;
+            let _kept = start(&owned, &kept_token, &mut started).await;
+            let session = Proxy::connection().await.unwrap();
+            Proxy::connection_lost(session.unique_name());
+
+            assert!(shared.pending_requests().is_empty());
+            assert!(lost.await.unwrap_err().is_cancelled());
+            assert_eq!(owned.pending_requests().len(), 1);
+        });
+    }
+
+    #[test]
+    fn cancel_pending_request() {
+        async_std::task::block_on(async {
+            let Some((_server, mut started, mut closed)) = mock_portal().await else {
+                return;
+            };
+            let proxy = Proxy::new_desktop("org.freedesktop.ashpd.Test")
+                .await
+                .unwrap();
+
+            let handle_token = HandleToken::default();
+            let request = start(&proxy, &handle_token, &mut started).await;
+            let pending = proxy.pending_requests();
+            assert_eq!(pending.len(), 1);
+            assert_eq!(pending[0].handle_token(), handle_token.to_string());
+            assert_eq!(pending[0].interface(), "org.freedesktop.ashpd.Test");
+            assert_eq!(pending[0].method(), "Start");
+
+            assert!(proxy
+                .cancel_request(pending[0].handle_token())
+                .await
+                .unwrap());
+            assert!(closed.try_recv().is_ok());
+            assert!(proxy.pending_requests().is_empty());
+            assert!(request.await.unwrap_err().is_cancelled());
+            assert!(!proxy
+                .cancel_request(pending[0].handle_token())
+                .await
+                .unwrap());
+        });
+    }
+
+    #[test]
+    fn cancelled_error_has_token() {
+        async_std::task::block_on(async {
+            let Some((_server, mut started, _closed)) = mock_portal().await else {
+                return;
+            };
+            let proxy = Proxy::new_desktop("org.freedesktop.ashpd.Test")
+                .await
+                .unwrap();
+
+            let handle_token = HandleToken::default();
+            let request = start(&proxy, &handle_token, &mut started).await;
+            assert!(proxy
+                .cancel_request(&handle_token.to_string())
+                .await
+                .unwrap());
+            let err = request.await.unwrap_err();
+            assert!(matches!(
+                &err,
+                Error::Response {
+                    token: Some(token),
+                    code: ResponseError::Cancelled,
+                } if *token == handle_token.to_string()
+            ));
+            assert!(err.to_string().contains(&handle_token.to_string()));
+        });
+    }
+
+    #[test]
+    fn reused_token_is_rejected() {
+        async_std::task::block_on(async {
+            let Some((_server, mut started, mut closed)) = mock_portal().await else {
+                return;
+            };
+            let proxy = Proxy::new_desktop("org.freedesktop.ashpd.Test")
+                .await
+                .unwrap();
+
+            let handle_token = HandleToken::default();
+            let request = start(&proxy, &handle_token, &mut started).await;
+            let options = HashMap::from([("handle_token", Value::from(handle_token.to_string()))]);
+            let err = proxy
+                .empty_request(&handle_token, "Start", options)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::TokenInUse(token) if token == handle_token.to_string()));
+            // The first request is still tracked and closed
+            assert_eq!(proxy.pending_requests().len(), 1);
+            drop(request);
+            assert!(is_closed(&mut closed).await, "The request wasn't closed");
+            assert!(proxy.pending_requests().is_empty());
+        });
+    }
+
+    #[test]
+    fn await_pending_request() {
+        async_std::task::block_on(async {
+            let Some((server, mut started, _closed)) = mock_portal().await else {
+                return;
+            };
+            let proxy = Proxy::new_desktop("org.freedesktop.ashpd.Test")
+                .await
+                .unwrap();
+
+            let handle_token = HandleToken::default();
+            let options = HashMap::from([("handle_token", Value::from(handle_token.to_string()))]);
+            let request = proxy
+                .send_request::<()>(&handle_token, "Start", options)
+                .await
+                .unwrap();
+            assert!(started.next().await.is_some());
+            let path = request.path().to_owned();
+            let respond = async {
+                async_std::task::sleep(Duration::from_millis(100)).await;
+                server
+                    .emit_signal(
+                        None::<&str>,
+                        &path,
+                        "org.freedesktop.portal.Request",
+                        "Response",
+                        &(0u32, HashMap::<String, Value>::new()),
+                    )
+                    .await
+                    .unwrap();
+            };
+            let (response, ()) = futures_util::join!(async { request.await }, respond);
+            response.unwrap();
+            assert!(proxy.pending_requests().is_empty());
+        });
+    }
+
+    #[test]
+    fn response_with_timeout() {
+        async_std::task::block_on(async {
+            let Some((_server, mut started, mut closed)) = mock_portal().await else {
+                return;
+            };
+            let proxy = Proxy::new_desktop("org.freedesktop.ashpd.Test")
+                .await
+                .unwrap();
+
+            let handle_token = HandleToken::default();
+            let options = HashMap::from([("handle_token", Value::from(handle_token.to_string()))]);
+            let request = proxy
+                .send_request::<()>(&handle_token, "Start", options)
+                .await
+                .unwrap();
+            assert!(started.next().await.is_some());
+            assert_eq!(proxy.pending_requests().len(), 1);
+
+            let err = request
+                .response_with_timeout(Duration::from_millis(100))
+                .await
+                .unwrap_err();
+            assert!(err.is_cancelled());
+            // Closed before returning, without waiting for the drop
+            assert!(closed.try_recv().is_ok());
+            assert!(proxy.pending_requests().is_empty());
+        });
+    }
+}
@@ -38,6 +38,8 @@
 //! }
 //! ```
 
+#[cfg(feature = "pipewire")]
+use std::os::unix::prelude::{AsRawFd, FromRawFd};
 use std::{
     collections::HashMap,
     fmt::Debug,
@@ -46,12 +48,19 @@ use std::{
 
 use enumflags2::{bitflags, BitFlags};
 use futures_util::TryFutureExt;
+use once_cell::sync::OnceCell;
 use serde::Deserialize;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{DeserializeDict, OwnedFd, SerializeDict, Type, Value};
 
-use super::{HandleToken, Request, Session};
-use crate::{proxy::Proxy, Error, WindowIdentifier};
+#[cfg(feature = "wayland")]
+pub use super::outputs::{list_outputs, OutputInfo};
+use super::{HandleToken, PortalSession, Request, Session, SessionGuard};
+use crate::{
+    helpers::Redacted,
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalError, PortalProxy, WindowIdentifier,
+};
 
 #[bitflags]
 #[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Copy, Clone, Debug, Type)]
@@ -70,6 +79,52 @@ pub enum SourceType {
     Virtual,
 }
 
+/// The kind of source a [`Stream`] records.
+///
+/// Unlike [`SourceType`], values that aren't known to this crate are kept in
+/// [`StreamSourceType::Unknown`] instead of failing to parse the streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamSourceType {
+    /// A monitor.
+    Monitor,
+    /// A specific window.
+    Window,
+    /// A virtual monitor.
+    Virtual,
+    /// A source type added after this version of the crate, with its raw
+    /// value.
+    Unknown(u32),
+}
+
+impl StreamSourceType {
+    /// The matching [`SourceType`], if the source type is known.
+    pub fn source_type(self) -> Option<SourceType> {
+        match self {
+            Self::Monitor => Some(SourceType::Monitor),
+            Self::Window => Some(SourceType::Window),
+            Self::Virtual => Some(SourceType::Virtual),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+impl From<u32> for StreamSourceType {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Monitor,
+            2 => Self::Window,
+            4 => Self::Virtual,
+            value => Self::Unknown(value),
+        }
+    }
+}
+
+impl From<SourceType> for StreamSourceType {
+    fn from(source_type: SourceType) -> Self {
+        Self::from(source_type as u32)
+    }
+}
+
 #[bitflags]
 #[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Copy, Clone, Type)]
 #[repr(u32)]
@@ -85,23 +140,40 @@ pub enum CursorMode {
     #[doc(alias = "XDP_CURSOR_MODE_METADATA")]
     /// The cursor is not part of the screen cast stream, but sent as PipeWire
     /// stream metadata.
+    ///
+    /// The position and bitmap of the cursor are attached to the buffers of
+    /// each stream, as a `SPA_META_Cursor` metadata, rather than to the
+    /// [`Stream`] properties returned by the portal. With multiple monitors,
+    /// the position is relative to each stream, and backends usually only
+    /// send the bitmap when it changes. See
+    /// [`ScreenCastGuard::has_cursor_metadata`].
     Metadata,
 }
 
-#[derive(Default, Serialize_repr, PartialEq, Eq, Debug, Copy, Clone, Type)]
+#[derive(Default, Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Copy, Clone, Type)]
 #[doc(alias = "XdpPersistMode")]
 #[repr(u32)]
 /// Persistence mode for a screencast session.
+///
+/// The restore token returned once the session is started, see
+/// [`Streams::restore_token`], can be passed to
+/// [`Screencast::select_sources`] to restore the same sources in a later
+/// session without asking the user again. Restore tokens are single use:
+/// store the new token of each session, the previous one is invalidated.
 pub enum PersistMode {
     #[doc(alias = "XDP_PERSIST_MODE_NONE")]
     #[default]
-    /// Do not persist.
+    /// Do not persist. Backends usually don't return a restore token in this
+    /// mode, it is still available if they do.
     DoNot = 0,
     #[doc(alias = "XDP_PERSIST_MODE_TRANSIENT")]
-    /// Persist while the application is running.
+    /// Persist while the application is running. The restore token is
+    /// invalidated once the application exits, it can't be used after a
+    /// restart.
     Application = 1,
     #[doc(alias = "XDP_PERSIST_MODE_PERSISTENT")]
-    /// Persist until explicitly revoked.
+    /// Persist until explicitly revoked by the user. The restore token can be
+    /// stored to restore the sources after a restart.
     ExplicitlyRevoked = 2,
 }
 
@@ -115,7 +187,7 @@ struct CreateSessionOptions {
     session_handle_token: HandleToken,
 }
 
-#[derive(SerializeDict, Type, Debug, Default)]
+#[derive(SerializeDict, Type, Default)]
 /// Specified options for a [`Screencast::select_sources`] request.
 #[zvariant(signature = "dict")]
 struct SelectSourcesOptions {
@@ -166,6 +238,19 @@ impl SelectSourcesOptions {
     }
 }
 
+impl Debug for SelectSourcesOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SelectSourcesOptions")
+            .field("handle_token", &self.handle_token)
+            .field("types", &self.types)
+            .field("multiple", &self.multiple)
+            .field("cursor_mode", &self.cursor_mode)
+            .field("restore_token", &Redacted(&self.restore_token))
+            .field("persist_mode", &self.persist_mode)
+            .finish()
+    }
+}
+
 #[derive(SerializeDict, Type, Debug, Default)]
 /// Specified options for a [`Screencast::start`] request.
 #[zvariant(signature = "dict")]
@@ -188,27 +273,51 @@ struct CreateSession {
 /// A response to a [`Screencast::start`] request.
 #[zvariant(signature = "dict")]
 pub struct Streams {
-    streams: Vec<Stream>,
+    streams: Option<Vec<Stream>>,
     restore_token: Option<String>,
+    persist_mode: Option<PersistMode>,
 }
 
 impl Streams {
-    /// The session restore token.
+    /// The session restore token, if the backend provided one, see
+    /// [`PersistMode`].
+    ///
+    /// The token is redacted from the [`Debug`] output.
     pub fn restore_token(&self) -> Option<&str> {
         self.restore_token.as_deref()
     }
 
+    /// The persist mode the backend started the session with, which can be
+    /// lower than the one passed to [`Screencast::select_sources`], for
+    /// example if the user chose not to remember the selection. Compare it
+    /// with the requested one to know whether the [`Self::restore_token`]
+    /// will survive a restart, see [`PersistMode::ExplicitlyRevoked`].
+    ///
+    /// [`None`] if the portal didn't report it.
+    pub fn effective_persist_mode(&self) -> Option<PersistMode> {
+        self.persist_mode
+    }
+
     /// The list of streams.
+    ///
+    /// **Note** the list may be empty if the user didn't select any source,
+    /// which is different from the user cancelling the request.
     pub fn streams(&self) -> &[Stream] {
-        &self.streams
+        self.streams.as_deref().unwrap_or_default()
+    }
+
+    /// Whether the user didn't select any source.
+    pub fn is_empty(&self) -> bool {
+        self.streams().is_empty()
     }
 }
 
 impl Debug for Streams {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("Streams")
-            .field(&self.restore_token)
+            .field(&Redacted(&self.restore_token))
             .field(&self.streams)
+            .field(&self.persist_mode)
             .finish()
     }
 }
@@ -243,14 +352,33 @@ impl Stream {
     }
 
     /// The source type of the stream.
-    pub fn source_type(&self) -> Option<SourceType> {
-        self.1.source_type
+    ///
+    /// Added in version 3 of the interface.
+    pub fn source_type(&self) -> Option<StreamSourceType> {
+        self.1.source_type.map(StreamSourceType::from)
     }
 
     /// The stream identifier.
+    ///
+    /// The identifier is opaque and unique within the session. It persists
+    /// across sessions restored using a restore token, so it can be used to
+    /// check that a restored stream corresponds to the same source as before.
+    ///
+    /// Added in version 4 of the interface.
     pub fn id(&self) -> Option<&str> {
         self.1.id.as_deref()
     }
+
+    /// An identifier used to map the different aspects of the resource the
+    /// stream corresponds to, for example to match the stream with a region of
+    /// an absolute libei device in a remote desktop session.
+    ///
+    /// Unlike [`Stream::id`], it is not guaranteed to persist across sessions.
+    ///
+    /// Added in version 5 of the interface.
+    pub fn mapping_id(&self) -> Option<&str> {
+        self.1.mapping_id.as_deref()
+    }
 }
 
 impl Debug for Stream {
@@ -261,6 +389,7 @@ impl Debug for Stream {
             .field("size", &self.size())
             .field("source_type", &self.source_type())
             .field("id", &self.id())
+            .field("mapping_id", &self.mapping_id())
             .finish()
     }
 }
@@ -269,9 +398,65 @@ impl Debug for Stream {
 #[zvariant(signature = "dict")]
 struct StreamProperties {
     id: Option<String>,
+    mapping_id: Option<String>,
     position: Option<(i32, i32)>,
     size: Option<(i32, i32)>,
-    source_type: Option<SourceType>,
+    source_type: Option<u32>,
+}
+
+/// A started screen cast session that gets closed once dropped, see
+/// [`Screencast::connect`].
+///
+/// **Note** when dropped, the session is closed in the background without
+/// waiting for the portal to handle it, as `Drop` can't be async.
+#[derive(Debug)]
+pub struct ScreenCastGuard<'a> {
+    session: SessionGuard<Session<'a>>,
+    streams: Streams,
+    cursor_mode: CursorMode,
+}
+
+impl<'a> ScreenCastGuard<'a> {
+    /// The screen cast session.
+    pub fn session(&self) -> &Session<'a> {
+        &self.session
+    }
+
+    /// The list of streams.
+    pub fn streams(&self) -> &[Stream] {
+        self.streams.streams()
+    }
+
+    /// The session restore token.
+    pub fn restore_token(&self) -> Option<&str> {
+        self.streams.restore_token()
+    }
+
+    /// The persist mode the session was started with, see
+    /// [`Streams::effective_persist_mode`].
+    pub fn effective_persist_mode(&self) -> Option<PersistMode> {
+        self.streams.effective_persist_mode()
+    }
+
+    /// The cursor mode the session was started with.
+    pub fn cursor_mode(&self) -> CursorMode {
+        self.cursor_mode
+    }
+
+    /// Whether the buffers of the streams carry the cursor as metadata, that
+    /// is the session uses [`CursorMode::Metadata`].
+    ///
+    /// The portal doesn't describe the cursor in the [`Stream`] properties,
+    /// applications have to request the `SPA_META_Cursor` metadata when
+    /// negotiating the PipeWire stream parameters to receive it.
+    pub fn has_cursor_metadata(&self) -> bool {
+        self.cursor_mode == CursorMode::Metadata
+    }
+
+    /// Retrieve the session back, without closing it.
+    pub fn into_inner(self) -> (Session<'a>, Streams) {
+        (self.session.into_inner(), self.streams)
+    }
 }
 
 /// The interface lets sandboxed applications create screen cast sessions.
@@ -279,13 +464,20 @@ struct StreamProperties {
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.ScreenCast`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.portal.ScreenCast).
 #[derive(Debug)]
 #[doc(alias = "org.freedesktop.portal.ScreenCast")]
-pub struct Screencast<'a>(Proxy<'a>);
+pub struct Screencast<'a>(Proxy<'a>, OnceCell<Option<AvailableSources>>);
 
 impl<'a> Screencast<'a> {
     /// Create a new instance of [`Screencast`].
     pub async fn new() -> Result<Screencast<'a>, Error> {
         let proxy = Proxy::new_desktop("org.freedesktop.portal.ScreenCast").await?;
-        Ok(Self(proxy))
+        Ok(Self(proxy, OnceCell::new()))
+    }
+
+    /// Same as [`Screencast::new`], failing with [`Error::NotSupported`] if the
+    /// portal doesn't provide `org.freedesktop.portal.ScreenCast`.
+    pub async fn new_checked() -> Result<Screencast<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.ScreenCast").await?;
+        Ok(Self(proxy, OnceCell::new()))
     }
 
     /// Create a screen cast session.
@@ -301,7 +493,7 @@ impl<'a> Screencast<'a> {
             self.0
                 .request::<CreateSession>(&options.handle_token, "CreateSession", &options)
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future(),
+            Session::from_unique_name(&self.0, &options.session_handle_token).into_future(),
         )?;
         assert_eq!(proxy.path().as_str(), &request.response()?.session_handle);
         Ok(proxy)
@@ -349,6 +541,25 @@ impl<'a> Screencast<'a> {
     ///   stream.
     /// * `types` - Sets the types of content to record.
     /// * `multiple`- Sets whether to allow selecting multiple sources.
+    /// * `restore_token` - The token of a previous session to restore, an
+    ///   empty token is ignored.
+    /// * `persist_mode` - How long the permission of the session should be
+    ///   remembered, see [`PersistMode`]. Restoring a session with
+    ///   [`PersistMode::DoNot`] consumes the token without providing a new
+    ///   one.
+    ///
+    /// To let the user pick a single window, use [`SourceType::Window`] as
+    /// `types` and set `multiple` to `false`.
+    ///
+    /// Returns [`PortalError::InvalidArgument`] without calling the portal if
+    /// `cursor_mode` or `types` are not part of
+    /// [`available_cursor_modes()`][`Screencast::available_cursor_modes`] or
+    /// [`available_source_types()`][`Screencast::available_source_types`].
+    ///
+    /// **Note** the portal doesn't provide a way to request a specific
+    /// resolution or frame rate. Those are negotiated with PipeWire when
+    /// connecting to the stream, see [`Stream::size`] for the size of the
+    /// source as displayed by the compositor.
     ///
     /// # Specifications
     ///
@@ -363,15 +574,24 @@ impl<'a> Screencast<'a> {
         restore_token: Option<&str>,
         persist_mode: PersistMode,
     ) -> Result<Request<()>, Error> {
+        if let Some((cursor_modes, source_types)) = self.available_sources().await {
+            check_sources(*cursor_modes, cursor_mode, *source_types, types)?;
+        }
         let options = SelectSourcesOptions::default()
             .cursor_mode(cursor_mode)
             .multiple(multiple)
             .types(types)
             .persist_mode(persist_mode)
-            .restore_token(restore_token);
-        self.0
+            .restore_token(restore_token.filter(|token| !token.is_empty()));
+        let request = self
+            .0
             .empty_request(&options.handle_token, "SelectSources", &(session, &options))
-            .await
+            .await?;
+        if request.response_raw().is_ok() {
+            // Remembered for `start`, the portal doesn't tell
+            session.set_sources_selected();
+        }
+        Ok(request)
     }
 
     /// Start the screen cast session.
@@ -391,6 +611,22 @@ impl<'a> Screencast<'a> {
     ///
     /// A list of [`Stream`] and an optional restore token.
     ///
+    /// If the user dismisses the dialog, [`Request::response`] returns
+    /// [`Error::Response`] with [`crate::desktop::ResponseError::Cancelled`],
+    /// while an empty [`Streams::streams`] means no source was selected.
+    ///
+    /// The dialog can be dismissed on behalf of the user, for example after a
+    /// timeout, by passing the handle token of the request listed in
+    /// [`PortalProxy::pending_requests`] to [`PortalProxy::cancel_request`]
+    /// on this proxy. The method then fails with
+    /// [`crate::desktop::ResponseError::Cancelled`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidState`] if the sources weren't successfully
+    /// selected with [`select_sources()`][`Screencast::select_sources`]
+    /// beforehand.
+    ///
     /// # Specifications
     ///
     /// See also [`Start`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-ScreenCast.Start).
@@ -400,6 +636,11 @@ impl<'a> Screencast<'a> {
         session: &Session<'_>,
         identifier: &WindowIdentifier,
     ) -> Result<Request<Streams>, Error> {
+        if !session.sources_selected() {
+            return Err(Error::InvalidState(
+                "The screen cast sources have to be selected before starting the session",
+            ));
+        }
         let options = StartCastOptions::default();
         self.0
             .request(
@@ -410,8 +651,67 @@ impl<'a> Screencast<'a> {
             .await
     }
 
+    /// Create a session, select the sources to record and start it.
+    ///
+    /// The session is closed if any of those steps fails, including the user
+    /// or [`PortalProxy::cancel_request`] cancelling the request, if the returned
+    /// future is dropped before completing, or once the returned
+    /// [`ScreenCastGuard`] is dropped.
+    ///
+    /// See [`Screencast::select_sources`] for the arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StaleRestoreToken`] if a `restore_token` was passed
+    /// with a `persist_mode` other than [`PersistMode::DoNot`] but the portal
+    /// didn't return a new restore token, in which case the application
+    /// should call it again without the token to let the user pick the
+    /// sources. If only some of the sources were restored, the session is
+    /// started with those and [`ScreenCastGuard::restore_token`] carries the
+    /// new token. Other failures, and an empty selection, are returned as is.
+    pub async fn connect(
+        &self,
+        identifier: &WindowIdentifier,
+        cursor_mode: CursorMode,
+        types: BitFlags<SourceType>,
+        multiple: bool,
+        restore_token: Option<&str>,
+        persist_mode: PersistMode,
+    ) -> Result<ScreenCastGuard<'a>, Error> {
+        let session = self.create_session().await?.close_on_drop();
+        let streams = async {
+            self.select_sources(
+                &session,
+                cursor_mode,
+                types,
+                multiple,
+                restore_token,
+                persist_mode,
+            )
+            .await?
+            .response()?;
+            self.start(&session, identifier).await?.response()
+        }
+        .await;
+        let streams = check_restored(restore_token, persist_mode, streams);
+        match streams {
+            Ok(streams) => Ok(ScreenCastGuard {
+                session,
+                streams,
+                cursor_mode,
+            }),
+            Err(err) => {
+                let _ = session.into_inner().close().await;
+                Err(err)
+            }
+        }
+    }
+
     /// Available cursor mode.
     ///
+    /// Can be used to only offer the supported modes before calling
+    /// [`Screencast::select_sources`].
+    ///
     /// # Specifications
     ///
     /// See also [`AvailableCursorModes`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-property-org-freedesktop-portal-ScreenCast.AvailableCursorModes).
@@ -420,8 +720,26 @@ impl<'a> Screencast<'a> {
         self.0.property("AvailableCursorModes").await
     }
 
+    /// The available cursor modes and source types, read once per proxy.
+    async fn available_sources(&self) -> &Option<AvailableSources> {
+        if let Some(sources) = self.1.get() {
+            return sources;
+        }
+        // Older portals might not expose the properties, leave it to the backend then
+        let sources =
+            match futures_util::join!(self.available_cursor_modes(), self.available_source_types())
+            {
+                (Ok(cursor_modes), Ok(source_types)) => Some((cursor_modes, source_types)),
+                _ => None,
+            };
+        self.1.get_or_init(|| sources)
+    }
+
     /// Available source types.
     ///
+    /// Can be used to only offer the supported types before calling
+    /// [`Screencast::select_sources`].
+    ///
     /// # Specifications
     ///
     /// See also [`AvailableSourceTypes`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-property-org-freedesktop-portal-ScreenCast.AvailableSourceTypes).
@@ -430,3 +748,642 @@ impl<'a> Screencast<'a> {
         self.0.property("AvailableSourceTypes").await
     }
 }
+
+impl AsProxy for Screencast<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for Screencast<'_> {}
+
+type AvailableSources = (BitFlags<CursorMode>, BitFlags<SourceType>);
+
+fn check_sources(
+    available_cursor_modes: BitFlags<CursorMode>,
+    cursor_mode: CursorMode,
+    available_types: BitFlags<SourceType>,
+    types: BitFlags<SourceType>,
+) -> Result<(), PortalError> {
+    if !available_cursor_modes.contains(cursor_mode) {
+        return Err(PortalError::InvalidArgument(format!(
+            "Unsupported cursor mode {cursor_mode:?}, available: {available_cursor_modes:?}"
+        )));
+    }
+    if !available_types.contains(types) {
+        return Err(PortalError::InvalidArgument(format!(
+            "Unsupported source types {types:?}, available: {available_types:?}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "pipewire")]
+/// A PipeWire stream consuming one of the screen cast [`Stream`]s, created with
+/// [`pipewire_stream`].
+///
+/// Keeps the PipeWire context and core it was created from alive.
+pub struct PipeWireStream<D> {
+    stream: pw::stream::Stream<D>,
+    node_id: u32,
+    _core: pw::Core,
+    _context: pw::Context<pw::MainLoop>,
+}
+
+#[cfg(feature = "pipewire")]
+impl<D> PipeWireStream<D> {
+    /// The underlying PipeWire stream.
+    ///
+    /// Listeners should be added before calling [`PipeWireStream::connect`].
+    pub fn stream(&self) -> &pw::stream::Stream<D> {
+        &self.stream
+    }
+
+    /// The PipeWire node the stream connects to.
+    pub fn node_id(&self) -> u32 {
+        self.node_id
+    }
+
+    /// Connect the stream to the screen cast node.
+    ///
+    /// # Arguments
+    ///
+    /// * `flags` - The stream flags, usually `AUTOCONNECT | MAP_BUFFERS`.
+    /// * `params` - The format parameters the application can handle.
+    pub fn connect(
+        &self,
+        flags: pw::stream::StreamFlags,
+        params: &mut [*const pw::spa::sys::spa_pod],
+    ) -> Result<(), pw::Error> {
+        self.stream
+            .connect(pw::spa::Direction::Input, Some(self.node_id), flags, params)
+    }
+}
+
+#[cfg(feature = "pipewire")]
+impl<D> Debug for PipeWireStream<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PipeWireStream")
+            .field("stream", &self.stream)
+            .field("node_id", &self.node_id)
+            .finish()
+    }
+}
+
+#[cfg(feature = "pipewire")]
+/// Create a PipeWire stream for the screen cast node `node_id`, using the
+/// remote returned by [`Screencast::open_pipe_wire_remote`].
+///
+/// The file descriptor is duplicated, the caller keeps ownership of `fd`.
+///
+/// ```rust,no_run
+/// use ashpd::desktop::screencast::pipewire_stream;
+///
+/// fn connect(screencast_fd: std::os::unix::io::RawFd, node_id: u32) -> Result<(), pw::Error> {
+///     let mainloop = pw::MainLoop::new()?;
+///     let stream = pipewire_stream::<()>(&mainloop, screencast_fd, node_id, "ashpd-screencast")?;
+///     // Add the stream listeners, then connect it with the supported formats
+///     stream.connect(
+///         pw::stream::StreamFlags::AUTOCONNECT | pw::stream::StreamFlags::MAP_BUFFERS,
+///         &mut [],
+///     )?;
+///     mainloop.run();
+///     Ok(())
+/// }
+/// ```
+pub fn pipewire_stream<D>(
+    mainloop: &pw::MainLoop,
+    fd: RawFd,
+    node_id: u32,
+    name: &str,
+) -> Result<PipeWireStream<D>, pw::Error> {
+    let context = pw::Context::new(mainloop)?;
+    let fd = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 3) };
+    if fd == -1 {
+        return Err(pw::Error::CreationFailed);
+    }
+    // Closed if the connection fails, owned by the core otherwise
+    let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+    let core = context.connect_fd(fd.as_raw_fd(), None)?;
+    let _ = fd.into_raw_fd();
+    let stream = pw::stream::Stream::new(
+        &core,
+        name,
+        pw::properties! {
+            *pw::keys::MEDIA_TYPE => "Video",
+            *pw::keys::MEDIA_CATEGORY => "Capture",
+            *pw::keys::MEDIA_ROLE => "Screen",
+        },
+    )?;
+    Ok(PipeWireStream {
+        stream,
+        node_id,
+        _core: core,
+        _context: context,
+    })
+}
+
+/// Report a session started with a restore token, asking for the sources to
+/// be persisted, but for which the portal returned no new restore token, as
+/// [`Error::StaleRestoreToken`].
+///
+/// Tokens are single use, a successful restore comes with a different token.
+/// The other failures and empty selections are passed through unchanged.
+fn check_restored(
+    restore_token: Option<&str>,
+    persist_mode: PersistMode,
+    streams: Result<Streams, Error>,
+) -> Result<Streams, Error> {
+    let restoring = restore_token.is_some_and(|token| !token.is_empty());
+    match streams {
+        Ok(streams)
+            if restoring
+                && persist_mode != PersistMode::DoNot
+                && streams.restore_token().is_none() =>
+        {
+            Err(Error::StaleRestoreToken)
+        }
+        streams => streams,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::desktop::ResponseError;
+
+    #[test]
+    fn effective_persist_mode() {
+        let ctxt = zbus::zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        // Asked for ExplicitlyRevoked, but the user only allowed a transient one
+        let mut results = HashMap::new();
+        results.insert(
+            "streams",
+            Value::from(Vec::<(u32, HashMap<&str, Value<'_>>)>::new()),
+        );
+        results.insert("restore_token", Value::from("token"));
+        results.insert("persist_mode", Value::from(1u32));
+        let encoded = zbus::zvariant::to_bytes(ctxt, &results).unwrap();
+        let streams: Streams = zbus::zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(
+            streams.effective_persist_mode(),
+            Some(PersistMode::Application)
+        );
+        assert_ne!(
+            streams.effective_persist_mode(),
+            Some(PersistMode::ExplicitlyRevoked)
+        );
+
+        results.remove("persist_mode");
+        let encoded = zbus::zvariant::to_bytes(ctxt, &results).unwrap();
+        let streams: Streams = zbus::zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(streams.effective_persist_mode(), None);
+    }
+
+    #[test]
+    fn stream_source_type() {
+        let ctxt = zbus::zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        let stream = |source_type: Option<u32>| {
+            let mut properties = HashMap::new();
+            if let Some(source_type) = source_type {
+                properties.insert("source_type", Value::from(source_type));
+            }
+            let encoded = zbus::zvariant::to_bytes(ctxt, &(42u32, properties)).unwrap();
+            zbus::zvariant::from_slice::<_, Stream>(&encoded, ctxt).unwrap()
+        };
+
+        assert_eq!(stream(None).source_type(), None);
+        assert_eq!(
+            stream(Some(1)).source_type(),
+            Some(StreamSourceType::Monitor)
+        );
+        assert_eq!(
+            stream(Some(2)).source_type(),
+            Some(StreamSourceType::Window)
+        );
+        assert_eq!(
+            stream(Some(4)).source_type(),
+            Some(StreamSourceType::Virtual)
+        );
+        let unknown = stream(Some(8)).source_type().unwrap();
+        assert_eq!(unknown, StreamSourceType::Unknown(8));
+        assert_eq!(unknown.source_type(), None);
+        assert_eq!(
+            StreamSourceType::from(SourceType::Window).source_type(),
+            Some(SourceType::Window)
+        );
+    }
+
+    #[test]
+    fn unsupported_sources() {
+        let cursor_modes = CursorMode::Hidden | CursorMode::Embedded;
+        let types = SourceType::Monitor | SourceType::Window;
+        assert!(check_sources(
+            cursor_modes,
+            CursorMode::Embedded,
+            types,
+            SourceType::Window.into()
+        )
+        .is_ok());
+        assert!(check_sources(cursor_modes, CursorMode::Metadata, types, types).is_err());
+        assert!(check_sources(
+            cursor_modes,
+            CursorMode::Hidden,
+            types,
+            SourceType::Virtual.into()
+        )
+        .is_err());
+    }
+
+    fn streams(count: usize) -> Streams {
+        Streams {
+            streams: Some(
+                (0..count)
+                    .map(|id| {
+                        Stream(
+                            id as u32,
+                            StreamProperties {
+                                id: None,
+                                mapping_id: None,
+                                position: None,
+                                size: None,
+                                source_type: None,
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
+            restore_token: Some("new-token".to_owned()),
+            persist_mode: None,
+        }
+    }
+
+    #[test]
+    fn stale_restore_token() {
+        let persist = PersistMode::ExplicitlyRevoked;
+        let without_token = || {
+            let mut streams = streams(1);
+            streams.restore_token = None;
+            streams
+        };
+        assert!(check_restored(None, persist, Ok(without_token())).is_ok());
+        assert!(check_restored(Some(""), persist, Ok(without_token())).is_ok());
+        assert!(check_restored(Some("token"), PersistMode::DoNot, Ok(without_token())).is_ok());
+        assert!(check_restored(Some("token"), persist, Ok(without_token()))
+            .unwrap_err()
+            .is_stale_restore_token());
+
+        // Nothing selected, or a failure, aren't about the token
+        assert!(check_restored(Some("token"), persist, Ok(streams(0)))
+            .unwrap()
+            .is_empty());
+        assert!(
+            check_restored(Some("token"), persist, Err(ResponseError::Other.into()))
+                .unwrap_err()
+                .is_ended()
+        );
+        assert!(
+            check_restored(Some("token"), persist, Err(ResponseError::Cancelled.into()))
+                .unwrap_err()
+                .is_cancelled()
+        );
+
+        let restored = check_restored(Some("token"), persist, Ok(streams(1))).unwrap();
+        assert_eq!(restored.restore_token(), Some("new-token"));
+        assert!(!format!("{restored:?}").contains("new-token"));
+    }
+
+    #[cfg(feature = "async-std")]
+    mod restore {
+        use std::{
+            sync::{Arc, Mutex},
+            time::Duration,
+        };
+
+        use zbus::{
+            dbus_interface,
+            zvariant::{OwnedObjectPath, OwnedValue},
+            MessageHeader,
+        };
+
+        use super::*;
+        use crate::proxy::DESKTOP_PATH;
+
+        type Options = HashMap<String, OwnedValue>;
+
+        /// Issues a new restore token on each start, like the backends do.
+        #[derive(Default)]
+        struct MockScreenCast {
+            selected: Arc<Mutex<Vec<Options>>>,
+            tokens: Arc<Mutex<u32>>,
+            /// Never responds to the start request, like a user ignoring the
+            /// dialog.
+            ignore_start: bool,
+            /// The paths of the closed sessions and requests.
+            closed: Arc<Mutex<Vec<String>>>,
+            /// The paths of the ignored start requests, once they can be
+            /// closed.
+            started: Arc<Mutex<Vec<String>>>,
+        }
+
+        struct MockSession(Arc<Mutex<Vec<String>>>, String);
+
+        #[dbus_interface(name = "org.freedesktop.portal.Session")]
+        impl MockSession {
+            fn close(&self) {
+                self.0.lock().unwrap().push(self.1.clone());
+            }
+        }
+
+        struct MockRequest(Arc<Mutex<Vec<String>>>, String);
+
+        #[dbus_interface(name = "org.freedesktop.portal.Request")]
+        impl MockRequest {
+            fn close(&self) {
+                self.0.lock().unwrap().push(self.1.clone());
+            }
+        }
+
+        fn handle(header: &MessageHeader<'_>, kind: &str, token: &OwnedValue) -> String {
+            let sender = header.sender().ok().flatten().unwrap();
+            let token = String::try_from(token.clone()).unwrap();
+            format!(
+                "/org/freedesktop/portal/desktop/{kind}/{}/{token}",
+                sender.trim_start_matches(':').replace('.', "_")
+            )
+        }
+
+        async fn respond(
+            connection: &zbus::Connection,
+            path: &str,
+            results: HashMap<&str, Value<'_>>,
+        ) -> zbus::fdo::Result<OwnedObjectPath> {
+            connection
+                .emit_signal(
+                    None::<()>,
+                    path,
+                    "org.freedesktop.portal.Request",
+                    "Response",
+                    &(0u32, results),
+                )
+                .await?;
+            Ok(OwnedObjectPath::try_from(path).unwrap())
+        }
+
+        #[dbus_interface(name = "org.freedesktop.portal.ScreenCast")]
+        impl MockScreenCast {
+            async fn create_session(
+                &self,
+                #[zbus(header)] header: MessageHeader<'_>,
+                #[zbus(connection)] connection: &zbus::Connection,
+                options: Options,
+            ) -> zbus::fdo::Result<OwnedObjectPath> {
+                let session = handle(&header, "session", &options["session_handle_token"]);
+                connection
+                    .object_server()
+                    .at(
+                        session.as_str(),
+                        MockSession(self.closed.clone(), session.clone()),
+                    )
+                    .await?;
+                let mut results = HashMap::new();
+                results.insert("session_handle", Value::from(session));
+                let path = handle(&header, "request", &options["handle_token"]);
+                respond(connection, &path, results).await
+            }
+
+            async fn select_sources(
+                &self,
+                #[zbus(header)] header: MessageHeader<'_>,
+                #[zbus(connection)] connection: &zbus::Connection,
+                _session: OwnedObjectPath,
+                options: Options,
+            ) -> zbus::fdo::Result<OwnedObjectPath> {
+                let path = handle(&header, "request", &options["handle_token"]);
+                self.selected.lock().unwrap().push(options);
+                respond(connection, &path, HashMap::new()).await
+            }
+
+            async fn start(
+                &self,
+                #[zbus(header)] header: MessageHeader<'_>,
+                #[zbus(connection)] connection: &zbus::Connection,
+                _session: OwnedObjectPath,
+                _parent_window: &str,
+                options: Options,
+            ) -> zbus::fdo::Result<OwnedObjectPath> {
+                if self.ignore_start {
+                    let path = handle(&header, "request", &options["handle_token"]);
+                    connection
+                        .object_server()
+                        .at(
+                            path.as_str(),
+                            MockRequest(self.closed.clone(), path.clone()),
+                        )
+                        .await?;
+                    self.started.lock().unwrap().push(path.clone());
+                    return Ok(OwnedObjectPath::try_from(path).unwrap());
+                }
+                let token = {
+                    let mut tokens = self.tokens.lock().unwrap();
+                    *tokens += 1;
+                    format!("token-{tokens}")
+                };
+                let streams = vec![(42u32, HashMap::<&str, Value<'_>>::new())];
+                let mut results = HashMap::new();
+                results.insert("streams", Value::from(streams));
+                results.insert("restore_token", Value::from(token));
+                let path = handle(&header, "request", &options["handle_token"]);
+                respond(connection, &path, results).await
+            }
+        }
+
+        async fn connect(restore_token: Option<&str>, persist_mode: PersistMode) -> Option<String> {
+            let proxy = Screencast::new().await.unwrap();
+            let guard = proxy
+                .connect(
+                    &WindowIdentifier::default(),
+                    CursorMode::Hidden,
+                    SourceType::Monitor.into(),
+                    false,
+                    restore_token,
+                    persist_mode,
+                )
+                .await
+                .unwrap();
+            assert_eq!(guard.streams()[0].pipe_wire_node_id(), 42);
+            guard.restore_token().map(ToOwned::to_owned)
+        }
+
+        #[test]
+        fn cursor_metadata() {
+            async_std::task::block_on(async {
+                let Some(server) = crate::proxy::mock_desktop().await else {
+                    return;
+                };
+                let portal = MockScreenCast::default();
+                let selected = portal.selected.clone();
+                server
+                    .object_server()
+                    .at(DESKTOP_PATH, portal)
+                    .await
+                    .unwrap();
+
+                let proxy = Screencast::new().await.unwrap();
+                let guard = proxy
+                    .connect(
+                        &WindowIdentifier::default(),
+                        CursorMode::Metadata,
+                        SourceType::Monitor.into(),
+                        true,
+                        None,
+                        PersistMode::DoNot,
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(guard.cursor_mode(), CursorMode::Metadata);
+                assert!(guard.has_cursor_metadata());
+                assert_eq!(
+                    u32::try_from(selected.lock().unwrap()[0]["cursor_mode"].clone()).unwrap(),
+                    4
+                );
+            });
+        }
+
+        #[test]
+        fn start_after_selecting_elsewhere() {
+            async_std::task::block_on(async {
+                let Some(server) = crate::proxy::mock_desktop().await else {
+                    return;
+                };
+                server
+                    .object_server()
+                    .at(DESKTOP_PATH, MockScreenCast::default())
+                    .await
+                    .unwrap();
+
+                let proxy = Screencast::new().await.unwrap();
+                let session = proxy.create_session().await.unwrap();
+                let identifier = WindowIdentifier::default();
+                assert!(matches!(
+                    proxy.start(&session, &identifier).await,
+                    Err(Error::InvalidState(_))
+                ));
+
+                Screencast::new()
+                    .await
+                    .unwrap()
+                    .select_sources(
+                        &session,
+                        CursorMode::Hidden,
+                        SourceType::Monitor.into(),
+                        false,
+                        None,
+                        PersistMode::DoNot,
+                    )
+                    .await
+                    .unwrap();
+                let streams = proxy.start(&session, &identifier).await.unwrap();
+                assert_eq!(
+                    streams.response().unwrap().streams()[0].pipe_wire_node_id(),
+                    42
+                );
+            });
+        }
+
+        #[test]
+        fn cancel_start() {
+            async_std::task::block_on(async {
+                let Some(server) = crate::proxy::mock_desktop().await else {
+                    return;
+                };
+                let portal = MockScreenCast {
+                    ignore_start: true,
+                    ..Default::default()
+                };
+                let closed = portal.closed.clone();
+                let started = portal.started.clone();
+                server
+                    .object_server()
+                    .at(DESKTOP_PATH, portal)
+                    .await
+                    .unwrap();
+
+                let proxy = Screencast::new().await.unwrap();
+                let identifier = WindowIdentifier::default();
+                let connect = proxy.connect(
+                    &identifier,
+                    CursorMode::Hidden,
+                    SourceType::Monitor.into(),
+                    false,
+                    None,
+                    PersistMode::DoNot,
+                );
+                let cancel = async {
+                    let start = loop {
+                        // Closing it before the portal created it would fail
+                        let start = proxy
+                            .pending_requests()
+                            .into_iter()
+                            .find(|request| request.method() == "Start")
+                            .filter(|_| !started.lock().unwrap().is_empty());
+                        match start {
+                            Some(start) => break start,
+                            None => async_std::task::sleep(Duration::from_millis(10)).await,
+                        }
+                    };
+                    assert!(proxy.cancel_request(start.handle_token()).await.unwrap());
+                    start
+                };
+                let (result, start) = futures_util::join!(connect, cancel);
+                assert!(result.unwrap_err().is_cancelled());
+
+                let closed = closed.lock().unwrap();
+                assert_eq!(closed.len(), 2);
+                assert!(closed[0].ends_with(start.handle_token()));
+                assert!(closed[1].contains("/session/"));
+            });
+        }
+
+        #[test]
+        fn restore_across_restarts() {
+            async_std::task::block_on(async {
+                let Some(server) = crate::proxy::mock_desktop().await else {
+                    return;
+                };
+                let portal = MockScreenCast::default();
+                let selected = portal.selected.clone();
+                server
+                    .object_server()
+                    .at(DESKTOP_PATH, portal)
+                    .await
+                    .unwrap();
+
+                let token = connect(None, PersistMode::ExplicitlyRevoked).await;
+                assert_eq!(token.as_deref(), Some("token-1"));
+                // The stored token is used by a new proxy, as after a restart
+                let token = connect(token.as_deref(), PersistMode::ExplicitlyRevoked).await;
+                assert_eq!(token.as_deref(), Some("token-2"));
+                // The token is returned even if it wasn't asked for
+                let token = connect(Some(""), PersistMode::DoNot).await;
+                assert_eq!(token.as_deref(), Some("token-3"));
+
+                let selected = selected.lock().unwrap();
+                assert!(!selected[0].contains_key("restore_token"));
+                assert_eq!(
+                    u32::try_from(selected[0]["persist_mode"].clone()).unwrap(),
+                    2
+                );
+                assert_eq!(
+                    String::try_from(selected[1]["restore_token"].clone()).unwrap(),
+                    "token-1"
+                );
+                assert!(!selected[2].contains_key("restore_token"));
+                assert_eq!(
+                    u32::try_from(selected[2]["persist_mode"].clone()).unwrap(),
+                    0
+                );
+            });
+        }
+    }
+}
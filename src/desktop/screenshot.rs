@@ -17,6 +17,7 @@
 //!         .await?
 //!         .response()?;
 //!     println!("URI: {}", response.uri());
+//!     let file = response.open()?;
 //!     Ok(())
 //! }
 //! ```
@@ -33,10 +34,11 @@
 //!     Ok(())
 //! }
 //! ```
-use std::fmt::Debug;
+use std::{fmt::Debug, fs::File, io};
 
 use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
 
+pub use super::Color;
 use super::{HandleToken, Request};
 use crate::{proxy::Proxy, Error, WindowIdentifier};
 
@@ -68,6 +70,31 @@ impl Screenshot {
     pub fn uri(&self) -> &url::Url {
         &self.uri
     }
+
+    /// Opens the screenshot file for reading.
+    ///
+    /// Returns [`Error::IO`] with a description of the issue if the URI is
+    /// not a local file or if the file is not readable from within the
+    /// sandbox.
+    pub fn open(&self) -> Result<File, Error> {
+        let path = self.uri.to_file_path().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("The screenshot URI {} is not a local file", self.uri),
+            )
+        })?;
+        File::open(&path).map_err(|err| match err.kind() {
+            io::ErrorKind::PermissionDenied => io::Error::new(
+                err.kind(),
+                format!(
+                    "The screenshot {} is not readable from the sandbox",
+                    path.display()
+                ),
+            )
+            .into(),
+            _ => err.into(),
+        })
+    }
 }
 
 impl Debug for Screenshot {
@@ -82,31 +109,7 @@ struct ColorOptions {
     handle_token: HandleToken,
 }
 
-#[derive(DeserializeDict, Clone, Copy, PartialEq, Type)]
-/// The response of a [`ColorRequest`] request.
-///
-/// **Note** the values are normalized.
-#[zvariant(signature = "dict")]
-pub struct Color {
-    color: [f64; 3],
-}
-
 impl Color {
-    /// Red.
-    pub fn red(&self) -> f64 {
-        self.color[0]
-    }
-
-    /// Green.
-    pub fn green(&self) -> f64 {
-        self.color[1]
-    }
-
-    /// Blue.
-    pub fn blue(&self) -> f64 {
-        self.color[2]
-    }
-
     /// Creates a new builder-pattern struct instance to construct
     /// [`Color`].
     ///
@@ -116,45 +119,6 @@ impl Color {
     }
 }
 
-#[cfg(feature = "gtk3")]
-impl From<Color> for gtk3::gdk::RGBA {
-    fn from(color: Color) -> Self {
-        gtk3::gdk::RGBA::new(color.red(), color.green(), color.blue(), 1.0)
-    }
-}
-
-#[cfg(feature = "gtk4")]
-impl From<Color> for gtk4::gdk::RGBA {
-    fn from(color: Color) -> Self {
-        gtk4::gdk::RGBA::builder()
-            .red(color.red() as f32)
-            .green(color.green() as f32)
-            .blue(color.blue() as f32)
-            .build()
-    }
-}
-
-impl std::fmt::Debug for Color {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Color")
-            .field("red", &self.red())
-            .field("green", &self.green())
-            .field("blue", &self.blue())
-            .finish()
-    }
-}
-
-impl std::fmt::Display for Color {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&format!(
-            "({}, {}, {})",
-            self.red(),
-            self.green(),
-            self.blue()
-        ))
-    }
-}
-
 #[derive(Debug)]
 #[doc(alias = "org.freedesktop.portal.Screenshot")]
 struct ScreenshotProxy<'a>(Proxy<'a>);
@@ -263,7 +227,11 @@ impl ScreenshotRequest {
         self
     }
 
-    /// Sets whether the dialog should be a modal.
+    /// Sets whether the dialog should be a modal, `true` by default.
+    ///
+    /// A non-modal dialog doesn't block the application window, for example
+    /// when the screenshot is triggered by a keyboard shortcut. It only
+    /// applies when a dialog is shown, see [`Self::interactive`].
     #[must_use]
     pub fn modal(mut self, modal: impl Into<Option<bool>>) -> Self {
         self.options.modal = modal.into();
@@ -271,7 +239,16 @@ impl ScreenshotRequest {
     }
 
     /// Sets whether the dialog should offer customization before a screenshot
-    /// or not.
+    /// or not, `false` by default.
+    ///
+    /// An interactive screenshot always shows a dialog letting the user pick
+    /// the area, window or monitor to capture. A non-interactive one asks the
+    /// user for the permission the first time only, once granted the
+    /// screenshot is taken right away without any dialog, and
+    /// [`Self::modal`] has no effect.
+    ///
+    /// **Note** the portal has no option to target a specific monitor, use an
+    /// interactive screenshot to let the user pick it.
     #[must_use]
     pub fn interactive(mut self, interactive: impl Into<Option<bool>>) -> Self {
         self.options.interactive = interactive.into();
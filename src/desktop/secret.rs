@@ -18,27 +18,90 @@
 //! }
 //! ```
 
-use std::os::unix::prelude::AsRawFd;
+use std::{fmt::Debug, os::unix::prelude::AsRawFd};
 
 #[cfg(feature = "async-std")]
 use async_std::{os::unix::net::UnixStream, prelude::*};
 #[cfg(feature = "tokio")]
 use tokio::{io::AsyncReadExt, net::UnixStream};
-use zbus::zvariant::{Fd, SerializeDict, Type};
+use zbus::zvariant::{DeserializeDict, Fd, SerializeDict, Type};
 
 use super::{HandleToken, Request};
-use crate::{proxy::Proxy, Error};
+use crate::{
+    helpers::Redacted,
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
-#[derive(SerializeDict, Type, Debug, Default)]
-/// Specified options for a [`Secret::retrieve`] request.
+#[derive(SerializeDict, Type, Default)]
+/// Specified options for a [`Secret::retrieve_with_options`] request.
 #[zvariant(signature = "dict")]
-struct RetrieveOptions {
+pub struct RetrieveSecretOptions {
     handle_token: HandleToken,
-    /// A string returned by a previous call to `retrieve`.
-    /// TODO: seems to not be used by the portal...
     token: Option<String>,
 }
 
+impl RetrieveSecretOptions {
+    /// Sets the token returned by a previous retrieval, see
+    /// [`RetrieveSecretResponse::token`].
+    ///
+    /// The token identifies the secret to the portal backend, store it with
+    /// the same care as the secret itself, and never share it with other
+    /// applications.
+    #[must_use]
+    pub fn token<'a>(mut self, token: impl Into<Option<&'a str>>) -> Self {
+        self.token = token.into().map(ToOwned::to_owned);
+        self
+    }
+}
+
+impl Debug for RetrieveSecretOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetrieveSecretOptions")
+            .field("handle_token", &self.handle_token)
+            .field("token", &Redacted(&self.token))
+            .finish()
+    }
+}
+
+impl From<()> for RetrieveSecretOptions {
+    fn from(_: ()) -> Self {
+        Self::default()
+    }
+}
+
+#[derive(DeserializeDict, Type, Default)]
+/// A response to a [`Secret::retrieve_with_options`] request.
+///
+/// The token is redacted from the [`Debug`] output, use
+/// [`RetrieveSecretResponse::expose`] to access it.
+#[zvariant(signature = "dict")]
+pub struct RetrieveSecretResponse {
+    token: Option<String>,
+}
+
+impl RetrieveSecretResponse {
+    /// A token the backend might return, to be passed to the following
+    /// retrievals with [`RetrieveSecretOptions::token`].
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// The token, like [`Self::token`], for the places where reading the
+    /// secret value should stand out, e.g. in reviews.
+    pub fn expose(&self) -> Option<&str> {
+        self.token()
+    }
+}
+
+impl Debug for RetrieveSecretResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetrieveSecretResponse")
+            .field("token", &Redacted(&self.token))
+            .finish()
+    }
+}
+
 /// The interface lets sandboxed applications retrieve a per-application secret.
 ///
 /// The secret can then be used for encrypting confidential data inside the
@@ -56,14 +119,25 @@ impl<'a> Secret<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`Secret::new`], failing with [`Error::NotSupported`] if the
+    /// portal doesn't provide `org.freedesktop.portal.Secret`.
+    pub async fn new_checked() -> Result<Secret<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Secret").await?;
+        Ok(Self(proxy))
+    }
+
     /// Retrieves a master secret for a sandboxed application.
     ///
     /// # Arguments
     ///
     /// * `fd` - Writaeble file descriptor for transporting the secret.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`RetrieveSecret`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-Secret.RetrieveSecret).
     #[doc(alias = "RetrieveSecret")]
     pub async fn retrieve(&self, fd: &impl AsRawFd) -> Result<Request<()>, Error> {
-        let options = RetrieveOptions::default();
+        let options = RetrieveSecretOptions::default();
         self.0
             .empty_request(
                 &options.handle_token,
@@ -72,8 +146,43 @@ impl<'a> Secret<'a> {
             )
             .await
     }
+
+    /// Retrieves a master secret for a sandboxed application, passing a
+    /// previously returned token.
+    ///
+    /// # Arguments
+    ///
+    /// * `fd` - Writeable file descriptor for transporting the secret.
+    /// * `options` - The retrieval options, or `()` for the default ones.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`RetrieveSecret`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-Secret.RetrieveSecret).
+    #[doc(alias = "RetrieveSecret")]
+    pub async fn retrieve_with_options(
+        &self,
+        fd: &impl AsRawFd,
+        options: impl Into<RetrieveSecretOptions>,
+    ) -> Result<Request<RetrieveSecretResponse>, Error> {
+        let options = options.into();
+        self.0
+            .request(
+                &options.handle_token,
+                "RetrieveSecret",
+                &(Fd::from(fd.as_raw_fd()), &options),
+            )
+            .await
+    }
 }
 
+impl AsProxy for Secret<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for Secret<'_> {}
+
 /// A handy wrapper around [`Secret::retrieve`].
 ///
 /// It crates a UnixStream internally for receiving the secret.
@@ -1,12 +1,24 @@
-use std::{collections::HashMap, fmt::Debug};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    ops::Deref,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
+use futures_util::{future::BoxFuture, lock::Mutex, stream::BoxStream, StreamExt};
 use serde::{Serialize, Serializer};
-use zbus::zvariant::{ObjectPath, OwnedValue, Signature, Type};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Signature, Type};
 
-use crate::{desktop::HandleToken, proxy::Proxy, Error};
+use crate::{
+    desktop::HandleToken,
+    proxy::{Handles, Proxy},
+    Error,
+};
 
 pub type SessionDetails = HashMap<String, OwnedValue>;
 
+const SESSION_INTERFACE: &str = "org.freedesktop.portal.Session";
+
 /// Shared by all portal interfaces that involve long lived sessions.
 ///
 /// When a method that creates a session is called, if successful, the reply
@@ -20,35 +32,94 @@ pub type SessionDetails = HashMap<String, OwnedValue>;
 ///
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.Session`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.portal.Session).
 #[doc(alias = "org.freedesktop.portal.Session")]
-pub struct Session<'a>(Proxy<'a>);
+pub struct Session<'a>(Proxy<'a>, Mutex<ClosedSignal<'a>>, Handles, AtomicBool);
+
+/// The subscription to the `Closed` signal, made once the session is created
+/// so that the signal isn't missed.
+struct ClosedSignal<'a> {
+    stream: BoxStream<'a, SessionDetails>,
+    details: Option<SessionDetails>,
+}
 
 impl<'a> Session<'a> {
     /// Create a new instance of [`Session`].
     ///
     /// **Note** A [`Session`] is not supposed to be created manually.
-    pub(crate) async fn new(path: ObjectPath<'a>) -> Result<Session<'a>, Error> {
-        let proxy = Proxy::new_desktop_with_path("org.freedesktop.portal.Session", path).await?;
-        Ok(Self(proxy))
+    ///
+    /// The session object is at `path`, its creating method has to be called
+    /// on `connection`. The session is tracked in `handles` until it is
+    /// closed.
+    pub(crate) async fn new(
+        connection: &zbus::Connection,
+        path: ObjectPath<'a>,
+        handles: Handles,
+    ) -> Result<Session<'a>, Error> {
+        let proxy = Proxy::from_connection_with_path(connection, SESSION_INTERFACE, path).await?;
+        let closed = ClosedSignal {
+            stream: proxy.signals::<SessionDetails>("Closed").await?.boxed(),
+            details: None,
+        };
+        handles.insert(
+            SESSION_INTERFACE,
+            proxy.path().to_owned().into(),
+            connection,
+        );
+        Ok(Self(
+            proxy,
+            Mutex::new(closed),
+            handles,
+            AtomicBool::new(false),
+        ))
     }
 
+    /// Creates the session the portal names after `handle_token`, on the
+    /// connection of the proxy creating it and tracked by it.
     pub(crate) async fn from_unique_name(
+        proxy: &Proxy<'_>,
         handle_token: &HandleToken,
     ) -> Result<Session<'a>, crate::Error> {
-        let path =
-            Proxy::unique_name("/org/freedesktop/portal/desktop/session", handle_token).await?;
+        let connection = proxy.call_connection().await?;
+        let path = Proxy::unique_name_with(
+            &connection,
+            "/org/freedesktop/portal/desktop/session",
+            handle_token,
+        )?;
         #[cfg(feature = "tracing")]
         tracing::info!("Creating a org.freedesktop.portal.Session {}", path);
-        Self::new(path).await
+        Self::new(&connection, path, proxy.handles().clone()).await
     }
 
     /// Emitted when a session is closed.
     ///
+    /// Resolves once the backend tears down the session, for example after
+    /// some inactivity or because the user revoked it, letting long-running
+    /// applications create a new one. Resolves right away if the session was
+    /// already closed by the backend, as the signal is listened to from the
+    /// creation of the session.
+    ///
     /// # Specifications
     ///
     /// See also [`Closed`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-signal-org-freedesktop-portal-Session.Closed).
     #[doc(alias = "Closed")]
     pub async fn receive_closed(&self) -> Result<SessionDetails, Error> {
-        self.0.signal("Closed").await
+        let mut closed = self.1.lock().await;
+        if let Some(details) = &closed.details {
+            return Ok(details.clone());
+        }
+        let details = closed.stream.next().await.ok_or(Error::NoResponse)?;
+        #[cfg(feature = "tracing")]
+        tracing::info!("Session {} closed with {:#?}", self.path(), details);
+        closed.details = Some(details.clone());
+        self.2.remove(&self.path().to_owned().into());
+        Ok(details)
+    }
+
+    /// Whether the backend closed the session, as reported by
+    /// [`Session::receive_closed`].
+    pub fn is_closed(&self) -> bool {
+        self.1
+            .try_lock()
+            .is_some_and(|closed| closed.details.is_some())
     }
 
     /// Closes the portal session to which this object refers and ends all
@@ -59,12 +130,102 @@ impl<'a> Session<'a> {
     /// See also [`Close`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-Session.Close).
     #[doc(alias = "Close")]
     pub async fn close(&self) -> Result<(), Error> {
-        self.0.call("Close", &()).await
+        self.0.call::<()>("Close", &()).await?;
+        self.2.remove(&self.path().to_owned().into());
+        Ok(())
     }
 
     pub(crate) fn path(&self) -> &ObjectPath<'_> {
         self.0.path()
     }
+
+    /// Marks the sources of the session as selected, through
+    /// `SelectSources`.
+    pub(crate) fn set_sources_selected(&self) {
+        self.3.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the sources of the session were selected, whichever proxy
+    /// selected them.
+    pub(crate) fn sources_selected(&self) -> bool {
+        self.3.load(Ordering::Relaxed)
+    }
+}
+
+/// A long lived session created by a portal, like the ones of
+/// [`Screencast`](crate::desktop::screencast::Screencast),
+/// [`RemoteDesktop`](crate::desktop::remote_desktop::RemoteDesktop),
+/// [`LocationProxy`](crate::desktop::location::LocationProxy),
+/// [`GlobalShortcuts`](crate::desktop::global_shortcuts::GlobalShortcuts) or
+/// [`InhibitProxy`](crate::desktop::inhibit::InhibitProxy).
+pub trait PortalSession {
+    /// The object path of the session.
+    fn session_handle(&self) -> ObjectPath<'_>;
+
+    /// Closes the session.
+    fn close(&self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Closes the session without waiting for the portal to handle it, like
+    /// when a [`SessionGuard`] is dropped.
+    fn close_in_background(&self);
+
+    /// Wraps the session in a [`SessionGuard`] that closes it once dropped.
+    fn close_on_drop(self) -> SessionGuard<Self>
+    where
+        Self: Sized,
+    {
+        SessionGuard(Some(self))
+    }
+}
+
+impl<'a> PortalSession for Session<'a> {
+    fn session_handle(&self) -> ObjectPath<'_> {
+        self.path().as_ref()
+    }
+
+    fn close(&self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(Session::close(self))
+    }
+
+    fn close_in_background(&self) {
+        let path = OwnedObjectPath::from(self.path().to_owned());
+        self.2.remove(&path);
+        let connection = self.0.connection().clone();
+        Proxy::close_in_background(connection, SESSION_INTERFACE, path);
+    }
+}
+
+/// A session that gets closed once dropped, see
+/// [`PortalSession::close_on_drop`].
+///
+/// **Note** the closing happens in the background, as `Drop` can't be async.
+/// With the `tokio` feature, the guard has to be dropped within a Tokio
+/// runtime.
+#[derive(Debug)]
+pub struct SessionGuard<S: PortalSession>(Option<S>);
+
+impl<S: PortalSession> SessionGuard<S> {
+    /// Retrieve the session back, without closing it.
+    pub fn into_inner(mut self) -> S {
+        self.0.take().unwrap()
+    }
+}
+
+impl<S: PortalSession> Deref for SessionGuard<S> {
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        // The session is only taken out when consuming the guard
+        self.0.as_ref().unwrap()
+    }
+}
+
+impl<S: PortalSession> Drop for SessionGuard<S> {
+    fn drop(&mut self) {
+        if let Some(session) = self.0.take() {
+            session.close_in_background();
+        }
+    }
 }
 
 impl<'a> Serialize for Session<'a> {
@@ -89,3 +250,53 @@ impl<'a> Debug for Session<'a> {
             .finish()
     }
 }
+
+#[cfg(all(test, feature = "async-std"))]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use zbus::{dbus_interface, zvariant::Value, SignalContext};
+
+    use super::*;
+
+    struct MockSession;
+
+    #[dbus_interface(name = "org.freedesktop.portal.Session")]
+    impl MockSession {
+        #[dbus_interface(signal)]
+        async fn closed(
+            ctxt: &SignalContext<'_>,
+            details: HashMap<&str, Value<'_>>,
+        ) -> zbus::Result<()>;
+    }
+
+    #[test]
+    fn closed_by_backend() {
+        async_std::task::block_on(async {
+            let path =
+                ObjectPath::try_from("/org/freedesktop/portal/desktop/session/1_0/test").unwrap();
+            let Some(server) = crate::proxy::mock_desktop().await else {
+                return;
+            };
+            server.object_server().at(&path, MockSession).await.unwrap();
+
+            let connection = Proxy::connection().await.unwrap();
+            let session = Session::new(&connection, path.clone(), Handles::default())
+                .await
+                .unwrap();
+            assert!(!session.is_closed());
+
+            let ctxt = SignalContext::new(&server, &path).unwrap();
+            MockSession::closed(&ctxt, HashMap::new()).await.unwrap();
+            // The signal is received even if it was emitted before listening to it
+            async_std::task::sleep(Duration::from_millis(100)).await;
+
+            async_std::future::timeout(Duration::from_secs(5), session.receive_closed())
+                .await
+                .expect("The closed signal wasn't received")
+                .unwrap();
+            assert!(session.is_closed());
+            session.receive_closed().await.unwrap();
+        });
+    }
+}
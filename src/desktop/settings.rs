@@ -21,12 +21,22 @@
 //! }
 //! ```
 
-use std::{collections::HashMap, convert::TryFrom, fmt::Debug};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
 
+use futures_util::StreamExt;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use zbus::zvariant::{OwnedValue, Type, Value};
 
-use crate::{proxy::Proxy, Error};
+use crate::{
+    desktop::Color,
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
 /// A HashMap of the <key, value> settings found on a specific namespace.
 pub type Namespace = HashMap<String, OwnedValue>;
@@ -63,9 +73,10 @@ impl std::fmt::Debug for Setting {
 }
 
 /// The system's preferred color scheme
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ColorScheme {
     /// No preference
+    #[default]
     NoPreference,
     /// Prefers dark appearance
     PreferDark,
@@ -73,6 +84,91 @@ pub enum ColorScheme {
     PreferLight,
 }
 
+impl From<u32> for ColorScheme {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::PreferDark,
+            2 => Self::PreferLight,
+            _ => Self::NoPreference,
+        }
+    }
+}
+
+impl TryFrom<OwnedValue> for ColorScheme {
+    type Error = zbus::zvariant::Error;
+
+    fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+        u32::try_from(value).map(Self::from)
+    }
+}
+
+/// A set of settings read at once, see [`Settings::read_settings`] and
+/// [`Settings::watch`].
+///
+/// With the `macros` feature, it can be derived with
+/// `#[derive(PortalSettings)]`, the fields being mapped to the keys through
+/// the `#[portal_settings(namespace = "...", key = "...")]` attribute.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ashpd::{
+///     desktop::settings::{ColorScheme, PortalSettings, Settings},
+///     zvariant::OwnedValue,
+/// };
+///
+/// #[derive(Debug, Default, Clone)]
+/// struct Theme {
+///     color_scheme: ColorScheme,
+/// }
+///
+/// impl PortalSettings for Theme {
+///     const NAMESPACES: &'static [&'static str] = &["org.freedesktop.appearance"];
+///
+///     fn update(&mut self, namespace: &str, key: &str, value: OwnedValue) -> bool {
+///         match (namespace, key) {
+///             ("org.freedesktop.appearance", "color-scheme") => {
+///                 self.color_scheme = ColorScheme::try_from(value).unwrap_or_default();
+///                 true
+///             }
+///             _ => false,
+///         }
+///     }
+/// }
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let proxy = Settings::new().await?;
+///     let theme = proxy.watch::<Theme>().await?;
+///     println!("{:#?}", theme.get());
+///     Ok(())
+/// }
+/// ```
+pub trait PortalSettings {
+    /// The namespaces of the settings.
+    const NAMESPACES: &'static [&'static str];
+
+    /// Updates the field matching `namespace` and `key`.
+    ///
+    /// Returns whether a field was updated.
+    fn update(&mut self, namespace: &str, key: &str, value: OwnedValue) -> bool;
+}
+
+#[cfg(feature = "macros")]
+pub use ashpd_macros::PortalSettings;
+
+/// Converts a setting value, that might be wrapped in a variant.
+#[doc(hidden)]
+pub fn __setting_value<T>(value: OwnedValue) -> Option<T>
+where
+    T: TryFrom<OwnedValue>,
+{
+    if let Some(v) = value.downcast_ref::<Value<'_>>() {
+        T::try_from(v.to_owned()).ok()
+    } else {
+        T::try_from(value).ok()
+    }
+}
+
 /// The interface provides read-only access to a small number of host settings
 /// required for toolkits similar to XSettings. It is not for general purpose
 /// settings.
@@ -89,6 +185,13 @@ impl<'a> Settings<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`Settings::new`], failing with [`Error::NotSupported`] if the
+    /// portal doesn't provide `org.freedesktop.portal.Settings`.
+    pub async fn new_checked() -> Result<Settings<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Settings").await?;
+        Ok(Self(proxy))
+    }
+
     /// Reads a single value. Returns an error on any unknown namespace or key.
     ///
     /// # Arguments
@@ -145,15 +248,17 @@ impl<'a> Settings<'a> {
     /// Reads the value of namespace: `org.freedesktop.appearance` and
     /// `color-scheme` key.
     pub async fn color_scheme(&self) -> Result<ColorScheme, Error> {
-        let scheme = match self
-            .read::<u32>("org.freedesktop.appearance", "color-scheme")
-            .await?
-        {
-            1 => ColorScheme::PreferDark,
-            2 => ColorScheme::PreferLight,
-            _ => ColorScheme::NoPreference,
-        };
-        Ok(scheme)
+        self.read::<u32>("org.freedesktop.appearance", "color-scheme")
+            .await
+            .map(ColorScheme::from)
+    }
+
+    /// Reads the value of namespace: `org.freedesktop.appearance` and
+    /// `accent-color` key.
+    pub async fn accent_color(&self) -> Result<Color, Error> {
+        self.read::<(f64, f64, f64)>("org.freedesktop.appearance", "accent-color")
+            .await
+            .map(|(red, green, blue)| Color::new(red, green, blue))
     }
 
     /// Listen to changes of the namespace `org.freedesktop.appearance` for
@@ -164,15 +269,104 @@ impl<'a> Settings<'a> {
             if setting.namespace() == "org.freedesktop.appearance"
                 && setting.key() == "color-scheme"
             {
-                return Ok(match u32::try_from(setting.value()) {
-                    Ok(1) => ColorScheme::PreferDark,
-                    Ok(2) => ColorScheme::PreferLight,
-                    _ => ColorScheme::NoPreference,
-                });
+                return Ok(u32::try_from(setting.value())
+                    .map(ColorScheme::from)
+                    .unwrap_or_default());
             }
         }
     }
 
+    /// Reads all the settings of `namespace` and keeps them up to date by
+    /// listening to [`Settings::receive_setting_changed`].
+    ///
+    /// The returned [`SettingsSnapshot`] holds a background task updating it
+    /// until it is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace to watch, globing is not supported.
+    pub async fn watch_namespace(&self, namespace: &str) -> Result<SettingsSnapshot, Error> {
+        // Start listening before reading the values so no change is missed
+        let mut stream = self.0.receive_signal("SettingChanged").await?;
+        let values = self
+            .read_all(&[namespace])
+            .await?
+            .remove(namespace)
+            .unwrap_or_default();
+        let values = Arc::new(Mutex::new(values));
+
+        let namespace = namespace.to_owned();
+        let task_values = Arc::clone(&values);
+        let task = self.0.connection().executor().spawn(
+            async move {
+                while let Some(message) = stream.next().await {
+                    let Ok(setting) = message.body::<Setting>() else {
+                        continue;
+                    };
+                    if setting.0 == namespace {
+                        task_values.lock().unwrap().insert(setting.1, setting.2);
+                    }
+                }
+            },
+            "ashpd settings snapshot",
+        );
+        Ok(SettingsSnapshot {
+            values,
+            _task: task,
+        })
+    }
+
+    /// Reads all the settings of `T` at once.
+    ///
+    /// The fields of the settings that aren't found keep their default
+    /// value.
+    pub async fn read_settings<T>(&self) -> Result<T, Error>
+    where
+        T: PortalSettings + Default,
+    {
+        let mut settings = T::default();
+        for (namespace, values) in self.read_all(T::NAMESPACES).await? {
+            for (key, value) in values {
+                settings.update(&namespace, &key, value);
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Reads all the settings of `T` and keeps them up to date by listening
+    /// to [`Settings::receive_setting_changed`].
+    ///
+    /// The returned [`WatchedSettings`] holds a background task updating it
+    /// until it is dropped.
+    pub async fn watch<T>(&self) -> Result<WatchedSettings<T>, Error>
+    where
+        T: PortalSettings + Default + Send + 'static,
+    {
+        // Start listening before reading the values so no change is missed
+        let mut stream = self.0.receive_signal("SettingChanged").await?;
+        let settings = Arc::new(Mutex::new(self.read_settings::<T>().await?));
+
+        let task_settings = Arc::clone(&settings);
+        let task = self.0.connection().executor().spawn(
+            async move {
+                while let Some(message) = stream.next().await {
+                    let Ok(setting) = message.body::<Setting>() else {
+                        continue;
+                    };
+                    task_settings
+                        .lock()
+                        .unwrap()
+                        .update(&setting.0, &setting.1, setting.2);
+                }
+            },
+            "ashpd watched settings",
+        );
+        Ok(WatchedSettings {
+            settings,
+            _task: task,
+        })
+    }
+
     /// Signal emitted when a setting changes.
     ///
     /// # Specifications
@@ -183,3 +377,127 @@ impl<'a> Settings<'a> {
         self.0.signal("SettingChanged").await
     }
 }
+
+impl AsProxy for Settings<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for Settings<'_> {}
+
+/// The settings of a namespace, kept up to date in the background. Created
+/// with [`Settings::watch_namespace`].
+///
+/// Dropping the snapshot stops listening to the changes.
+pub struct SettingsSnapshot {
+    values: Arc<Mutex<Namespace>>,
+    _task: zbus::Task<()>,
+}
+
+impl SettingsSnapshot {
+    /// The current value of `key`, if it is set and is of type `T`.
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: TryFrom<OwnedValue>,
+    {
+        let value = self.values.lock().unwrap().get(key)?.clone();
+        __setting_value(value)
+    }
+
+    /// A copy of all the current settings of the namespace.
+    pub fn values(&self) -> Namespace {
+        self.values.lock().unwrap().clone()
+    }
+}
+
+impl Debug for SettingsSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SettingsSnapshot")
+            .field("values", &self.values)
+            .finish()
+    }
+}
+
+/// A set of settings kept up to date in the background. Created with
+/// [`Settings::watch`].
+///
+/// Dropping it stops listening to the changes.
+pub struct WatchedSettings<T> {
+    settings: Arc<Mutex<T>>,
+    _task: zbus::Task<()>,
+}
+
+impl<T: Clone> WatchedSettings<T> {
+    /// A copy of the current settings.
+    pub fn get(&self) -> T {
+        self.settings.lock().unwrap().clone()
+    }
+}
+
+impl<T: Debug> Debug for WatchedSettings<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchedSettings")
+            .field("settings", &self.settings)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_scheme() {
+        let value = OwnedValue::from(1u32);
+        assert_eq!(
+            ColorScheme::try_from(value).unwrap(),
+            ColorScheme::PreferDark
+        );
+        assert_eq!(ColorScheme::from(3), ColorScheme::NoPreference);
+        assert!(ColorScheme::try_from(OwnedValue::from(Value::from("dark"))).is_err());
+    }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn derive_portal_settings() {
+        #[derive(Debug, Default, PortalSettings)]
+        #[portal_settings(namespace = "org.freedesktop.appearance")]
+        struct Theme {
+            color_scheme: ColorScheme,
+            accent_color: Option<Color>,
+            #[portal_settings(namespace = "org.gnome.desktop.interface", key = "font-name")]
+            font: String,
+            #[portal_settings(skip)]
+            loaded: bool,
+        }
+
+        assert_eq!(
+            Theme::NAMESPACES,
+            &["org.freedesktop.appearance", "org.gnome.desktop.interface"]
+        );
+
+        let mut theme = Theme::default();
+        assert!(theme.accent_color.is_none());
+        // The values are sometimes wrapped in a variant
+        let value = OwnedValue::from(Value::Value(Box::new(Value::from(1u32))));
+        assert!(theme.update("org.freedesktop.appearance", "color-scheme", value));
+        assert_eq!(theme.color_scheme, ColorScheme::PreferDark);
+
+        let value = Value::from((1.0, 0.5, 0.0)).into();
+        assert!(theme.update("org.freedesktop.appearance", "accent-color", value));
+        assert_eq!(theme.accent_color, Some(Color::new(1.0, 0.5, 0.0)));
+
+        let value = OwnedValue::from(Value::from("Cantarell 11"));
+        assert!(theme.update("org.gnome.desktop.interface", "font-name", value));
+        assert_eq!(theme.font, "Cantarell 11");
+
+        // Wrong types and unknown keys are ignored
+        let value = OwnedValue::from(Value::from("dark"));
+        assert!(!theme.update("org.freedesktop.appearance", "color-scheme", value));
+        assert_eq!(theme.color_scheme, ColorScheme::PreferDark);
+        let value = OwnedValue::from(true);
+        assert!(!theme.update("org.freedesktop.appearance", "loaded", value));
+        assert!(!theme.loaded);
+    }
+}
@@ -15,6 +15,17 @@
 //! }
 //! ```
 //!
+//! Or by passing a path
+//!
+//! ```rust,no_run
+//! use ashpd::desktop::trash;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     trash::trash_path("/home/bilelmoussaoui/adwaita-night.jpg").await?;
+//!     Ok(())
+//! }
+//! ```
+//!
 //! Or by using the Proxy directly
 //!
 //! ```rust,no_run
@@ -30,12 +41,16 @@
 //! }
 //! ```
 
-use std::os::unix::io::AsRawFd;
+use std::{fs::File, os::unix::io::AsRawFd, path::Path};
 
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{Fd, Type};
 
-use crate::{error::PortalError, proxy::Proxy, Error};
+use crate::{
+    error::PortalError,
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
 #[derive(Debug, Deserialize_repr, Serialize_repr, PartialEq, Type)]
 #[repr(u32)]
@@ -58,10 +73,21 @@ impl<'a> TrashProxy<'a> {
         Ok(Self(proxy))
     }
 
+    /// Same as [`TrashProxy::new`], failing with [`Error::NotSupported`] if the
+    /// portal doesn't provide `org.freedesktop.portal.Trash`.
+    pub async fn new_checked() -> Result<TrashProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Trash").await?;
+        Ok(Self(proxy))
+    }
+
     /// Sends a file to the trashcan.
     /// Applications are allowed to trash a file if they can open it in
     /// read/write mode.
     ///
+    /// The method takes no options, the portal doesn't ask the user for a
+    /// confirmation. Applications wanting one have to show it before calling
+    /// this method.
+    ///
     /// # Arguments
     ///
     /// * `fd` - The file descriptor.
@@ -81,8 +107,34 @@ impl<'a> TrashProxy<'a> {
             TrashStatus::Succeeded => Ok(()),
         }
     }
+
+    /// Sends a file to the trashcan, opening it from its path.
+    ///
+    /// Returns [`Error::IO`] if the file doesn't exist or can't be opened.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`TrashFile`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-Trash.TrashFile).
+    #[doc(alias = "TrashFile")]
+    #[doc(alias = "xdp_portal_trash_file")]
+    pub async fn trash_path(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let file = File::open(path)?;
+        self.trash_file(&file).await
+    }
 }
 
+impl AsProxy for TrashProxy<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for TrashProxy<'_> {}
+
 #[doc(alias = "xdp_portal_trash_file")]
 /// A handy wrapper around [`TrashProxy::trash_file`].
 pub async fn trash_file(fd: &impl AsRawFd) -> Result<(), Error> {
@@ -90,6 +142,13 @@ pub async fn trash_file(fd: &impl AsRawFd) -> Result<(), Error> {
     proxy.trash_file(fd).await
 }
 
+#[doc(alias = "xdp_portal_trash_file")]
+/// A handy wrapper around [`TrashProxy::trash_path`].
+pub async fn trash_path(path: impl AsRef<Path>) -> Result<(), Error> {
+    let proxy = TrashProxy::new().await?;
+    proxy.trash_path(path).await
+}
+
 #[cfg(test)]
 mod test {
     use super::TrashStatus;
@@ -17,7 +17,8 @@
 //!         .set_on(SetOn::Both)
 //!         .show_preview(true)
 //!         .build_file(&file)
-//!         .await?;
+//!         .await?
+//!         .response()?;
 //!     Ok(())
 //! }
 //! ```
@@ -34,17 +35,24 @@
 //!         .set_on(SetOn::Both)
 //!         .show_preview(true)
 //!         .build_uri(&uri)
-//!         .await?;
+//!         .await?
+//!         .response()?;
 //!     Ok(())
 //! }
 //! ```
 
-use std::{fmt, os::unix::prelude::AsRawFd, str::FromStr};
+use std::{
+    fmt,
+    io::Write,
+    os::unix::prelude::AsRawFd,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use serde::{self, Deserialize, Serialize};
 use zbus::zvariant::{Fd, SerializeDict, Type};
 
-use super::Request;
+use super::{Color, Request};
 use crate::{desktop::HandleToken, proxy::Proxy, Error, WindowIdentifier};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Type)]
@@ -121,6 +129,11 @@ impl<'a> WallpaperProxy<'a> {
         Ok(Self(proxy))
     }
 
+    pub async fn new_checked() -> Result<WallpaperProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop_checked("org.freedesktop.portal.Wallpaper").await?;
+        Ok(Self(proxy))
+    }
+
     pub async fn set_wallpaper_file(
         &self,
         identifier: &WindowIdentifier,
@@ -161,6 +174,7 @@ impl<'a> WallpaperProxy<'a> {
 pub struct WallpaperRequest {
     identifier: WindowIdentifier,
     options: WallpaperOptions,
+    export_local_file: bool,
 }
 
 impl WallpaperRequest {
@@ -187,8 +201,28 @@ impl WallpaperRequest {
         self
     }
 
+    /// Whether [`Self::build_uri`] should open local `file://` URIs and pass
+    /// them as a file descriptor, like [`Self::build_file`], instead of
+    /// passing the URI.
+    ///
+    /// Files inside the sandbox can't be read by the desktop, enable it when
+    /// the URI might point to such a file. Defaults to `false`.
+    #[must_use]
+    pub fn export_local_file(mut self, export_local_file: bool) -> Self {
+        self.export_local_file = export_local_file;
+        self
+    }
+
     /// Build using a URI.
+    ///
+    /// See [`Self::build_file`] for the possible outcomes.
     pub async fn build_uri(self, uri: &url::Url) -> Result<Request<()>, Error> {
+        if self.export_local_file {
+            if let Ok(path) = uri.to_file_path() {
+                let file = std::fs::File::open(path)?;
+                return self.build_file(&file).await;
+            }
+        }
         let proxy = WallpaperProxy::new().await?;
         proxy
             .set_wallpaper_uri(&self.identifier, uri, self.options)
@@ -196,16 +230,133 @@ impl WallpaperRequest {
     }
 
     /// Build using a file.
+    ///
+    /// The response of the returned [`Request`] tells whether the wallpaper
+    /// was set, it is a [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled)
+    /// error if the user dismissed the preview.
+    ///
+    /// Returns [`Error::NotSupported`] if setting a wallpaper is not supported
+    /// by the running desktop.
     pub async fn build_file(self, file: &impl AsRawFd) -> Result<Request<()>, Error> {
         let proxy = WallpaperProxy::new().await?;
         proxy
             .set_wallpaper_file(&self.identifier, file, self.options)
             .await
     }
+
+    /// Build using a solid color.
+    ///
+    /// The portal only takes pictures, a small PNG image filled with the
+    /// color is written to `$XDG_CACHE_HOME/ashpd`, which the desktop can
+    /// read inside a Flatpak too. The backend might keep using the file once
+    /// the request is over, it is left there and reused for the same color,
+    /// removing it is up to the application.
+    ///
+    /// See [`Self::build_file`] for the possible outcomes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if the running portal doesn't provide
+    /// the interface, or an [`Error::IO`] if the image couldn't be written.
+    pub async fn build_color(self, color: Color) -> Result<Request<()>, Error> {
+        let proxy = WallpaperProxy::new_checked().await?;
+        let dir = cache_dir().join("ashpd");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("wallpaper-{}.png", &color.to_hex()[1..]));
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        // Left as is if it was already written for the same color, a backend
+        // might be reading it
+        if file.metadata()?.len() == 0 {
+            file.write_all(&solid_color_image(color))?;
+        }
+        proxy
+            .set_wallpaper_file(&self.identifier, &file, self.options)
+            .await
+    }
+}
+
+/// The user cache directory, readable by the desktop.
+fn cache_dir() -> PathBuf {
+    if let Some(cache_home) = std::env::var_os("XDG_CACHE_HOME") {
+        return PathBuf::from(cache_home);
+    }
+    match std::env::var_os("HOME") {
+        Some(home) => Path::new(&home).join(".cache"),
+        None => std::env::temp_dir(),
+    }
+}
+
+/// A 1x1 PNG image of the color.
+fn solid_color_image(color: Color) -> Vec<u8> {
+    fn chunk(image: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        image.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = image.len();
+        image.extend_from_slice(kind);
+        image.extend_from_slice(data);
+        let crc = crc32(&image[start..]);
+        image.extend_from_slice(&crc.to_be_bytes());
+    }
+
+    // 8 bits RGB, no interlacing
+    let header = [0, 0, 0, 1, 0, 0, 0, 1, 8, 2, 0, 0, 0];
+    // The only row, without filter
+    let [red, green, blue] = color.to_rgb8();
+    let row = [0, red, green, blue];
+    // A zlib stream with a single uncompressed deflate block
+    let mut data = vec![0x78, 0x01, 0x01, 0x04, 0x00, 0xfb, 0xff];
+    data.extend_from_slice(&row);
+    data.extend_from_slice(&adler32(&row).to_be_bytes());
+
+    let mut image = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut image, b"IHDR", &header);
+    chunk(&mut image, b"IDAT", &data);
+    chunk(&mut image, b"IEND", &[]);
+    image
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), byte| {
+        let a = (a + u32::from(*byte)) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
 }
+
 #[cfg(test)]
 mod tests {
-    use super::SetOn;
+    use super::{solid_color_image, Color, SetOn};
+
+    #[test]
+    fn solid_color() {
+        let image = solid_color_image(Color::new(1.0, 0.5, 0.0));
+        let chunks: [&[u8]; 4] = [
+            b"\x89PNG\r\n\x1a\n",
+            b"\0\0\0\x0dIHDR\0\0\0\x01\0\0\0\x01\x08\x02\0\0\0\x90\x77\x53\xde",
+            b"\0\0\0\x0fIDAT\x78\x01\x01\x04\0\xfb\xff\0\xff\x80\0\x04\x01\x01\x80\xe3\xc2\x4a\xc3",
+            b"\0\0\0\0IEND\xae\x42\x60\x82",
+        ];
+        assert_eq!(image, chunks.concat());
+    }
 
     #[test]
     fn serialize_deserialize() {
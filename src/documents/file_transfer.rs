@@ -24,9 +24,13 @@
 
 use std::{collections::HashMap, os::unix::prelude::AsRawFd};
 
+use futures_util::Stream;
 use zbus::zvariant::{Fd, SerializeDict, Type, Value};
 
-use crate::{proxy::Proxy, Error};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy,
+};
 
 #[derive(SerializeDict, Debug, Type, Default)]
 /// Specified options for a [`FileTransfer::start_transfer`] request.
@@ -190,4 +194,24 @@ impl<'a> FileTransfer<'a> {
     pub async fn transfer_closed(&self) -> Result<String, Error> {
         self.0.signal("TransferClosed").await
     }
+
+    /// A stream of the keys of the transfers as they get closed.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`TransferClosed`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-signal-org-freedesktop-portal-FileTransfer.TransferClosed).
+    #[doc(alias = "TransferClosed")]
+    pub async fn receive_transfer_closed(
+        &self,
+    ) -> Result<impl Stream<Item = String> + Send + Unpin + 'a, Error> {
+        self.0.signals("TransferClosed").await
+    }
 }
+
+impl AsProxy for FileTransfer<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for FileTransfer<'_> {}
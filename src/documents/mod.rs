@@ -31,21 +31,43 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! # Application ID
+//!
+//! The portals identify the calling application from its sandbox, the
+//! application ID can't be overridden by the caller. The document portal is
+//! the only one that lets an application act on behalf of another one, which
+//! is why [`add_full`](crate::documents::Documents::add_full),
+//! [`add_named_full`](crate::documents::Documents::add_named_full),
+//! [`grant_permissions`](crate::documents::Documents::grant_permissions),
+//! [`revoke_permissions`](crate::documents::Documents::revoke_permissions) and
+//! [`list`](crate::documents::Documents::list) take an explicit
+//! [`AppID`](crate::AppID).
 
-use std::{collections::HashMap, fmt, os::unix::prelude::AsRawFd, path::Path, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt,
+    os::unix::prelude::AsRawFd,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use enumflags2::{bitflags, BitFlags};
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{Fd, OwnedValue, Type};
 
 pub use crate::app_id::DocumentID;
-use crate::{proxy::Proxy, AppID, Error, FilePath};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    AppID, Error, FilePath, PortalProxy,
+};
 
 #[bitflags]
 #[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Copy, Clone, Debug, Type)]
 #[repr(u32)]
-///
+/// Flags used when adding a file to the document store.
 pub enum DocumentFlags {
     /// Reuse the existing document store entry for the file.
     ReuseExisting,
@@ -144,13 +166,13 @@ impl FromStr for Permission {
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.Documents`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.portal.Documents).
 #[derive(Debug)]
 #[doc(alias = "org.freedesktop.portal.Documents")]
-pub struct Documents<'a>(Proxy<'a>);
+pub struct Documents<'a>(Proxy<'a>, OnceCell<FilePath>);
 
 impl<'a> Documents<'a> {
     /// Create a new instance of [`Documents`].
     pub async fn new() -> Result<Documents<'a>, Error> {
         let proxy = Proxy::new_documents("org.freedesktop.portal.Documents").await?;
-        Ok(Self(proxy))
+        Ok(Self(proxy, OnceCell::new()))
     }
 
     /// Adds a file to the document store.
@@ -323,13 +345,20 @@ impl<'a> Documents<'a> {
     /// Returns the path at which the document store fuse filesystem is mounted.
     /// This will typically be `/run/user/$UID/doc/`.
     ///
+    /// The mount point is retrieved once and cached by the proxy for the
+    /// following calls.
+    ///
     /// # Specifications
     ///
     /// See also [`GetMountPoint`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-portal-Documents.GetMountPoint).
     #[doc(alias = "GetMountPoint")]
     #[doc(alias = "get_mount_point")]
     pub async fn mount_point(&self) -> Result<FilePath, Error> {
-        self.0.call("GetMountPoint", &()).await
+        if let Some(mount_point) = self.1.get() {
+            return Ok(mount_point.clone());
+        }
+        let mount_point = self.0.call::<FilePath>("GetMountPoint", &()).await?;
+        Ok(self.1.get_or_init(|| mount_point).clone())
     }
 
     /// Grants access permissions for a file in the document store to an
@@ -387,6 +416,46 @@ impl<'a> Documents<'a> {
         self.0.call("Info", &(doc_id.into())).await
     }
 
+    /// Gets the permissions each application has on a document store entry.
+    ///
+    /// The same as the permissions returned by [`Self::info`], which allows
+    /// deciding whether more have to be granted with
+    /// [`Self::grant_permissions`].
+    ///
+    /// **Note** This call is not available inside the sandbox.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc_id` - The ID of the file in the document store.
+    pub async fn list_permissions(
+        &self,
+        doc_id: impl Into<DocumentID>,
+    ) -> Result<Permissions, Error> {
+        let (_, permissions) = self.info(doc_id).await?;
+        Ok(permissions)
+    }
+
+    /// Whether an application has a permission on a document store entry.
+    ///
+    /// **Note** This call is not available inside the sandbox.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc_id` - The ID of the file in the document store.
+    /// * `app_id` - The ID of the application.
+    /// * `permission` - The permission to look for.
+    pub async fn has_permission(
+        &self,
+        doc_id: impl Into<DocumentID>,
+        app_id: &AppID,
+        permission: Permission,
+    ) -> Result<bool, Error> {
+        let permissions = self.list_permissions(doc_id).await?;
+        Ok(permissions
+            .get(app_id)
+            .is_some_and(|permissions| permissions.contains(&permission)))
+    }
+
     /// Lists documents in the document store for an application (or for all
     /// applications).
     ///
@@ -479,14 +548,80 @@ impl<'a> Documents<'a> {
     }
 }
 
+impl AsProxy for Documents<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for Documents<'_> {}
+
+/// Resolves a URI to a path of the host filesystem, for display purposes.
+///
+/// Paths inside the document portal fuse filesystem, like
+/// `/run/user/1000/doc/<id>/name`, are resolved to the path of the original
+/// file with [`Documents::info`]. As that call is not available inside the
+/// sandbox, the path inside the document portal is returned when it fails.
+/// Other `file://` URIs are converted to their path as is.
+///
+/// # Returns
+///
+/// [`None`] if the URI doesn't point to a local file.
+pub async fn host_path(uri: &url::Url) -> Option<PathBuf> {
+    let path = uri.to_file_path().ok()?;
+    let Ok(proxy) = Documents::new().await else {
+        return Some(path);
+    };
+    let Ok(mount_point) = proxy.mount_point().await else {
+        return Some(path);
+    };
+    let Some((doc_id, relative)) = split_document_path(&path, mount_point.as_ref()) else {
+        return Some(path);
+    };
+    match proxy.info(doc_id).await {
+        Ok((host_path, _)) => {
+            let host_path = host_path.as_ref();
+            // The document is exposed as `<id>/<file name>`, with the content of
+            // exported directories below it
+            Some(match host_path.parent() {
+                Some(parent) if !relative.as_os_str().is_empty() => parent.join(relative),
+                _ => host_path.to_path_buf(),
+            })
+        }
+        Err(_) => Some(path),
+    }
+}
+
+/// Splits a path inside the document portal mount point into the document ID
+/// and the path relative to the document directory, also handling the
+/// `by-app/<app id>/` directories.
+fn split_document_path(path: &Path, mount_point: &Path) -> Option<(DocumentID, PathBuf)> {
+    let mut components = path.strip_prefix(mount_point).ok()?.iter();
+    let mut doc_id = components.next()?;
+    if doc_id == "by-app" {
+        components.next()?;
+        doc_id = components.next()?;
+    }
+    let doc_id = DocumentID::from(doc_id.to_str()?);
+    Some((doc_id, components.collect()))
+}
+
 /// Interact with `org.freedesktop.portal.FileTransfer` interface.
 mod file_transfer;
 
 pub use file_transfer::FileTransfer;
 
+/// Interact with `org.freedesktop.impl.portal.PermissionStore` interface.
+mod permission_store;
+
+pub use permission_store::{AppPermissions, PermissionStore, ResourceChanged};
+
 #[cfg(test)]
 mod tests {
-    use crate::documents::Permission;
+    use std::path::{Path, PathBuf};
+
+    use super::split_document_path;
+    use crate::documents::{DocumentID, Permission};
 
     #[test]
     fn serialize_deserialize() {
@@ -497,4 +632,28 @@ mod tests {
         let decoded = serde_json::from_str(&string).unwrap();
         assert_eq!(permission, decoded);
     }
+
+    #[test]
+    fn document_path() {
+        let mount_point = Path::new("/run/user/1000/doc");
+        assert_eq!(
+            split_document_path(
+                Path::new("/run/user/1000/doc/f2ee988d/file.txt"),
+                mount_point
+            ),
+            Some((DocumentID::from("f2ee988d"), PathBuf::from("file.txt")))
+        );
+        assert_eq!(
+            split_document_path(
+                Path::new("/run/user/1000/doc/by-app/org.gnome.Maps/f2ee988d/dir/file.txt"),
+                mount_point
+            ),
+            Some((DocumentID::from("f2ee988d"), PathBuf::from("dir/file.txt")))
+        );
+        assert_eq!(
+            split_document_path(Path::new("/home/user/file.txt"), mount_point),
+            None
+        );
+        assert_eq!(split_document_path(mount_point, mount_point), None);
+    }
 }
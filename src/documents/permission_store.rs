@@ -0,0 +1,242 @@
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ashpd::documents::PermissionStore;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let proxy = PermissionStore::new().await?;
+//!
+//!     let permissions = proxy
+//!         .permission("background", "background", "org.gnome.Maps")
+//!         .await?;
+//!     println!("{:#?}", permissions);
+//!
+//!     proxy
+//!         .set_permission("background", true, "background", "org.gnome.Maps", &["yes"])
+//!         .await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use futures_util::Stream;
+use serde::Deserialize;
+use zbus::zvariant::{OwnedValue, Type};
+
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    AppID, Error, PortalProxy,
+};
+
+/// The permissions of the applications for a resource, mapping application IDs
+/// to a table specific list of permissions.
+pub type AppPermissions = HashMap<String, Vec<String>>;
+
+#[derive(Debug, Deserialize, Type)]
+/// A resource that changed, see [`PermissionStore::receive_changed`].
+pub struct ResourceChanged {
+    table: String,
+    id: String,
+    deleted: bool,
+    data: OwnedValue,
+    permissions: AppPermissions,
+}
+
+impl ResourceChanged {
+    /// The name of the table.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// The resource ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Whether the resource was removed.
+    pub fn is_deleted(&self) -> bool {
+        self.deleted
+    }
+
+    /// The table specific data of the resource.
+    pub fn data(&self) -> &OwnedValue {
+        &self.data
+    }
+
+    /// The permissions of the applications for the resource.
+    pub fn permissions(&self) -> &AppPermissions {
+        &self.permissions
+    }
+}
+
+/// The interface stores the permissions granted by the portals, organized as
+/// tables of resources, each resource holding the permissions of the
+/// applications along with some table specific data.
+///
+/// The permission store can be used by privileged tools to inspect or manage
+/// the permissions granted to the applications. It is not meant to be exposed
+/// to sandboxed applications.
+///
+/// Wrapper of the DBus interface: [`org.freedesktop.impl.portal.PermissionStore`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-org.freedesktop.impl.portal.PermissionStore).
+#[derive(Debug)]
+#[doc(alias = "org.freedesktop.impl.portal.PermissionStore")]
+pub struct PermissionStore<'a>(Proxy<'a>);
+
+impl<'a> PermissionStore<'a> {
+    /// Create a new instance of [`PermissionStore`].
+    pub async fn new() -> Result<PermissionStore<'a>, Error> {
+        let proxy =
+            Proxy::new_permission_store("org.freedesktop.impl.portal.PermissionStore").await?;
+        Ok(Self(proxy))
+    }
+
+    /// Lookup the permissions and the data of a resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table.
+    /// * `id` - The resource ID.
+    ///
+    /// # Returns
+    ///
+    /// The [`AppPermissions`] of the resource along with its data.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Lookup`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-impl-portal-PermissionStore.Lookup).
+    #[doc(alias = "Lookup")]
+    pub async fn lookup(
+        &self,
+        table: &str,
+        id: &str,
+    ) -> Result<(AppPermissions, OwnedValue), Error> {
+        self.0.call("Lookup", &(table, id)).await
+    }
+
+    /// Sets the permissions of an application for a resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table.
+    /// * `create` - Whether to create the table if it does not exist.
+    /// * `id` - The resource ID.
+    /// * `app_id` - The ID of the application.
+    /// * `permissions` - The permissions to set.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`SetPermission`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-impl-portal-PermissionStore.SetPermission).
+    #[doc(alias = "SetPermission")]
+    pub async fn set_permission(
+        &self,
+        table: &str,
+        create: bool,
+        id: &str,
+        app_id: impl TryInto<AppID, Error = Error>,
+        permissions: &[&str],
+    ) -> Result<(), Error> {
+        self.0
+            .call(
+                "SetPermission",
+                &(table, create, id, app_id.try_into()?, permissions),
+            )
+            .await
+    }
+
+    /// Gets the permissions of an application for a resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table.
+    /// * `id` - The resource ID.
+    /// * `app_id` - The ID of the application.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`GetPermission`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-impl-portal-PermissionStore.GetPermission).
+    #[doc(alias = "GetPermission")]
+    pub async fn permission(
+        &self,
+        table: &str,
+        id: &str,
+        app_id: impl TryInto<AppID, Error = Error>,
+    ) -> Result<Vec<String>, Error> {
+        self.0
+            .call("GetPermission", &(table, id, app_id.try_into()?))
+            .await
+    }
+
+    /// Removes the permissions of an application for a resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table.
+    /// * `id` - The resource ID.
+    /// * `app_id` - The ID of the application.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`DeletePermission`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-impl-portal-PermissionStore.DeletePermission).
+    #[doc(alias = "DeletePermission")]
+    pub async fn delete_permission(
+        &self,
+        table: &str,
+        id: &str,
+        app_id: impl TryInto<AppID, Error = Error>,
+    ) -> Result<(), Error> {
+        self.0
+            .call("DeletePermission", &(table, id, app_id.try_into()?))
+            .await
+    }
+
+    /// Removes a resource from a table.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table.
+    /// * `id` - The resource ID.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Delete`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-impl-portal-PermissionStore.Delete).
+    #[doc(alias = "Delete")]
+    pub async fn delete(&self, table: &str, id: &str) -> Result<(), Error> {
+        self.0.call("Delete", &(table, id)).await
+    }
+
+    /// Lists the resources of a table.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`List`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-method-org-freedesktop-impl-portal-PermissionStore.List).
+    #[doc(alias = "List")]
+    pub async fn list(&self, table: &str) -> Result<Vec<String>, Error> {
+        self.0.call("List", &(table)).await
+    }
+
+    /// A stream of the resources that changed, in any table.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Changed`](https://flatpak.github.io/xdg-desktop-portal/index.html#gdbus-signal-org-freedesktop-impl-portal-PermissionStore.Changed).
+    #[doc(alias = "Changed")]
+    pub async fn receive_changed(
+        &self,
+    ) -> Result<impl Stream<Item = ResourceChanged> + Send + Unpin + 'a, Error> {
+        self.0.signals("Changed").await
+    }
+}
+
+impl AsProxy for PermissionStore<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for PermissionStore<'_> {}
@@ -33,7 +33,14 @@ pub enum PortalError {
 /// The error type for ashpd.
 pub enum Error {
     /// The portal request didn't succeed.
-    Response(ResponseError),
+    Response {
+        /// The handle token of the request, the last element of its object
+        /// path, to correlate the error with the request in logs. `None` if
+        /// the error wasn't received from a request.
+        token: Option<String>,
+        /// Why the request didn't succeed.
+        code: ResponseError,
+    },
     /// Something Failed on the portal request.
     Portal(PortalError),
     /// A zbus::fdo specific error.
@@ -44,15 +51,84 @@ pub enum Error {
     ParseError(&'static str),
     /// Input/Output
     IO(std::io::Error),
+    /// Failed to parse a URI
+    Url(url::ParseError),
     /// A pipewire error
     #[cfg(feature = "pipewire")]
     Pipewire(pw::Error),
+    /// Failed to decode an image
+    #[cfg(feature = "image")]
+    Image(image::ImageError),
     /// Invalid AppId
     ///
     /// See <https://developer.gnome.org/documentation/tutorials/application-id.html#rules-for-application-ids>
     InvalidAppID,
     /// An error indicating that an interior nul byte was found
     NulTerminated(usize),
+    /// The method is not supported by the running portal.
+    NotSupported {
+        /// The DBus interface.
+        interface: String,
+        /// The method name, empty if the whole interface is missing.
+        member: String,
+    },
+    /// A method was called while the session, or the response, wasn't in the
+    /// expected state.
+    InvalidState(&'static str),
+    /// The desktop portal is not running, nor could it be activated. Carries
+    /// the requested DBus interface.
+    PortalNotFound(String),
+    /// The restore token of a previous screen cast session couldn't be used
+    /// to restore any of its sources, for example because the monitor was
+    /// removed or the permission revoked. The application should fall back
+    /// to letting the user pick the sources again.
+    StaleRestoreToken,
+    /// A request was made with the handle token of another request that is
+    /// still waiting for its response. Carries the handle token.
+    TokenInUse(String),
+}
+
+impl Error {
+    /// Whether the portal request was cancelled by the user.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(
+            self,
+            Self::Response {
+                code: ResponseError::Cancelled,
+                ..
+            }
+        )
+    }
+
+    /// Whether the portal request was ended by the portal rather than by the
+    /// user, for example because the session was revoked.
+    pub fn is_ended(&self) -> bool {
+        matches!(
+            self,
+            Self::Response {
+                code: ResponseError::Other,
+                ..
+            }
+        )
+    }
+
+    /// Whether the error is caused by a method not supported by the running
+    /// portal.
+    pub fn is_not_supported(&self) -> bool {
+        matches!(self, Self::NotSupported { .. })
+    }
+
+    /// Whether the error is caused by the desktop portal not being available,
+    /// see [`crate::portal_is_running`].
+    pub fn is_portal_not_found(&self) -> bool {
+        matches!(self, Self::PortalNotFound(_))
+    }
+
+    /// Whether the error is caused by a restore token that is no longer
+    /// valid, see [`Error::StaleRestoreToken`].
+    pub fn is_stale_restore_token(&self) -> bool {
+        matches!(self, Self::StaleRestoreToken)
+    }
 }
 
 impl std::error::Error for Error {}
@@ -60,23 +136,49 @@ impl std::error::Error for Error {}
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Response(e) => f.write_str(&format!("Portal request didn't succeed: {e}")),
+            Self::Response {
+                token: Some(token),
+                code,
+            } => f.write_str(&format!("Portal request {token} didn't succeed: {code}")),
+            Self::Response { token: None, code } => {
+                f.write_str(&format!("Portal request didn't succeed: {code}"))
+            }
             Self::Zbus(e) => f.write_str(&format!("ZBus Error: {e}")),
             Self::Portal(e) => f.write_str(&format!("Portal request failed: {e}")),
             Self::NoResponse => f.write_str("Portal error: no response"),
             Self::IO(e) => f.write_str(&format!("IO: {e}")),
+            Self::Url(e) => f.write_str(&format!("Invalid URI: {e}")),
             #[cfg(feature = "pipewire")]
             Self::Pipewire(e) => f.write_str(&format!("Pipewire: {e}")),
+            #[cfg(feature = "image")]
+            Self::Image(e) => f.write_str(&format!("Image: {e}")),
             Self::ParseError(e) => f.write_str(e),
             Self::InvalidAppID => f.write_str("Invalid app id"),
             Self::NulTerminated(u) => write!(f, "Nul byte found in provided data at position {u}"),
+            Self::NotSupported { interface, member } if member.is_empty() => {
+                f.write_str(&format!("{interface} is not supported"))
+            }
+            Self::NotSupported { interface, member } => {
+                f.write_str(&format!("{interface} doesn't support {member}"))
+            }
+            Self::InvalidState(e) => f.write_str(&format!("Invalid state: {e}")),
+            Self::PortalNotFound(interface) => f.write_str(&format!(
+                "The desktop portal providing {interface} is not running"
+            )),
+            Self::StaleRestoreToken => f.write_str("The restore token is no longer valid"),
+            Self::TokenInUse(token) => f.write_str(&format!(
+                "The handle token {token} is used by another pending request"
+            )),
         }
     }
 }
 
 impl From<ResponseError> for Error {
     fn from(e: ResponseError) -> Self {
-        Self::Response(e)
+        Self::Response {
+            token: None,
+            code: e,
+        }
     }
 }
 
@@ -93,6 +195,13 @@ impl From<pw::Error> for Error {
     }
 }
 
+#[cfg(feature = "image")]
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        Self::Image(e)
+    }
+}
+
 impl From<zbus::fdo::Error> for Error {
     fn from(e: zbus::fdo::Error) -> Self {
         Self::Zbus(zbus::Error::FDO(Box::new(e)))
@@ -116,3 +225,9 @@ impl From<std::io::Error> for Error {
         Self::IO(e)
     }
 }
+
+impl From<url::ParseError> for Error {
+    fn from(e: url::ParseError) -> Self {
+        Self::Url(e)
+    }
+}
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use zbus::zvariant::Type;
 
 /// A file name represented as a nul-terminated byte array.
-#[derive(Type, Debug, Default, PartialEq)]
+#[derive(Type, Debug, Default, PartialEq, Clone)]
 #[zvariant(signature = "ay")]
 pub struct FilePath(CString);
 
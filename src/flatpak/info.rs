@@ -0,0 +1,154 @@
+//! # Examples
+//!
+//! ```rust,no_run
+//! async fn run() -> ashpd::Result<()> {
+//!     let info = ashpd::flatpak::info().await?;
+//!
+//!     println!("{}", info.application_id());
+//!     println!("{:#?}", info.instance_id());
+//!     println!("{:#?}", info.permissions("sockets"));
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+#[cfg(feature = "async-std")]
+use async_std::{fs::File, prelude::*};
+#[cfg(feature = "tokio")]
+use tokio::{fs::File, io::AsyncReadExt};
+
+use crate::{AppID, Error};
+
+/// The information of a running Flatpak application, read from
+/// `/.flatpak-info`.
+#[derive(Debug, Clone)]
+pub struct FlatpakInfo {
+    application_id: AppID,
+    runtime: Option<String>,
+    instance_id: Option<String>,
+    context: HashMap<String, Vec<String>>,
+}
+
+impl FlatpakInfo {
+    /// The application ID.
+    pub fn application_id(&self) -> &AppID {
+        &self.application_id
+    }
+
+    /// The runtime reference, like `runtime/org.gnome.Platform/x86_64/44`.
+    pub fn runtime(&self) -> Option<&str> {
+        self.runtime.as_deref()
+    }
+
+    /// The ID of the running instance of the application.
+    pub fn instance_id(&self) -> Option<&str> {
+        self.instance_id.as_deref()
+    }
+
+    /// The permissions of the sandbox for a kind of resource, for example
+    /// `shared`, `sockets`, `devices` or `filesystems`.
+    pub fn permissions(&self, kind: &str) -> &[String] {
+        self.context
+            .get(kind)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+impl std::str::FromStr for FlatpakInfo {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut groups = parse_key_file(s);
+        let mut application = groups.remove("Application").unwrap_or_default();
+        let mut instance = groups.remove("Instance").unwrap_or_default();
+        let context = groups
+            .remove("Context")
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, value)| {
+                let values = value
+                    .split(';')
+                    .filter(|value| !value.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect();
+                (key, values)
+            })
+            .collect();
+        let application_id = application
+            .remove("name")
+            .ok_or(Error::ParseError(
+                "Failed to parse Flatpak info, missing application name",
+            ))?
+            .try_into()?;
+        Ok(Self {
+            application_id,
+            runtime: application.remove("runtime"),
+            instance_id: instance.remove("instance-id"),
+            context,
+        })
+    }
+}
+
+fn parse_key_file(content: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut groups: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut group = None;
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            group = Some(groups.entry(name.to_owned()).or_default());
+        } else if let (Some(group), Some((key, value))) = (group.as_mut(), line.split_once('=')) {
+            group.insert(key.trim().to_owned(), value.trim().to_owned());
+        }
+    }
+    groups
+}
+
+/// Read the information of the running Flatpak application.
+///
+/// Fails with an [`Error::IO`] error when not running inside a Flatpak.
+pub async fn info() -> Result<FlatpakInfo, Error> {
+    let mut file = File::open("/.flatpak-info").await?;
+    let mut buffer = String::new();
+    file.read_to_string(&mut buffer).await?;
+    buffer.parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_flatpak_info() {
+        let info = "[Application]
+name=com.belmoussaoui.ashpd.demo
+runtime=runtime/org.gnome.Platform/x86_64/44
+
+[Instance]
+instance-id=2574198618
+app-path=/var/lib/flatpak/app/com.belmoussaoui.ashpd.demo/x86_64/master/active/files
+
+[Context]
+shared=network;ipc;
+sockets=x11;wayland;
+"
+        .parse::<FlatpakInfo>()
+        .unwrap();
+        assert_eq!(
+            info.application_id().as_ref(),
+            "com.belmoussaoui.ashpd.demo"
+        );
+        assert_eq!(info.runtime(), Some("runtime/org.gnome.Platform/x86_64/44"));
+        assert_eq!(info.instance_id(), Some("2574198618"));
+        assert_eq!(info.permissions("sockets"), ["x11", "wayland"]);
+        assert!(info.permissions("devices").is_empty());
+
+        assert!("[Instance]\ninstance-id=1\n"
+            .parse::<FlatpakInfo>()
+            .is_err());
+    }
+}
@@ -5,7 +5,7 @@
 //! ```rust,no_run
 //! use std::collections::HashMap;
 //!
-//! use ashpd::flatpak::{Flatpak, SpawnFlags, SpawnOptions};
+//! use ashpd::flatpak::{Flatpak, SpawnFlags};
 //!
 //! async fn run() -> ashpd::Result<()> {
 //!     let proxy = Flatpak::new().await?;
@@ -17,7 +17,36 @@
 //!             HashMap::new(),
 //!             HashMap::new(),
 //!             SpawnFlags::ClearEnv | SpawnFlags::NoNetwork,
-//!             SpawnOptions::default(),
+//!             (),
+//!         )
+//!         .await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Redirect the standard input & outputs of the spawned process
+//!
+//! ```rust,no_run
+//! use std::{collections::HashMap, fs::File};
+//!
+//! use ashpd::flatpak::{Flatpak, SpawnFlags, SpawnOptions};
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let proxy = Flatpak::new().await?;
+//!     let pty = File::options().read(true).write(true).open("/dev/ptmx")?;
+//!
+//!     proxy
+//!         .spawn(
+//!             "/",
+//!             &["bash"],
+//!             HashMap::new(),
+//!             HashMap::new(),
+//!             SpawnFlags::ClearEnv.into(),
+//!             SpawnOptions::default()
+//!                 .fd(0, &pty)?
+//!                 .fd(1, &pty)?
+//!                 .fd(2, &pty)?,
 //!         )
 //!         .await?;
 //!
@@ -28,16 +57,21 @@
 use std::{
     collections::HashMap,
     fmt::Debug,
-    os::unix::prelude::{AsRawFd, RawFd},
+    os::unix::prelude::{AsFd, AsRawFd, RawFd},
     path::Path,
 };
 
 use enumflags2::{bitflags, BitFlags};
+use futures_util::StreamExt;
 use serde::Serialize;
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{Fd, OwnedObjectPath, SerializeDict, Type};
+use zbus::zvariant::{Fd, OwnedObjectPath, OwnedValue, SerializeDict, Type, Value};
 
-use crate::{proxy::Proxy, Error, FilePath};
+use crate::{
+    helpers::WithExtra,
+    proxy::{sealed::AsProxy, Proxy},
+    Error, FilePath, PortalError, PortalProxy,
+};
 
 #[bitflags]
 #[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Copy, Clone, Debug, Type)]
@@ -78,8 +112,15 @@ pub enum SpawnFlags {
     #[doc(alias = "XDP_SPAWN_FLAG_WATCH")]
     /// Kill the sandbox when the caller disappears from the session bus.
     WatchBus,
+    #[doc(alias = "FLATPAK_SPAWN_FLAGS_EXPOSE_PIDS")]
     /// Expose the sandbox pids in the callers sandbox, only supported if using
-    /// user namespaces for containers (not setuid), see the support property.
+    /// user namespaces for containers (not setuid), see
+    /// [`Flatpak::supports`] and [`SupportsFlags::ExposePids`].
+    ///
+    /// The processes of the new sandbox are then visible, and can be traced
+    /// or signalled, from the caller's sandbox. The PID returned by
+    /// [`Flatpak::spawn`] is always the host one, use
+    /// [`Flatpak::spawn_exposing_pids`] to also get the sandbox one.
     ExposePids,
     /// Emit a SpawnStarted signal once the sandboxed process has been fully
     /// started.
@@ -101,9 +142,8 @@ pub enum SupportsFlags {
 }
 
 #[derive(SerializeDict, Type, Debug, Default)]
-/// Specified options for a [`Flatpak::spawn`] request.
 #[zvariant(signature = "dict")]
-pub struct SpawnOptions {
+struct SpawnOptionsDict {
     /// A list of filenames for files inside the sandbox that will be exposed to
     /// the new sandbox, for reading and writing.
     #[zvariant(rename = "sandbox-expose")]
@@ -136,6 +176,20 @@ pub struct SpawnOptions {
     app_fd: Option<RawFd>,
 }
 
+#[derive(Debug, Default)]
+/// Specified options for a [`Flatpak::spawn`] request.
+pub struct SpawnOptions {
+    options: SpawnOptionsDict,
+    fds: HashMap<u32, Fd>,
+    extra: HashMap<String, OwnedValue>,
+}
+
+impl From<()> for SpawnOptions {
+    fn from(_: ()) -> Self {
+        Self::default()
+    }
+}
+
 impl SpawnOptions {
     /// Sets the list of filenames for files to expose the new sandbox.
     /// **Note** absolute paths or subdirectories are not allowed.
@@ -144,7 +198,7 @@ impl SpawnOptions {
         mut self,
         sandbox_expose: impl Into<Option<P>>,
     ) -> Self {
-        self.sandbox_expose = sandbox_expose
+        self.options.sandbox_expose = sandbox_expose
             .into()
             .map(|a| a.into_iter().map(|s| s.as_ref().to_owned()).collect());
         self
@@ -158,7 +212,7 @@ impl SpawnOptions {
         mut self,
         sandbox_expose_ro: impl Into<Option<P>>,
     ) -> Self {
-        self.sandbox_expose_ro = sandbox_expose_ro
+        self.options.sandbox_expose_ro = sandbox_expose_ro
             .into()
             .map(|a| a.into_iter().map(|s| s.as_ref().to_owned()).collect());
         self
@@ -170,7 +224,7 @@ impl SpawnOptions {
         mut self,
         sandbox_expose_fd: impl Into<Option<P>>,
     ) -> Self {
-        self.sandbox_expose_fd = sandbox_expose_fd
+        self.options.sandbox_expose_fd = sandbox_expose_fd
             .into()
             .map(|a| a.into_iter().map(|s| Fd::from(s.as_raw_fd())).collect());
         self
@@ -183,7 +237,7 @@ impl SpawnOptions {
         mut self,
         sandbox_expose_fd_ro: impl Into<Option<P>>,
     ) -> Self {
-        self.sandbox_expose_fd_ro = sandbox_expose_fd_ro
+        self.options.sandbox_expose_fd_ro = sandbox_expose_fd_ro
             .into()
             .map(|a| a.into_iter().map(|s| Fd::from(s.as_raw_fd())).collect());
         self
@@ -195,7 +249,7 @@ impl SpawnOptions {
         mut self,
         sandbox_flags: impl Into<Option<BitFlags<SandboxFlags>>>,
     ) -> Self {
-        self.sandbox_flags = sandbox_flags.into();
+        self.options.sandbox_flags = sandbox_flags.into();
         self
     }
 
@@ -205,7 +259,7 @@ impl SpawnOptions {
         mut self,
         env: impl Into<Option<P>>,
     ) -> Self {
-        self.unset_env = env
+        self.options.unset_env = env
             .into()
             .map(|a| a.into_iter().map(|s| s.as_ref().to_owned()).collect());
         self
@@ -215,7 +269,7 @@ impl SpawnOptions {
     /// the new sandbox.
     #[must_use]
     pub fn usr_fd<F: AsRawFd>(mut self, fd: impl Into<Option<F>>) -> Self {
-        self.usr_fd = fd.into().map(|s| s.as_raw_fd());
+        self.options.usr_fd = fd.into().map(|s| s.as_raw_fd());
         self
     }
 
@@ -223,7 +277,40 @@ impl SpawnOptions {
     /// the new sandbox.
     #[must_use]
     pub fn app_fd<F: AsRawFd>(mut self, fd: impl Into<Option<F>>) -> Self {
-        self.app_fd = fd.into().map(|s| s.as_raw_fd());
+        self.options.app_fd = fd.into().map(|s| s.as_raw_fd());
+        self
+    }
+
+    /// Maps the file descriptor `source` to the file descriptor number
+    /// `target` in the new process, for example `0`, `1` and `2` to redirect
+    /// its standard input and outputs.
+    ///
+    /// **Note** only the file descriptor number is stored, `source` has to
+    /// stay open until the [`Flatpak::spawn`] call returns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PortalError::InvalidArgument`] if `target` is already mapped.
+    pub fn fd(mut self, target: u32, source: &impl AsFd) -> Result<Self, Error> {
+        if self.fds.contains_key(&target) {
+            return Err(PortalError::InvalidArgument(format!(
+                "File descriptor {target} is already mapped"
+            ))
+            .into());
+        }
+        self.fds
+            .insert(target, Fd::from(source.as_fd().as_raw_fd()));
+        Ok(self)
+    }
+
+    /// Adds a raw option, for options the portal supports but this type
+    /// doesn't expose yet.
+    ///
+    /// **Note** the option is sent as is, without any validation, and
+    /// replaces the typed one with the same key.
+    #[must_use]
+    pub fn extra<'a>(mut self, key: &str, value: impl Into<Value<'a>>) -> Self {
+        self.extra.insert(key.to_owned(), value.into().to_owned());
         self
     }
 }
@@ -296,15 +383,19 @@ impl<'a> Flatpak<'a> {
     /// * `cwd_path` - The working directory for the new process.
     /// * `arvg` - The argv for the new process, starting with the executable to
     ///   launch.
-    /// * `fds` - Array of file descriptors to pass to the new process.
+    /// * `fds` - Array of file descriptors to pass to the new process. They are
+    ///   merged with the ones mapped with [`SpawnOptions::fd`].
     /// * `envs` - Array of variable/value pairs for the environment of the new
     ///   process.
     /// * `flags`
-    /// * `options` - A [`SpawnOptions`].
+    /// * `options` - A [`SpawnOptions`], or `()` for the default options.
     ///
     /// # Returns
     ///
-    /// The PID of the new process.
+    /// The PID of the new process, in the host PID namespace. It can be used
+    /// with [`Self::spawn_signal`] and matched against the
+    /// [`Self::receive_spawn_existed`] signal, but not with the process
+    /// related system calls from within the sandbox.
     ///
     /// # Specifications
     ///
@@ -318,18 +409,75 @@ impl<'a> Flatpak<'a> {
         fds: HashMap<u32, Fd>,
         envs: HashMap<&str, &str>,
         flags: BitFlags<SpawnFlags>,
-        options: SpawnOptions,
+        options: impl Into<SpawnOptions>,
     ) -> Result<u32, Error> {
+        let SpawnOptions {
+            options,
+            fds: options_fds,
+            extra,
+        } = options.into();
+        let mut fds = fds;
+        for (target, fd) in options_fds {
+            if fds.insert(target, fd).is_some() {
+                return Err(PortalError::InvalidArgument(format!(
+                    "File descriptor {target} is already mapped"
+                ))
+                .into());
+            }
+        }
         let cwd_path = FilePath::new(cwd_path)?;
         let argv = argv
             .iter()
             .map(FilePath::new)
             .collect::<Result<Vec<FilePath>, _>>()?;
         self.0
-            .call("Spawn", &(cwd_path, argv, fds, envs, flags, options))
+            .call(
+                "Spawn",
+                &(
+                    cwd_path,
+                    argv,
+                    fds,
+                    envs,
+                    flags,
+                    WithExtra(&options, &extra),
+                ),
+            )
             .await
     }
 
+    /// Same as [`Self::spawn`], with the [`SpawnFlags::ExposePids`] and
+    /// [`SpawnFlags::NotifyStart`] flags, waiting for the process to start.
+    ///
+    /// # Returns
+    ///
+    /// The PID of the new process in the host PID namespace, like
+    /// [`Self::spawn`], and the one in the caller's sandbox, as reported by
+    /// the [`Self::receive_spawn_started`] signal. The latter can be used with
+    /// the process related system calls, e.g. to trace the process.
+    #[doc(alias = "Spawn")]
+    pub async fn spawn_exposing_pids(
+        &self,
+        cwd_path: impl AsRef<Path>,
+        argv: &[impl AsRef<Path>],
+        fds: HashMap<u32, Fd>,
+        envs: HashMap<&str, &str>,
+        flags: BitFlags<SpawnFlags>,
+        options: impl Into<SpawnOptions>,
+    ) -> Result<(u32, u32), Error> {
+        // Listened to beforehand, the process might start before the reply
+        let mut started = self.0.signals::<(u32, u32)>("SpawnStarted").await?;
+        let flags = flags | SpawnFlags::ExposePids | SpawnFlags::NotifyStart;
+        let pid = self
+            .spawn(cwd_path, argv, fds, envs, flags, options)
+            .await?;
+        while let Some((started_pid, relative_pid)) = started.next().await {
+            if started_pid == pid {
+                return Ok((pid, relative_pid));
+            }
+        }
+        Err(Error::NoResponse)
+    }
+
     /// This methods let you send a Unix signal to a process that was started
     /// [`spawn()`][`Flatpak::spawn`].
     ///
@@ -365,6 +513,18 @@ impl<'a> Flatpak<'a> {
     }
 }
 
+impl AsProxy for Flatpak<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for Flatpak<'_> {}
+
+/// Read the information of the running Flatpak application.
+mod info;
+pub use info::{info, FlatpakInfo};
+
 /// Monitor if there's an update it and install it.
 mod update_monitor;
 pub use update_monitor::{UpdateInfo, UpdateMonitor, UpdateProgress, UpdateStatus};
@@ -23,7 +23,10 @@
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{DeserializeDict, ObjectPath, SerializeDict, Type};
 
-use crate::{proxy::Proxy, Error, WindowIdentifier};
+use crate::{
+    proxy::{sealed::AsProxy, Proxy},
+    Error, PortalProxy, WindowIdentifier,
+};
 
 #[derive(SerializeDict, Type, Debug, Default)]
 /// Specified options for a [`UpdateMonitor::update`] request.
@@ -166,3 +169,11 @@ impl<'a> UpdateMonitor<'a> {
         self.0.call("Close", &()).await
     }
 }
+
+impl AsProxy for UpdateMonitor<'_> {
+    fn proxy(&self) -> &Proxy<'_> {
+        &self.0
+    }
+}
+
+impl PortalProxy for UpdateMonitor<'_> {}
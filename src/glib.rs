@@ -0,0 +1,60 @@
+//! GTK applications run a glib main loop, the helpers spawn the futures and
+//! signal streams of the portals on the default [`MainContext`], so they can
+//! update the UI from the main thread.
+//!
+//! **Note** with the `tokio` feature, the futures still have to run within a
+//! Tokio runtime, prefer the `async-std` feature otherwise.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ashpd::desktop::network_monitor::NetworkMonitor;
+//!
+//! fn watch_network() {
+//!     ashpd::glib::spawn(async {
+//!         let proxy = NetworkMonitor::new().await?;
+//!         while proxy.receive_changed().await.is_ok() {
+//!             println!("Network available: {}", proxy.is_available().await?);
+//!         }
+//!         ashpd::Result::Ok(())
+//!     });
+//! }
+//! ```
+
+use std::{future::Future, ops::ControlFlow};
+
+use ::glib::{JoinHandle, MainContext};
+use futures_util::{Stream, StreamExt};
+
+/// Spawn a future on the default [`MainContext`].
+///
+/// The future runs on the thread of the main context, it doesn't have to be
+/// [`Send`]. The returned handle can be awaited for its output.
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    MainContext::default().spawn_local(future)
+}
+
+/// Call `callback` with each item of `stream` on the default
+/// [`MainContext`], until the stream ends or the callback breaks.
+///
+/// Meant for the signal streams, like the one of
+/// [`responses_for`](crate::desktop::responses_for). For a stream borrowing
+/// its proxy, use [`spawn`] with an `async` block owning both instead.
+pub fn receive<S, F>(stream: S, mut callback: F) -> JoinHandle<()>
+where
+    S: Stream + 'static,
+    F: FnMut(S::Item) -> ControlFlow<()> + 'static,
+{
+    spawn(async move {
+        futures_util::pin_mut!(stream);
+        while let Some(item) = stream.next().await {
+            if callback(item).is_break() {
+                break;
+            }
+        }
+    })
+}
@@ -1,7 +1,53 @@
+use std::collections::HashMap;
+
 #[cfg(feature = "async-std")]
 use async_std::{fs::File, prelude::*};
+use serde::{ser::Error as _, Serialize, Serializer};
 #[cfg(feature = "tokio")]
 use tokio::{fs::File, io::AsyncReadExt};
+use zbus::zvariant::{self, OwnedValue, Signature, Type};
+
+/// Serializes a dictionary of options along with raw entries, which are
+/// added on top of the typed ones and replace them on conflict.
+#[derive(Debug)]
+pub(crate) struct WithExtra<'a, T>(pub(crate) &'a T, pub(crate) &'a HashMap<String, OwnedValue>);
+
+impl<T: Serialize + Type> Serialize for WithExtra<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.1.is_empty() {
+            return self.0.serialize(serializer);
+        }
+        let ctxt = zvariant::EncodingContext::<byteorder::NativeEndian>::new_dbus(0);
+        let (bytes, fds) = zvariant::to_bytes_fds(ctxt, self.0).map_err(S::Error::custom)?;
+        let mut options: HashMap<String, OwnedValue> =
+            zvariant::from_slice_fds(&bytes, Some(&fds), ctxt).map_err(S::Error::custom)?;
+        options.extend(
+            self.1
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        options.serialize(serializer)
+    }
+}
+
+impl<T> Type for WithExtra<'_, T> {
+    fn signature() -> Signature<'static> {
+        <HashMap<String, OwnedValue>>::signature()
+    }
+}
+
+/// Formats an optional token, or secret, without leaking its value into the
+/// logs.
+pub(crate) struct Redacted<'a>(pub(crate) &'a Option<String>);
+
+impl std::fmt::Debug for Redacted<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("Some(<redacted>)"),
+            None => f.write_str("None"),
+        }
+    }
+}
 
 pub(crate) async fn is_flatpak() -> bool {
     #[cfg(feature = "async-std")]
@@ -55,14 +101,50 @@ fn cgroup_v2_is_snap(cgroups: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn redacted() {
+        let token = Some("secret-token".to_owned());
+        assert_eq!(format!("{:?}", Redacted(&token)), "Some(<redacted>)");
+        assert_eq!(format!("{:?}", Redacted(&None)), "None");
+    }
+
+    #[test]
+    fn with_extra() {
+        #[derive(zvariant::SerializeDict, Type, Debug)]
+        #[zvariant(signature = "dict")]
+        struct Options {
+            modal: Option<bool>,
+            target: Option<String>,
+        }
+
+        let options = Options {
+            modal: Some(true),
+            target: Some("typed".to_owned()),
+        };
+        let mut extra = HashMap::new();
+        extra.insert(
+            "target".to_owned(),
+            OwnedValue::from(zvariant::Value::from("raw")),
+        );
+        extra.insert("new-option".to_owned(), OwnedValue::from(42u32));
+
+        let ctxt = zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+        let encoded = zvariant::to_bytes(ctxt, &WithExtra(&options, &extra)).unwrap();
+        let decoded: HashMap<String, OwnedValue> = zvariant::from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert!(bool::try_from(decoded["modal"].clone()).unwrap());
+        assert_eq!(String::try_from(decoded["target"].clone()).unwrap(), "raw");
+        assert_eq!(u32::try_from(decoded["new-option"].clone()).unwrap(), 42);
+    }
+
     #[test]
     fn test_cgroup_v2_is_snap() {
         let data =
             "0::/user.slice/user-1000.slice/user@1000.service/apps.slice/snap.something.scope\n";
-        assert_eq!(cgroup_v2_is_snap(data), true);
+        assert!(cgroup_v2_is_snap(data));
 
         let data = "0::/user.slice/user-1000.slice/user@1000.service/apps.slice\n";
-        assert_eq!(cgroup_v2_is_snap(data), false);
+        assert!(!cgroup_v2_is_snap(data));
 
         let data = "12:pids:/user.slice/user-1000.slice/user@1000.service
 11:perf_event:/
@@ -77,6 +159,6 @@ mod tests {
 2:cpu,cpuacct:/user.slice
 1:name=systemd:/user.slice/user-1000.slice/user@1000.service/apps.slice/apps-org.gnome.Terminal.slice/vte-spawn-228ae109-a869-4533-8988-65ea4c10b492.scope
 0::/user.slice/user-1000.slice/user@1000.service/apps.slice/apps-org.gnome.Terminal.slice/vte-spawn-228ae109-a869-4533-8988-65ea4c10b492.scope\n";
-        assert_eq!(cgroup_v2_is_snap(data), true);
+        assert!(cgroup_v2_is_snap(data));
     }
 }
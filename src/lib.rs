@@ -28,12 +28,27 @@ pub use self::file_path::FilePath;
 
 mod proxy;
 
+#[cfg(feature = "blocking")]
+/// Call the portals without an async runtime.
+pub mod blocking;
 /// Spawn commands outside the sandbox or monitor if the running application has
 /// received an update & install it.
 pub mod flatpak;
+#[cfg(feature = "glib")]
+/// Run portal futures and signal streams on the glib main loop.
+pub mod glib;
 mod helpers;
+/// Re-exports of the commonly used types.
+pub mod prelude;
+/// Detect the sandbox the application is running in.
+pub mod sandbox;
+/// Helpers to issue many portal calls at once.
+pub mod util;
 pub use enumflags2;
 pub use zbus::{self, zvariant};
+// Allows the derive macros to refer to `::ashpd` from within the crate
+#[cfg(feature = "macros")]
+extern crate self as ashpd;
 
 /// Check whether the application is running inside a sandbox.
 ///
@@ -48,4 +63,38 @@ pub async fn is_sandboxed() -> bool {
             .unwrap_or(false)
 }
 
-pub use self::error::{Error, PortalError};
+/// Check whether the desktop portal, `org.freedesktop.portal.Desktop`, is
+/// running, trying to activate it otherwise.
+///
+/// Creating a desktop portal proxy fails with [`Error::PortalNotFound`] when
+/// that is not the case, for example in minimal or headless environments.
+pub async fn portal_is_running() -> Result<bool> {
+    proxy::Proxy::desktop_is_running().await
+}
+
+/// Set a callback called when the session bus connection is lost, for
+/// example because the bus daemon was restarted.
+///
+/// The next portal call opens a new connection, the method calls made
+/// through already created proxies are sent over it too. The requests and
+/// sessions of the lost connection can't be restored though, neither can the
+/// signal streams, the callback is the place to create them again.
+pub fn set_connection_lost_callback(callback: impl Fn() + Send + Sync + 'static) {
+    proxy::Proxy::set_connection_lost_callback(std::sync::Arc::new(callback));
+}
+
+/// Whether the running portal supports the option `key` of an interface
+/// method, e.g. `persist_mode` of
+/// `org.freedesktop.portal.ScreenCast.SelectSources`.
+///
+/// The answer is based on the version of the interface that introduced the
+/// option, which the crate only knows for the options added after the first
+/// version of their interface. It is [`OptionSupport::Unknown`] otherwise.
+pub async fn supports_option(interface: &str, method: &str, key: &str) -> Result<OptionSupport> {
+    proxy::Proxy::supports_option(interface, method, key).await
+}
+
+pub use self::{
+    error::{Error, PortalError},
+    proxy::{OptionSupport, PortalProxy, RetryPolicy},
+};
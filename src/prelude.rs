@@ -0,0 +1,26 @@
+//! ```rust,no_run
+//! use ashpd::prelude::*;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let proxy = Settings::new().await?;
+//!     println!("{:#?}", proxy.color_scheme().await?);
+//!     Ok(())
+//! }
+//! ```
+
+pub use crate::{
+    desktop::{
+        account::UserInformation, background::Background, camera::Camera, device::DeviceProxy,
+        dynamic_launcher::DynamicLauncherProxy, email::EmailRequest, file_chooser::SelectedFiles,
+        game_mode::GameMode, global_shortcuts::GlobalShortcuts, inhibit::InhibitProxy,
+        location::LocationProxy, memory_monitor::MemoryMonitor, network_monitor::NetworkMonitor,
+        notification::NotificationProxy, power_profile_monitor::PowerProfileMonitor,
+        print::PrintProxy, proxy_resolver::ProxyResolver, realtime::Realtime,
+        remote_desktop::RemoteDesktop, screencast::Screencast, screenshot::Screenshot,
+        secret::Secret, settings::Settings, trash::TrashProxy, wallpaper::WallpaperRequest, Color,
+        Icon, PortalSession, Request, Response, ResponseError, Session,
+    },
+    documents::{Documents, FileTransfer},
+    flatpak::Flatpak,
+    Error, PortalProxy, Result, WindowIdentifier,
+};
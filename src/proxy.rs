@@ -1,37 +1,701 @@
-use std::{fmt::Debug, ops::Deref};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    future::{ready, Future},
+    io,
+    ops::Deref,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use futures_util::StreamExt;
+use futures_channel::oneshot;
+use futures_util::{future::BoxFuture, Stream, StreamExt};
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
-use zbus::zvariant::{ObjectPath, OwnedValue, Type};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Type};
 
 use crate::{
-    desktop::{HandleToken, Request},
+    desktop::{HandleToken, PendingRequest, Request},
     Error, PortalError,
 };
 
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+
 pub(crate) const DESKTOP_DESTINATION: &str = "org.freedesktop.portal.Desktop";
 pub(crate) const DESKTOP_PATH: &str = "/org/freedesktop/portal/desktop";
 
 pub(crate) const DOCUMENTS_DESTINATION: &str = "org.freedesktop.portal.Documents";
 pub(crate) const DOCUMENTS_PATH: &str = "/org/freedesktop/portal/documents";
 
+pub(crate) const PERMISSION_STORE_DESTINATION: &str = "org.freedesktop.impl.portal.PermissionStore";
+pub(crate) const PERMISSION_STORE_PATH: &str = "/org/freedesktop/impl/portal/PermissionStore";
+
 pub(crate) const FLATPAK_DESTINATION: &str = "org.freedesktop.portal.Flatpak";
 pub(crate) const FLATPAK_PATH: &str = "/org/freedesktop/portal/Flatpak";
 
-static SESSION: OnceCell<zbus::Connection> = OnceCell::new();
+/// The session bus connection shared by all the proxies.
+static SESSION: Mutex<Option<zbus::Connection>> = Mutex::new(None);
+/// Called when the session bus connection is lost, see
+/// [`crate::set_connection_lost_callback`].
+#[allow(clippy::type_complexity)]
+static CONNECTION_LOST_CALLBACK: Mutex<Option<Arc<dyn Fn() + Send + Sync>>> = Mutex::new(None);
+/// Set once the desktop portal is known to be running.
+static DESKTOP_FOUND: OnceCell<()> = OnceCell::new();
+
+/// The version of the interface that introduced an option of a method, as
+/// `(interface, method, key, version)`.
+const OPTION_VERSIONS: &[(&str, &str, &str, u32)] = &[
+    (
+        "org.freedesktop.portal.Email",
+        "ComposeEmail",
+        "addresses",
+        2,
+    ),
+    ("org.freedesktop.portal.Email", "ComposeEmail", "cc", 2),
+    ("org.freedesktop.portal.Email", "ComposeEmail", "bcc", 2),
+    (
+        "org.freedesktop.portal.FileChooser",
+        "OpenFile",
+        "current_folder",
+        3,
+    ),
+    (
+        "org.freedesktop.portal.FileChooser",
+        "OpenFile",
+        "directory",
+        3,
+    ),
+    (
+        "org.freedesktop.portal.Notification",
+        "AddNotification",
+        "markup-body",
+        2,
+    ),
+    (
+        "org.freedesktop.portal.Notification",
+        "AddNotification",
+        "sound",
+        2,
+    ),
+    (
+        "org.freedesktop.portal.Notification",
+        "AddNotification",
+        "display-hint",
+        2,
+    ),
+    (
+        "org.freedesktop.portal.Notification",
+        "AddNotification",
+        "category",
+        2,
+    ),
+    (
+        "org.freedesktop.portal.OpenURI",
+        "OpenURI",
+        "activation_token",
+        4,
+    ),
+    (
+        "org.freedesktop.portal.OpenURI",
+        "OpenFile",
+        "activation_token",
+        4,
+    ),
+    (
+        "org.freedesktop.portal.OpenURI",
+        "OpenDirectory",
+        "activation_token",
+        4,
+    ),
+    (
+        "org.freedesktop.portal.ScreenCast",
+        "SelectSources",
+        "cursor_mode",
+        2,
+    ),
+    (
+        "org.freedesktop.portal.ScreenCast",
+        "SelectSources",
+        "restore_token",
+        4,
+    ),
+    (
+        "org.freedesktop.portal.ScreenCast",
+        "SelectSources",
+        "persist_mode",
+        4,
+    ),
+];
+
+/// The version of the interface that introduced a method, as
+/// `(interface, method, version)`. The methods calls are refused beforehand
+/// if the running portal is older.
+///
+/// Only the methods added after the first version of their interface are
+/// listed, the other calls don't read the version.
+const METHOD_VERSIONS: &[(&str, &str, u32)] = &[
+    ("org.freedesktop.portal.Background", "SetStatus", 2),
+    ("org.freedesktop.portal.OpenURI", "OpenDirectory", 3),
+];
+
+fn method_version(interface: &str, method: &str) -> Option<u32> {
+    METHOD_VERSIONS
+        .iter()
+        .find(|(i, m, _)| *i == interface && *m == method)
+        .map(|(_, _, version)| *version)
+}
+
+fn option_version(interface: &str, method: &str, key: &str) -> Option<u32> {
+    OPTION_VERSIONS
+        .iter()
+        .find(|(i, m, k, _)| *i == interface && *m == method && *k == key)
+        .map(|(_, _, _, version)| *version)
+}
+
+/// Whether the running portal supports an option, see
+/// [`crate::supports_option`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptionSupport {
+    /// The option is supported by the running version of the interface.
+    Supported,
+    /// The interface is older than the version that introduced the option,
+    /// or is not provided at all.
+    Unsupported,
+    /// The crate doesn't know when the option was introduced, or the portal
+    /// doesn't report the version of the interface.
+    Unknown,
+}
+
+/// How to retry a method call failing with a transient error, like the bus
+/// not replying in time on a busy system.
+///
+/// Only the methods that can safely be called again are retried, the delay
+/// between the attempts doubles every time up to the maximum delay. By
+/// default, a call is attempted 3 times, waiting 100 milliseconds then 200
+/// milliseconds in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Sets how many times the call is attempted in total, at least once.
+    #[must_use]
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    #[must_use]
+    pub fn initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    /// Sets the longest delay between two attempts.
+    #[must_use]
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// The delay before the given retry, starting from `0`.
+    fn delay(&self, retry: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay)
+    }
+}
+
+/// The methods shared by the portal proxies, like
+/// [`Screencast`](crate::desktop::screencast::Screencast) or
+/// [`Documents`](crate::documents::Documents).
+///
+/// The trait is sealed, it is only implemented by the proxies of the crate.
+pub trait PortalProxy: sealed::AsProxy {
+    /// Sets the timeout of the method calls made through the proxy, none by
+    /// default.
+    ///
+    /// A call that doesn't get a reply in time fails with an [`Error::IO`]
+    /// error of kind [`std::io::ErrorKind::TimedOut`].
+    fn set_call_timeout(&self, timeout: impl Into<Option<Duration>>) {
+        self.proxy().set_call_timeout(timeout.into());
+    }
+
+    /// Same as [`PortalProxy::set_call_timeout`], for chaining.
+    #[must_use]
+    fn with_call_timeout(self, timeout: impl Into<Option<Duration>>) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_call_timeout(timeout);
+        self
+    }
+
+    /// Closes the requests and sessions created through the proxy that are
+    /// still open, for example before exiting.
+    ///
+    /// All of them are closed even if closing some fails, in which case the
+    /// first error is returned. The methods waiting for the response of one
+    /// of the requests fail with
+    /// [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled).
+    fn close_all(&self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.proxy().close_all())
+    }
+
+    /// The requests created through the proxy that are still waiting for a
+    /// response, for example because the user didn't close the dialog yet.
+    fn pending_requests(&self) -> Vec<PendingRequest> {
+        self.proxy().pending_requests()
+    }
+
+    /// Closes the pending request with the given handle token, see
+    /// [`PendingRequest::handle_token`].
+    ///
+    /// The method that created the request fails with
+    /// [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled)
+    /// afterwards. Returns whether such a request was pending.
+    fn cancel_request<'p>(&'p self, handle_token: &'p str) -> BoxFuture<'p, Result<bool, Error>> {
+        Box::pin(self.proxy().cancel_request(handle_token))
+    }
+}
+
+pub(crate) mod sealed {
+    /// Gives access to the [`Proxy`](super::Proxy) a portal proxy wraps.
+    pub trait AsProxy {
+        fn proxy(&self) -> &super::Proxy<'_>;
+    }
+}
+
+/// Calls `f` again while it fails with a transient error, following
+/// `policy`.
+pub(crate) async fn retry<T, F, Fut>(policy: RetryPolicy, mut f: F) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut retry = 0;
+    loop {
+        match f().await {
+            Err(err) if retry + 1 < policy.max_attempts && is_transient(&err) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("Retrying after a transient error: {}", err);
+                sleep(policy.delay(retry)).await;
+                retry += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(feature = "async-std")]
+    async_std::task::sleep(duration).await;
+    #[cfg(feature = "tokio")]
+    tokio::time::sleep(duration).await;
+}
+
+/// A request or session created through a proxy and still open.
+#[derive(Debug)]
+struct Handle {
+    interface: &'static str,
+    /// The connection the handle was created through, the portal only lets
+    /// it close the handle.
+    connection: zbus::Connection,
+    /// Whether `connection` is the shared one, the handle is forgotten once
+    /// it's lost.
+    shared: bool,
+    /// Set while the request waits for its response. Dropping the sender
+    /// makes the request fail with
+    /// [`crate::desktop::ResponseError::Cancelled`].
+    pending: Option<(PendingRequest, oneshot::Sender<()>)>,
+}
+
+/// The requests and sessions created through a proxy that are still open, by
+/// object path.
+///
+/// Shared with the requests and sessions, which stop being tracked once
+/// closed.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Handles(Arc<Mutex<HashMap<OwnedObjectPath, Handle>>>);
+
+impl Handle {
+    fn new(
+        interface: &'static str,
+        connection: &zbus::Connection,
+        pending: Option<(PendingRequest, oneshot::Sender<()>)>,
+    ) -> Self {
+        let shared = Proxy::session()
+            .is_some_and(|session| session.unique_name() == connection.unique_name());
+        Self {
+            interface,
+            connection: connection.clone(),
+            shared,
+            pending,
+        }
+    }
+}
+
+impl Handles {
+    /// The tracked handles, without the ones created on a shared connection
+    /// that was lost since, they can't be closed anymore.
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<OwnedObjectPath, Handle>> {
+        let session = Proxy::session();
+        let session = session.as_ref().and_then(|session| session.unique_name());
+        let mut handles = self.0.lock().unwrap();
+        handles.retain(|_, handle| !handle.shared || handle.connection.unique_name() == session);
+        handles
+    }
+
+    /// Keeps track of an open request or session, see [`Proxy::close_all`].
+    pub(crate) fn insert(
+        &self,
+        interface: &'static str,
+        path: OwnedObjectPath,
+        connection: &zbus::Connection,
+    ) {
+        self.lock()
+            .entry(path)
+            .or_insert_with(|| Handle::new(interface, connection, None));
+    }
+
+    /// Keeps track of a request about to be created, until it receives its
+    /// response, see [`Proxy::pending_requests`].
+    fn insert_pending(
+        &self,
+        path: OwnedObjectPath,
+        connection: &zbus::Connection,
+        request: PendingRequest,
+        cancel: oneshot::Sender<()>,
+    ) -> Result<(), Error> {
+        let mut handles = self.lock();
+        // Both requests would share the object path, one of them would never
+        // get its response
+        if handles.contains_key(&path) {
+            return Err(Error::TokenInUse(request.handle_token().to_owned()));
+        }
+        handles.insert(
+            path,
+            Handle::new(REQUEST_INTERFACE, connection, Some((request, cancel))),
+        );
+        Ok(())
+    }
+
+    /// Whether a request or session is still tracked, i.e. wasn't closed yet.
+    pub(crate) fn contains(&self, path: &OwnedObjectPath) -> bool {
+        self.lock().contains_key(path)
+    }
+
+    /// Stops tracking a request or session once it is closed.
+    pub(crate) fn remove(&self, path: &OwnedObjectPath) {
+        self.lock().remove(path);
+    }
+
+    fn pending_requests(&self) -> Vec<PendingRequest> {
+        self.lock()
+            .values()
+            .filter_map(|handle| handle.pending.as_ref())
+            .map(|(request, _)| request.clone())
+            .collect()
+    }
+
+    /// Stops tracking the pending request with the given handle token, which
+    /// cancels it, returns it if there was such a request.
+    fn remove_pending(&self, handle_token: &str) -> Option<(OwnedObjectPath, Handle)> {
+        let mut handles = self.lock();
+        let path = handles
+            .iter()
+            .find(|(_, handle)| {
+                handle
+                    .pending
+                    .as_ref()
+                    .is_some_and(|(request, _)| request.handle_token() == handle_token)
+            })
+            .map(|(path, _)| path.clone())?;
+        handles.remove_entry(&path)
+    }
+
+    fn take(&self) -> HashMap<OwnedObjectPath, Handle> {
+        std::mem::take(&mut *self.lock())
+    }
+}
+
+/// The connection a [`Proxy`] was created on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConnectionKind {
+    /// The session bus connection shared by the crate, replaced once lost.
+    Shared,
+    /// A connection given by the application, see [`Proxy::from_connection`].
+    Owned,
+}
 
+/// A proxy of a portal interface, along with the kind of connection it was
+/// created on, the timeout of its method calls and the requests and sessions
+/// created through it.
+///
+/// The method calls of a proxy on the shared connection go through a new one
+/// if it was lost. The properties and signals are only read from the
+/// connection the proxy was created on, the proxy has to be created again
+/// once it's lost.
 #[derive(Debug)]
-pub struct Proxy<'a>(zbus::Proxy<'a>);
+pub struct Proxy<'a>(
+    zbus::Proxy<'a>,
+    ConnectionKind,
+    Mutex<Option<Duration>>,
+    Handles,
+);
 
 impl<'a> Proxy<'a> {
+    /// The shared session bus connection, created on first usage and again
+    /// once it was lost.
+    ///
+    /// Concurrent first calls might each open a connection, in which case
+    /// only the first stored one is kept and returned to every caller.
     pub(crate) async fn connection() -> zbus::Result<zbus::Connection> {
-        if let Some(cnx) = SESSION.get() {
-            Ok(cnx.clone())
+        if let Some(cnx) = Self::session() {
+            return Ok(cnx);
+        }
+        let cnx = zbus::Connection::session().await?;
+        let mut session = SESSION.lock().unwrap();
+        if let Some(cnx) = &*session {
+            return Ok(cnx.clone());
+        }
+        Self::watch_connection(&cnx);
+        *session = Some(cnx.clone());
+        Ok(cnx)
+    }
+
+    fn session() -> Option<zbus::Connection> {
+        SESSION.lock().unwrap().clone()
+    }
+
+    /// Forgets the connection once its socket is closed, so that the next
+    /// call opens a new one.
+    fn watch_connection(connection: &zbus::Connection) {
+        let unique_name = connection.unique_name().cloned();
+        let mut stream = zbus::MessageStream::from(connection);
+        connection
+            .executor()
+            .spawn(
+                async move {
+                    // The stream yields an error then ends once the socket is closed
+                    while let Some(Ok(_)) = stream.next().await {}
+                    Self::connection_lost(unique_name.as_ref());
+                },
+                "ashpd connection watcher",
+            )
+            .detach();
+    }
+
+    pub(crate) fn connection_lost(unique_name: Option<&zbus::names::OwnedUniqueName>) {
+        {
+            let mut session = SESSION.lock().unwrap();
+            match &*session {
+                Some(cnx) if cnx.unique_name() == unique_name => *session = None,
+                _ => return,
+            }
+        }
+        #[cfg(feature = "tracing")]
+        tracing::warn!("The session bus connection was lost");
+        // Not called with the lock held, the callback might replace itself
+        let callback = CONNECTION_LOST_CALLBACK.lock().unwrap().clone();
+        if let Some(callback) = callback {
+            callback();
+        }
+    }
+
+    pub(crate) fn set_connection_lost_callback(callback: Arc<dyn Fn() + Send + Sync>) {
+        *CONNECTION_LOST_CALLBACK.lock().unwrap() = Some(callback);
+    }
+
+    /// The connection the method calls are sent on, the shared one being
+    /// opened again if it was lost.
+    pub(crate) async fn call_connection(&self) -> zbus::Result<zbus::Connection> {
+        match self.1 {
+            // A connection given by the application is never replaced
+            ConnectionKind::Owned => Ok(self.0.connection().clone()),
+            ConnectionKind::Shared => Self::connection().await,
+        }
+    }
+
+    /// Calls a method of the interface, through a new proxy if the connection
+    /// this one was created with was lost meanwhile.
+    async fn call_method_reconnecting(
+        &self,
+        method_name: &str,
+        body: &(impl Serialize + Type + Debug),
+    ) -> zbus::Result<Arc<zbus::Message>> {
+        let connection = self.call_connection().await?;
+        self.call_method_on(&connection, method_name, body).await
+    }
+
+    /// Calls a method of the interface on `connection`, through a new proxy
+    /// if it isn't the one this proxy was created with.
+    async fn call_method_on(
+        &self,
+        connection: &zbus::Connection,
+        method_name: &str,
+        body: &(impl Serialize + Type + Debug),
+    ) -> zbus::Result<Arc<zbus::Message>> {
+        if connection.unique_name() == self.0.connection().unique_name() {
+            return self.0.call_method(method_name, body).await;
+        }
+        let proxy = zbus::ProxyBuilder::<zbus::Proxy<'_>>::new_bare(connection)
+            .interface(self.interface().to_owned())?
+            .path(self.path().to_owned())?
+            .destination(self.destination().to_owned())?
+            .cache_properties(zbus::CacheProperties::No)
+            .build()
+            .await?;
+        proxy.call_method(method_name, body).await
+    }
+
+    /// Calls the `Close` method of the object at `path`, created through
+    /// `connection`, without waiting for the reply, for usage in `Drop`
+    /// implementations.
+    pub(crate) fn close_in_background(
+        connection: zbus::Connection,
+        interface: &'static str,
+        path: OwnedObjectPath,
+    ) {
+        #[cfg(feature = "tracing")]
+        tracing::info!("Closing {} {} in the background", interface, path.as_str());
+        let executor = connection.executor().clone();
+        executor
+            .spawn(
+                async move {
+                    let _ = connection
+                        .call_method(
+                            Some(DESKTOP_DESTINATION),
+                            path,
+                            Some(interface),
+                            "Close",
+                            &(),
+                        )
+                        .await;
+                },
+                "ashpd close",
+            )
+            .detach();
+    }
+
+    /// The requests and sessions created through the proxy that are still
+    /// open.
+    pub(crate) fn handles(&self) -> &Handles {
+        &self.3
+    }
+
+    /// Closes the requests and sessions created through the proxy that are
+    /// still open.
+    ///
+    /// Every handle is closed even if some fail, the first error is returned.
+    pub(crate) async fn close_all(&self) -> Result<(), Error> {
+        // Dropping the handles cancels the requests waiting for a response
+        let handles = self.3.take();
+        let mut result = Ok(());
+        for (path, handle) in handles {
+            let closed = Self::close_handle(&handle, &path).await;
+            if let (Err(err), Ok(())) = (closed, &result) {
+                result = Err(err);
+            }
+        }
+        result
+    }
+
+    /// The requests created through the proxy that are waiting for a
+    /// response.
+    pub(crate) fn pending_requests(&self) -> Vec<PendingRequest> {
+        self.3.pending_requests()
+    }
+
+    /// Closes the pending request with the given handle token, returns
+    /// whether there was such a request.
+    pub(crate) async fn cancel_request(&self, handle_token: &str) -> Result<bool, Error> {
+        let Some((path, handle)) = self.3.remove_pending(handle_token) else {
+            return Ok(false);
+        };
+        Self::close_handle(&handle, &path).await?;
+        Ok(true)
+    }
+
+    /// Closes a request or session through the connection that created it.
+    async fn close_handle(handle: &Handle, path: &OwnedObjectPath) -> Result<(), Error> {
+        #[cfg(feature = "tracing")]
+        tracing::info!("Closing {} {}", handle.interface, path.as_str());
+        handle
+            .connection
+            .call_method(
+                Some(DESKTOP_DESTINATION),
+                path,
+                Some(handle.interface),
+                "Close",
+                &(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Whether the desktop portal is running, trying to activate it if not.
+    pub(crate) async fn desktop_is_running() -> Result<bool, Error> {
+        if DESKTOP_FOUND.get().is_some() {
+            return Ok(true);
+        }
+        let connection = Self::connection().await?;
+        let dbus = zbus::fdo::DBusProxy::new(&connection).await?;
+        let name = zbus::names::WellKnownName::from_static_str_unchecked(DESKTOP_DESTINATION);
+        let running = if dbus.name_has_owner(name.clone().into()).await? {
+            true
+        } else {
+            match dbus.start_service_by_name(name, 0).await {
+                Ok(_) => true,
+                Err(err) if is_service_not_found(&err) => false,
+                Err(err) => return Err(err.into()),
+            }
+        };
+        if running {
+            let _ = DESKTOP_FOUND.set(());
+        }
+        Ok(running)
+    }
+
+    pub(crate) async fn supports_option(
+        interface: &str,
+        method: &str,
+        key: &str,
+    ) -> Result<OptionSupport, Error> {
+        let Some(introduced) = option_version(interface, method, key) else {
+            return Ok(OptionSupport::Unknown);
+        };
+        let proxy = match Proxy::new_desktop_checked(interface).await {
+            Ok(proxy) => proxy,
+            Err(Error::NotSupported { .. }) => return Ok(OptionSupport::Unsupported),
+            Err(err) => return Err(err),
+        };
+        Ok(match proxy.version().await {
+            Ok(version) if version >= introduced => OptionSupport::Supported,
+            Ok(_) => OptionSupport::Unsupported,
+            Err(_) => OptionSupport::Unknown,
+        })
+    }
+
+    async fn ensure_desktop(interface: &str) -> Result<(), Error> {
+        if Self::desktop_is_running().await? {
+            Ok(())
         } else {
-            let cnx = zbus::Connection::session().await?;
-            SESSION.set(cnx.clone()).expect("Can't reset a OnceCell");
-            Ok(cnx)
+            Err(Error::PortalNotFound(interface.to_owned()))
         }
     }
 
@@ -40,6 +704,16 @@ impl<'a> Proxy<'a> {
         handle_token: &HandleToken,
     ) -> Result<ObjectPath<'static>, Error> {
         let connection = Self::connection().await?;
+        Self::unique_name_with(&connection, prefix, handle_token)
+    }
+
+    /// Same as [`Proxy::unique_name`], for the objects created through
+    /// `connection`.
+    pub(crate) fn unique_name_with(
+        connection: &zbus::Connection,
+        prefix: &str,
+        handle_token: &HandleToken,
+    ) -> Result<ObjectPath<'static>, Error> {
         let unique_name = connection.unique_name().unwrap();
         let unique_identifier = unique_name.trim_start_matches(':').replace('.', "_");
         ObjectPath::try_from(format!("{prefix}/{unique_identifier}/{handle_token}"))
@@ -62,7 +736,49 @@ impl<'a> Proxy<'a> {
             .destination(destination)?
             .build()
             .await?;
-        Ok(Self(proxy))
+        Ok(Self(
+            proxy,
+            ConnectionKind::Shared,
+            Mutex::new(None),
+            Handles::default(),
+        ))
+    }
+
+    /// Creates a proxy of the desktop portal on a connection owned by the
+    /// application instead of the shared one.
+    ///
+    /// The requests created through it use the same connection. It is not
+    /// replaced if it gets closed, and the desktop portal isn't looked up
+    /// beforehand to avoid opening the shared connection.
+    pub async fn from_connection(
+        connection: &zbus::Connection,
+        interface: &'a str,
+    ) -> Result<Proxy<'a>, Error> {
+        Self::from_connection_with_path(connection, interface, DESKTOP_PATH).await
+    }
+
+    /// Same as [`Proxy::from_connection`], for the object at `path`.
+    pub async fn from_connection_with_path<P>(
+        connection: &zbus::Connection,
+        interface: &'a str,
+        path: P,
+    ) -> Result<Proxy<'a>, Error>
+    where
+        P: TryInto<ObjectPath<'a>>,
+        P::Error: Into<zbus::Error>,
+    {
+        let proxy = zbus::ProxyBuilder::new_bare(connection)
+            .interface(interface)?
+            .path(path)?
+            .destination(DESKTOP_DESTINATION)?
+            .build()
+            .await?;
+        Ok(Self(
+            proxy,
+            ConnectionKind::Owned,
+            Mutex::new(None),
+            Handles::default(),
+        ))
     }
 
     pub async fn new_desktop_with_path<P>(interface: &'a str, path: P) -> Result<Proxy<'a>, Error>
@@ -70,17 +786,55 @@ impl<'a> Proxy<'a> {
         P: TryInto<ObjectPath<'a>>,
         P::Error: Into<zbus::Error>,
     {
+        Self::ensure_desktop(interface).await?;
         Self::new(interface, path, DESKTOP_DESTINATION).await
     }
 
     pub async fn new_desktop(interface: &'a str) -> Result<Proxy<'a>, Error> {
+        Self::ensure_desktop(interface).await?;
         Self::new(interface, DESKTOP_PATH, DESKTOP_DESTINATION).await
     }
 
+    /// Like [`Proxy::new_desktop`], but fails with [`Error::NotSupported`] if
+    /// the interface isn't exported on the desktop portal object.
+    pub async fn new_desktop_checked(interface: &'a str) -> Result<Proxy<'a>, Error> {
+        let proxy = Self::new_desktop(interface).await?;
+        if proxy.is_exported().await? {
+            Ok(proxy)
+        } else {
+            Err(Error::NotSupported {
+                interface: interface.to_owned(),
+                member: String::new(),
+            })
+        }
+    }
+
+    /// Whether the interface is exported on the object, looked up by
+    /// introspecting it.
+    pub(crate) async fn is_exported(&self) -> Result<bool, Error> {
+        let xml = zbus::fdo::IntrospectableProxy::builder(self.connection())
+            .destination(self.destination().to_owned())?
+            .path(self.path().to_owned())?
+            .build()
+            .await?
+            .introspect()
+            .await?;
+        Ok(xml.contains(&format!("<interface name=\"{}\"", self.interface())))
+    }
+
     pub async fn new_documents(interface: &'a str) -> Result<Proxy<'a>, Error> {
         Self::new(interface, DOCUMENTS_PATH, DOCUMENTS_DESTINATION).await
     }
 
+    pub async fn new_permission_store(interface: &'a str) -> Result<Proxy<'a>, Error> {
+        Self::new(
+            interface,
+            PERMISSION_STORE_PATH,
+            PERMISSION_STORE_DESTINATION,
+        )
+        .await
+    }
+
     pub async fn new_flatpak(interface: &'a str) -> Result<Proxy<'a>, Error> {
         Self::new(interface, FLATPAK_PATH, FLATPAK_DESTINATION).await
     }
@@ -93,6 +847,18 @@ impl<'a> Proxy<'a> {
         Self::new(interface, path, FLATPAK_DESTINATION).await
     }
 
+    /// The raw `Response` signals of the request identified by
+    /// `handle_token`, see [`crate::desktop::responses_for`].
+    pub async fn responses_for(
+        handle_token: &HandleToken,
+    ) -> Result<impl Stream<Item = (u32, HashMap<String, OwnedValue>)>, Error> {
+        let path =
+            Self::unique_name("/org/freedesktop/portal/desktop/request", handle_token).await?;
+        let proxy = Self::new_desktop_with_path("org.freedesktop.portal.Request", path).await?;
+        let stream = proxy.receive_signal("Response").await?;
+        Ok(stream.filter_map(|message| ready(message.body().ok())))
+    }
+
     pub async fn request<T>(
         &self,
         handle_token: &HandleToken,
@@ -102,12 +868,61 @@ impl<'a> Proxy<'a> {
     where
         T: for<'de> Deserialize<'de> + Type + Debug,
     {
-        let mut request = Request::from_unique_name(handle_token).await?;
-        futures_util::try_join!(request.prepare_response(), async {
-            self.call_method(method_name, &body)
+        let mut request = self.send_request(handle_token, method_name, body).await?;
+        request.wait_response().await?;
+        Ok(request)
+    }
+
+    /// Same as [`Proxy::request`], returning the request as soon as the
+    /// portal created it, without waiting for its response.
+    pub(crate) async fn send_request<T>(
+        &self,
+        handle_token: &HandleToken,
+        method_name: &str,
+        body: impl Serialize + Type + Debug,
+    ) -> Result<Request<T>, Error>
+    where
+        T: for<'de> Deserialize<'de> + Type + Debug,
+    {
+        self.check_method_version(method_name).await?;
+        // The portal names the request after the caller, the connection the
+        // method is called on has to be the one listening for the response
+        let connection = self.call_connection().await?;
+        let path = OwnedObjectPath::from(Self::unique_name_with(
+            &connection,
+            "/org/freedesktop/portal/desktop/request",
+            handle_token,
+        )?);
+        let (cancel, cancelled) = oneshot::channel();
+        let pending = PendingRequest::new(handle_token, self.interface(), method_name);
+        self.3
+            .insert_pending(path.clone(), &connection, pending, cancel)?;
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            "Creating a org.freedesktop.portal.Request {}",
+            path.as_str()
+        );
+        let request =
+            match Request::with_connection(&connection, path.clone(), cancelled, self.3.clone())
+                .await
+            {
+                Ok(request) => request,
+                Err(err) => {
+                    self.3.remove(&path);
+                    return Err(err);
+                }
+            };
+        // Dropping the request on failure closes it, in case the portal
+        // created it anyway
+        with_timeout(self.call_timeout(), async {
+            self.call_method_on(&connection, method_name, &body)
                 .await
-                .map_err(From::from)
-        })?;
+                .map_err(|e| match self.typed_error(method_name, &e) {
+                    Some(err) => err,
+                    None => From::from(e),
+                })
+        })
+        .await?;
         Ok(request)
     }
 
@@ -133,16 +948,86 @@ impl<'a> Proxy<'a> {
             tracing::info!("Calling method {}:{}", self.interface(), method_name);
             tracing::debug!("With body {:#?}", body);
         }
-        let msg = self
-            .call_method(method_name, &body)
-            .await
-            .map_err::<PortalError, _>(From::from)?;
+        self.check_method_version(method_name).await?;
+        let msg = with_timeout(self.call_timeout(), async {
+            self.call_method_reconnecting(method_name, &body)
+                .await
+                .map_err(|e| match self.typed_error(method_name, &e) {
+                    Some(err) => err,
+                    None => PortalError::from(e).into(),
+                })
+        })
+        .await?;
         let reply = msg.body::<R>()?;
         msg.take_fds();
 
         Ok(reply)
     }
 
+    /// Same as [`Proxy::call`], retried following `policy`.
+    ///
+    /// **Note** only for the methods that can be called several times
+    /// without side effects.
+    pub(crate) async fn call_with_retry<R>(
+        &self,
+        method_name: &str,
+        body: impl Serialize + Type + Debug,
+        policy: RetryPolicy,
+    ) -> Result<R, Error>
+    where
+        R: for<'de> Deserialize<'de> + Type,
+    {
+        retry(policy, || self.call(method_name, &body)).await
+    }
+
+    /// The timeout of the method calls, none by default.
+    pub(crate) fn call_timeout(&self) -> Option<Duration> {
+        *self.2.lock().unwrap()
+    }
+
+    pub(crate) fn set_call_timeout(&self, timeout: Option<Duration>) {
+        *self.2.lock().unwrap() = timeout;
+    }
+
+    /// Fails with [`Error::NotSupported`] if the running version of the
+    /// interface is older than the one that introduced `method_name`, instead
+    /// of the portal replying with an unknown method error.
+    async fn check_method_version(&self, method_name: &str) -> Result<(), Error> {
+        let Some(introduced) = method_version(self.interface(), method_name) else {
+            return Ok(());
+        };
+        match self.version().await {
+            Ok(version) if version < introduced => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    "{}:{} requires version {} of the interface, the portal provides version {}",
+                    self.interface(),
+                    method_name,
+                    introduced,
+                    version
+                );
+                Err(Error::NotSupported {
+                    interface: self.interface().to_string(),
+                    member: method_name.to_owned(),
+                })
+            }
+            // A missing interface makes the call fail on its own
+            _ => Ok(()),
+        }
+    }
+
+    /// The error to return instead of the generic DBus one, if the method, or
+    /// the whole portal, is missing.
+    fn typed_error(&self, method_name: &str, error: &zbus::Error) -> Option<Error> {
+        if is_unknown_member(error) {
+            return Some(Error::NotSupported {
+                interface: self.interface().to_string(),
+                member: method_name.to_owned(),
+            });
+        }
+        portal_not_found(self.interface(), error)
+    }
+
     pub async fn property<T>(&self, property_name: &str) -> Result<T, Error>
     where
         T: TryFrom<OwnedValue>,
@@ -151,7 +1036,35 @@ impl<'a> Proxy<'a> {
         self.0
             .get_property::<T>(property_name)
             .await
-            .map_err(From::from)
+            .map_err(|e| portal_not_found(self.interface(), &e).unwrap_or_else(|| e.into()))
+    }
+
+    /// A stream of the values of `property_name` as they change.
+    ///
+    /// Properties that are only invalidated by the service are fetched again,
+    /// values that fail to be retrieved or converted are skipped.
+    pub(crate) async fn receive_property_changed<T>(
+        &self,
+        property_name: &'static str,
+    ) -> Result<impl Stream<Item = T> + 'a, Error>
+    where
+        T: TryFrom<OwnedValue> + Unpin + 'a,
+        <T as TryFrom<OwnedValue>>::Error: Into<zbus::Error>,
+    {
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            "Listening to property '{}' changes on '{}'",
+            property_name,
+            self.interface()
+        );
+        let stream = self.0.receive_property_changed::<T>(property_name).await;
+        Ok(stream
+            .then(|changed| async move { changed.get().await.ok() })
+            .filter_map(ready))
+    }
+
+    pub(crate) async fn version(&self) -> Result<u32, Error> {
+        self.property("version").await
     }
 
     pub(crate) async fn signal<R>(&self, signal_name: &'static str) -> Result<R, Error>
@@ -181,6 +1094,32 @@ impl<'a> Proxy<'a> {
         tracing::debug!("With body {:#?}", content);
         Ok(content)
     }
+
+    /// A stream of the `signal_name` signals, skipping the ones that can't be
+    /// deserialized.
+    ///
+    /// The stream is `Send` and `Unpin` so it can be raced against other
+    /// streams or futures, e.g. with `futures_util::select!`.
+    pub(crate) async fn signals<R>(
+        &self,
+        signal_name: &'static str,
+    ) -> Result<impl Stream<Item = R> + Send + Unpin + 'a, Error>
+    where
+        R: for<'de> Deserialize<'de> + Type + Debug + Send + 'a,
+    {
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            "Listening to signals '{}' on '{}'",
+            signal_name,
+            self.interface()
+        );
+        let stream = self
+            .0
+            .receive_signal(signal_name)
+            .await
+            .map_err::<PortalError, _>(From::from)?;
+        Ok(stream.filter_map(|message| ready(message.body::<R>().ok())))
+    }
 }
 
 impl<'a> Deref for Proxy<'a> {
@@ -190,3 +1129,465 @@ impl<'a> Deref for Proxy<'a> {
         &self.0
     }
 }
+
+/// Owns the desktop portal name for a test, waiting for the other tests to
+/// release it. Returns `None`, reporting the test as skipped, if there is no
+/// session bus, e.g. outside of `dbus-run-session cargo test`, or if a portal
+/// is already running.
+#[cfg(all(test, feature = "async-std"))]
+pub(crate) async fn mock_desktop() -> Option<zbus::Connection> {
+    struct MockDesktop;
+
+    if std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_none() {
+        eprintln!("skipped: requires a session bus");
+        return None;
+    }
+
+    #[zbus::dbus_interface(name = "org.freedesktop.portal.Mock")]
+    impl MockDesktop {}
+
+    for _ in 0..50 {
+        // Serving an object makes the builder wait for the object server to
+        // dispatch method calls before owning the name, otherwise the first
+        // calls to the mocked portals can get lost.
+        let builder = zbus::ConnectionBuilder::session()
+            .unwrap()
+            .name(DESKTOP_DESTINATION)
+            .unwrap()
+            .serve_at("/org/freedesktop/portal/mock", MockDesktop)
+            .unwrap();
+        if let Ok(connection) = builder.build().await {
+            return Some(connection);
+        }
+        async_std::task::sleep(Duration::from_millis(100)).await;
+    }
+    eprintln!("skipped: a portal is already running");
+    None
+}
+
+/// Fails with an [`io::ErrorKind::TimedOut`] error if `future` doesn't
+/// complete within `timeout`.
+pub(crate) async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    future: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    let Some(timeout) = timeout else {
+        return future.await;
+    };
+    #[cfg(feature = "async-std")]
+    let result = async_std::future::timeout(timeout, future).await;
+    #[cfg(feature = "tokio")]
+    let result = tokio::time::timeout(timeout, future).await;
+    result.map_err(|_| {
+        Error::IO(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "The portal didn't reply in time",
+        ))
+    })?
+}
+
+/// Whether the call might succeed if attempted again, e.g. the bus or the
+/// portal didn't reply in time.
+fn is_transient(error: &Error) -> bool {
+    let error = match error {
+        Error::IO(err) => return err.kind() == io::ErrorKind::TimedOut,
+        Error::Zbus(err) | Error::Portal(PortalError::ZBus(err)) => err,
+        _ => return false,
+    };
+    match error {
+        zbus::Error::MethodError(name, _, _) => matches!(
+            name.as_str(),
+            "org.freedesktop.DBus.Error.NoReply"
+                | "org.freedesktop.DBus.Error.Timeout"
+                | "org.freedesktop.DBus.Error.TimedOut"
+                | "org.freedesktop.DBus.Error.LimitsExceeded"
+        ),
+        zbus::Error::FDO(err) => matches!(
+            **err,
+            zbus::fdo::Error::NoReply(_)
+                | zbus::fdo::Error::Timeout(_)
+                | zbus::fdo::Error::TimedOut(_)
+                | zbus::fdo::Error::LimitsExceeded(_)
+        ),
+        _ => false,
+    }
+}
+
+/// Whether the method, or the whole interface, is not provided by the portal.
+fn is_unknown_member(error: &zbus::Error) -> bool {
+    match error {
+        zbus::Error::MethodError(name, _, _) => matches!(
+            name.as_str(),
+            "org.freedesktop.DBus.Error.UnknownMethod"
+                | "org.freedesktop.DBus.Error.UnknownInterface"
+        ),
+        zbus::Error::FDO(e) => matches!(
+            **e,
+            zbus::fdo::Error::UnknownMethod(_) | zbus::fdo::Error::UnknownInterface(_)
+        ),
+        _ => false,
+    }
+}
+
+/// Maps the errors of a portal that stopped running, or was never activated,
+/// to [`Error::PortalNotFound`].
+fn portal_not_found(interface: &str, error: &zbus::Error) -> Option<Error> {
+    let not_found = match error {
+        zbus::Error::MethodError(name, _, _) => matches!(
+            name.as_str(),
+            "org.freedesktop.DBus.Error.ServiceUnknown"
+                | "org.freedesktop.DBus.Error.NameHasNoOwner"
+        ),
+        zbus::Error::FDO(e) => is_service_not_found(e),
+        _ => false,
+    };
+    not_found.then(|| Error::PortalNotFound(interface.to_owned()))
+}
+
+fn is_service_not_found(error: &zbus::fdo::Error) -> bool {
+    use zbus::fdo::Error;
+    matches!(
+        error,
+        Error::ServiceUnknown(_)
+            | Error::NameHasNoOwner(_)
+            | Error::SpawnServiceNotFound(_)
+            | Error::SpawnExecFailed(_)
+            | Error::SpawnFailed(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn call_timeout() {
+        async_std::task::block_on(async {
+            let result = with_timeout(
+                Some(Duration::from_millis(10)),
+                futures_util::future::pending::<Result<(), Error>>(),
+            )
+            .await;
+            assert!(matches!(result, Err(Error::IO(e)) if e.kind() == io::ErrorKind::TimedOut));
+
+            let result = with_timeout(None, async { Ok(1) }).await;
+            assert_eq!(result.unwrap(), 1);
+        });
+    }
+
+    #[test]
+    fn option_versions() {
+        assert_eq!(
+            option_version(
+                "org.freedesktop.portal.ScreenCast",
+                "SelectSources",
+                "persist_mode"
+            ),
+            Some(4)
+        );
+        assert_eq!(
+            option_version("org.freedesktop.portal.ScreenCast", "Start", "persist_mode"),
+            None
+        );
+        assert_eq!(
+            option_version("org.freedesktop.portal.Email", "ComposeEmail", "subject"),
+            None
+        );
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn desktop_checked() {
+        struct MockTrash;
+
+        #[zbus::dbus_interface(name = "org.freedesktop.portal.Trash")]
+        impl MockTrash {
+            #[dbus_interface(property)]
+            fn version(&self) -> u32 {
+                1
+            }
+        }
+
+        async_std::task::block_on(async {
+            let Some(server) = mock_desktop().await else {
+                return;
+            };
+            server
+                .object_server()
+                .at(DESKTOP_PATH, MockTrash)
+                .await
+                .unwrap();
+
+            assert!(Proxy::new_desktop_checked("org.freedesktop.portal.Trash")
+                .await
+                .is_ok());
+            let error = Proxy::new_desktop_checked("org.freedesktop.portal.Camera")
+                .await
+                .unwrap_err();
+            assert!(error.is_not_supported());
+            assert_eq!(
+                error.to_string(),
+                "org.freedesktop.portal.Camera is not supported"
+            );
+        });
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn request_after_reconnect() {
+        use zbus::{zvariant::Value, MessageHeader};
+
+        struct MockScreenshot;
+
+        #[zbus::dbus_interface(name = "org.freedesktop.portal.Screenshot")]
+        impl MockScreenshot {
+            async fn screenshot(
+                &self,
+                #[zbus(header)] header: MessageHeader<'_>,
+                #[zbus(connection)] connection: &zbus::Connection,
+                handle_token: String,
+            ) -> zbus::fdo::Result<OwnedObjectPath> {
+                let sender = header.sender().ok().flatten().unwrap();
+                let path = format!(
+                    "/org/freedesktop/portal/desktop/request/{}/{handle_token}",
+                    sender.trim_start_matches(':').replace('.', "_")
+                );
+                connection
+                    .emit_signal(
+                        None::<()>,
+                        path.as_str(),
+                        "org.freedesktop.portal.Request",
+                        "Response",
+                        &(0u32, HashMap::<&str, Value<'_>>::new()),
+                    )
+                    .await?;
+                Ok(OwnedObjectPath::try_from(path).unwrap())
+            }
+        }
+
+        async_std::task::block_on(async {
+            let Some(server) = mock_desktop().await else {
+                return;
+            };
+            server
+                .object_server()
+                .at(DESKTOP_PATH, MockScreenshot)
+                .await
+                .unwrap();
+
+            let proxy = Proxy::new_desktop("org.freedesktop.portal.Screenshot")
+                .await
+                .unwrap();
+            // The connection the proxy was created on is lost, the request has
+            // to be named after the one opened to call the method
+            let lost = Proxy::connection().await.unwrap();
+            Proxy::connection_lost(lost.unique_name());
+            let token = HandleToken::default();
+            let request = with_timeout(
+                Some(Duration::from_secs(5)),
+                proxy.empty_request(&token, "Screenshot", token.to_string()),
+            )
+            .await
+            .unwrap();
+            assert_ne!(
+                request.path().as_str(),
+                Proxy::unique_name_with(&lost, "/org/freedesktop/portal/desktop/request", &token)
+                    .unwrap()
+                    .as_str()
+            );
+            request.response().unwrap();
+        });
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn property_changed() {
+        struct MockCamera {
+            present: bool,
+        }
+
+        #[zbus::dbus_interface(name = "org.freedesktop.portal.Camera")]
+        impl MockCamera {
+            #[dbus_interface(property)]
+            fn is_camera_present(&self) -> bool {
+                self.present
+            }
+        }
+
+        async_std::task::block_on(async {
+            let Some(server) = mock_desktop().await else {
+                return;
+            };
+            server
+                .object_server()
+                .at(DESKTOP_PATH, MockCamera { present: false })
+                .await
+                .unwrap();
+            let iface = server
+                .object_server()
+                .interface::<_, MockCamera>(DESKTOP_PATH)
+                .await
+                .unwrap();
+
+            let proxy = Proxy::new_desktop("org.freedesktop.portal.Camera")
+                .await
+                .unwrap();
+            let mut stream = Box::pin(
+                proxy
+                    .receive_property_changed::<bool>("IsCameraPresent")
+                    .await
+                    .unwrap(),
+            );
+            assert!(!stream.next().await.unwrap());
+
+            // Only invalidated, the value has to be read again
+            iface.get_mut().await.present = true;
+            zbus::fdo::Properties::properties_changed(
+                iface.signal_context(),
+                "org.freedesktop.portal.Camera".try_into().unwrap(),
+                &HashMap::new(),
+                &["IsCameraPresent"],
+            )
+            .await
+            .unwrap();
+            assert!(stream.next().await.unwrap());
+
+            iface.get_mut().await.present = false;
+            iface
+                .get()
+                .await
+                .is_camera_present_changed(iface.signal_context())
+                .await
+                .unwrap();
+            assert!(!stream.next().await.unwrap());
+        });
+    }
+
+    #[test]
+    fn unknown_member() {
+        let error = zbus::Error::FDO(Box::new(zbus::fdo::Error::UnknownMethod(
+            "Unknown method".to_owned(),
+        )));
+        assert!(is_unknown_member(&error));
+
+        let error = zbus::Error::FDO(Box::new(zbus::fdo::Error::UnknownInterface(
+            "No such interface".to_owned(),
+        )));
+        assert!(is_unknown_member(&error));
+
+        let error = zbus::Error::FDO(Box::new(zbus::fdo::Error::AccessDenied(
+            "Access denied".to_owned(),
+        )));
+        assert!(!is_unknown_member(&error));
+        assert!(!is_unknown_member(&zbus::Error::InvalidReply));
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn retry_transient() {
+        let policy = RetryPolicy::default()
+            .max_attempts(4)
+            .initial_delay(Duration::from_millis(1));
+        let transient = || {
+            Error::Portal(PortalError::ZBus(zbus::Error::FDO(Box::new(
+                zbus::fdo::Error::NoReply("No reply".to_owned()),
+            ))))
+        };
+        async_std::task::block_on(async {
+            // Succeeds on the third attempt
+            let mut attempts = 0;
+            let result = retry(policy, || {
+                attempts += 1;
+                ready(if attempts < 3 {
+                    Err(transient())
+                } else {
+                    Ok(attempts)
+                })
+            })
+            .await;
+            assert_eq!(result.unwrap(), 3);
+
+            // Gives up after the maximum number of attempts
+            let mut attempts = 0;
+            let result = retry(policy, || {
+                attempts += 1;
+                ready(Err::<(), _>(transient()))
+            })
+            .await;
+            assert!(result.is_err());
+            assert_eq!(attempts, 4);
+
+            // Not retried
+            let mut attempts = 0;
+            let result = retry(policy, || {
+                attempts += 1;
+                ready(Err::<(), _>(Error::Portal(PortalError::ZBus(
+                    zbus::Error::FDO(Box::new(zbus::fdo::Error::AccessDenied(
+                        "Access denied".to_owned(),
+                    ))),
+                ))))
+            })
+            .await;
+            assert!(result.is_err());
+            assert_eq!(attempts, 1);
+        });
+    }
+
+    #[test]
+    fn retry_policy() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.delay(10), Duration::from_secs(2));
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(2));
+        assert_eq!(RetryPolicy::default().max_attempts(0).max_attempts, 1);
+
+        let timed_out = Error::IO(io::Error::new(io::ErrorKind::TimedOut, "Timed out"));
+        assert!(is_transient(&timed_out));
+        let error = zbus::Error::MethodError(
+            zbus::names::OwnedErrorName::try_from("org.freedesktop.DBus.Error.NoReply").unwrap(),
+            None,
+            Arc::new(
+                zbus::Message::method(None::<()>, None::<()>, "/", None::<()>, "Test", &())
+                    .unwrap(),
+            ),
+        );
+        assert!(is_transient(&Error::Zbus(error)));
+        assert!(!is_transient(&Error::NoResponse));
+    }
+
+    #[test]
+    fn service_unknown() {
+        let interface = "org.freedesktop.portal.DynamicLauncher";
+        let error = zbus::Error::FDO(Box::new(zbus::fdo::Error::ServiceUnknown(
+            "The name is not activatable".to_owned(),
+        )));
+        let error = portal_not_found(interface, &error).unwrap();
+        assert!(error.is_portal_not_found());
+        assert!(matches!(error, Error::PortalNotFound(i) if i == interface));
+
+        let error = zbus::Error::FDO(Box::new(zbus::fdo::Error::NameHasNoOwner(
+            "No owner".to_owned(),
+        )));
+        assert!(portal_not_found(interface, &error).is_some());
+
+        let error = zbus::Error::FDO(Box::new(zbus::fdo::Error::AccessDenied(
+            "Access denied".to_owned(),
+        )));
+        assert!(portal_not_found(interface, &error).is_none());
+        assert!(portal_not_found(interface, &zbus::Error::InvalidReply).is_none());
+    }
+
+    #[test]
+    fn service_not_found() {
+        let error = zbus::fdo::Error::ServiceUnknown(
+            "The name org.freedesktop.portal.Desktop was not provided by any .service files"
+                .to_owned(),
+        );
+        assert!(is_service_not_found(&error));
+        assert!(!is_service_not_found(&zbus::fdo::Error::AccessDenied(
+            "Access denied".to_owned()
+        )));
+    }
+}
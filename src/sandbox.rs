@@ -0,0 +1,37 @@
+use crate::{helpers, AppID};
+
+/// The sandbox the application is running in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sandbox {
+    /// The application is running inside a Flatpak.
+    Flatpak,
+    /// The application is running as a Snap.
+    Snap,
+    /// The application is not sandboxed.
+    None,
+}
+
+/// Detect the sandbox the application is running in.
+///
+/// A Flatpak is detected by the presence of `/.flatpak-info` and a Snap from
+/// the cgroup of the process.
+pub async fn kind() -> Sandbox {
+    if helpers::is_flatpak().await {
+        Sandbox::Flatpak
+    } else if helpers::is_snap().await {
+        Sandbox::Snap
+    } else {
+        Sandbox::None
+    }
+}
+
+/// The application ID of the running Flatpak application, read from
+/// `/.flatpak-info`.
+///
+/// Returns `None` outside of a Flatpak, Snap names are not application IDs.
+pub async fn app_id() -> Option<AppID> {
+    crate::flatpak::info()
+        .await
+        .ok()
+        .map(|info| info.application_id().clone())
+}
@@ -0,0 +1,76 @@
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ashpd::{desktop::trash::TrashProxy, util};
+//!
+//! async fn run(paths: &[&str]) -> ashpd::Result<()> {
+//!     let proxy = TrashProxy::new().await?;
+//!
+//!     let results = util::join_all_limited(
+//!         paths.iter().map(|path| proxy.trash_path(path)),
+//!         util::DEFAULT_CONCURRENCY,
+//!     )
+//!     .await;
+//!     for result in results {
+//!         result?;
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::future::Future;
+
+use futures_util::StreamExt;
+
+/// The number of concurrent portal calls used by the batch methods, like
+/// [`DynamicLauncherProxy::entries`](crate::desktop::dynamic_launcher::DynamicLauncherProxy::entries).
+///
+/// It keeps the portal responsive to the other applications while still
+/// hiding most of the round trips latency.
+pub const DEFAULT_CONCURRENCY: usize = 16;
+
+/// Polls the futures with at most `concurrency` of them running at the same
+/// time, and returns their outputs in the same order.
+///
+/// A `concurrency` of `0` is treated as `1`.
+pub async fn join_all_limited<I>(futures: I, concurrency: usize) -> Vec<<I::Item as Future>::Output>
+where
+    I: IntoIterator,
+    I::Item: Future,
+{
+    futures_util::stream::iter(futures)
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(all(test, feature = "async-std"))]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn limited_concurrency() {
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let futures = (0..10).map(|i| {
+            let running = &running;
+            let max_running = &max_running;
+            async move {
+                let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(current, Ordering::SeqCst);
+                async_std::task::sleep(std::time::Duration::from_millis(10 - i)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        });
+        let outputs = async_std::task::block_on(join_all_limited(futures, 3));
+        assert_eq!(outputs, (0..10).collect::<Vec<_>>());
+        assert_eq!(max_running.load(Ordering::SeqCst), 3);
+
+        let outputs = async_std::task::block_on(join_all_limited([async { 1 }], 0));
+        assert_eq!(outputs, [1]);
+    }
+}
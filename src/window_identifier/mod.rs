@@ -41,6 +41,14 @@ use zbus::zvariant::Type;
 /// /// Open some portals
 /// ```
 ///
+/// Or, if the connection is at hand
+///
+/// ```text
+/// // let identifier = WindowIdentifier::from_surface(wl_surface, connection).await?;
+///
+/// /// Open some portals
+/// ```
+///
 /// Or using a raw `wl_surface` pointer
 ///
 /// ```text
@@ -99,6 +107,27 @@ use zbus::zvariant::Type;
 /// /// Open some portals
 /// ```
 ///
+/// ## From a handle string
+///
+/// An identifier exported by another process, e.g. a browser hosting a
+/// plugin, can be passed across the process boundary as a string and
+/// reconstructed. Only `x11:` and `wayland:` handles are accepted.
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use ashpd::WindowIdentifier;
+///
+/// let identifier = WindowIdentifier::from_str("x11:0x400").unwrap();
+/// assert_eq!(identifier.to_string(), "x11:0x400");
+/// assert!(WindowIdentifier::from_str("win32:1234").is_err());
+/// ```
+///
+/// **Note** the [`Display`](std::fmt::Display) implementation is lossless,
+/// it can be used to pass the identifier to another process. The handle of a
+/// reconstructed Wayland identifier is not unexported when dropped, that is
+/// up to the process that exported it.
+///
 /// In case you don't have access to a WindowIdentifier:
 /// ```rust
 /// use ashpd::WindowIdentifier;
@@ -122,7 +151,7 @@ pub enum WindowIdentifier {
     Wayland(WaylandWindowIdentifier),
     #[doc(hidden)]
     X11(WindowIdentifierType),
-    #[doc(hidden)]
+    /// No parent window, the dialogs are not attached to any window.
     #[default]
     None,
 }
@@ -162,6 +191,33 @@ impl std::fmt::Debug for WindowIdentifier {
     }
 }
 
+impl FromStr for WindowIdentifier {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::None);
+        }
+        Ok(Self::X11(WindowIdentifierType::from_str(s)?))
+    }
+}
+
+impl TryFrom<&str> for WindowIdentifier {
+    type Error = crate::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_str(value)
+    }
+}
+
+impl TryFrom<String> for WindowIdentifier {
+    type Error = crate::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
 impl WindowIdentifier {
     #[cfg(feature = "gtk4")]
     /// Creates a [`WindowIdentifier`] from a [`gtk4::Native`](https://docs.gtk.org/gtk4/class.Native.html).
@@ -194,6 +250,7 @@ impl WindowIdentifier {
     }
 
     #[cfg(feature = "raw_handle")]
+    #[doc(alias = "from_raw_window_handle")]
     /// Create an instance of [`WindowIdentifier`] from a
     /// [`RawWindowHandle`](raw_window_handle::RawWindowHandle).
     ///
@@ -201,6 +258,13 @@ impl WindowIdentifier {
     ///
     /// This method is only async and requires a `RawDisplayHandle` only for
     /// Wayland handles.
+    ///
+    /// **Note** Wayland surfaces have to be exported through the
+    /// `xdg-foreign` protocol before being usable by the portal. If no
+    /// matching [`RawDisplayHandle`](raw_window_handle::RawDisplayHandle) is
+    /// provided or the compositor doesn't support the protocol, an empty
+    /// identifier is returned instead. The same applies to handles of
+    /// unsupported windowing systems.
     pub async fn from_raw_handle(
         window_handle: &raw_window_handle::RawWindowHandle,
         display_handle: Option<&raw_window_handle::RawDisplayHandle>,
@@ -224,6 +288,21 @@ impl WindowIdentifier {
         Self::X11(WindowIdentifierType::X11(xid))
     }
 
+    /// Create an instance of [`WindowIdentifier`] from a Wayland surface
+    /// handle, for toolkits that export their surfaces themselves.
+    ///
+    /// The handle must be the one received from
+    /// `zxdg_exporter_v2.export_toplevel`, and the exported surface must
+    /// stay exported until the request is done, so the identifier must
+    /// outlive the request. Falls back to [`WindowIdentifier::default`] for
+    /// an empty handle.
+    pub fn from_wayland_handle(handle: &str) -> Self {
+        if handle.is_empty() {
+            return Self::default();
+        }
+        Self::X11(WindowIdentifierType::Wayland(handle.to_owned()))
+    }
+
     #[cfg(feature = "wayland")]
     /// Create an instance of [`WindowIdentifier`] from a Wayland surface.
     ///
@@ -250,6 +329,26 @@ impl WindowIdentifier {
             None => Self::default(),
         }
     }
+
+    #[cfg(feature = "wayland")]
+    /// Create an instance of [`WindowIdentifier`] from a Wayland surface and
+    /// the connection it belongs to, for applications not using GTK, like the
+    /// ones built on top of smithay-client-toolkit.
+    ///
+    /// The surface is exported using the `xdg_foreign` protocol, the exported
+    /// handle is released once the identifier is dropped.
+    ///
+    /// Unlike [`WindowIdentifier::from_wayland`], an error is returned if the
+    /// handle couldn't be exported, for example if the compositor doesn't
+    /// support the protocol.
+    pub async fn from_surface(
+        surface: &wayland_client::protocol::wl_surface::WlSurface,
+        connection: &wayland_client::Connection,
+    ) -> Result<Self, crate::Error> {
+        WaylandWindowIdentifier::with_connection(connection, surface)
+            .await
+            .map(Self::Wayland)
+    }
 }
 
 #[cfg(all(feature = "raw_handle", any(feature = "gtk3", feature = "gtk4")))]
@@ -299,7 +398,6 @@ unsafe impl HasRawWindowHandle for WindowIdentifier {
 #[zvariant(signature = "s")]
 pub enum WindowIdentifierType {
     X11(std::os::raw::c_ulong),
-    #[allow(dead_code)]
     Wayland(String),
 }
 
@@ -326,7 +424,10 @@ impl FromStr for WindowIdentifierType {
                         .map_err(|_| PortalError::InvalidArgument(format!("Wrong XID {handle}")))?,
                 ))
             }
-            "wayland" => Ok(Self::Wayland(handle.to_owned())),
+            "wayland" if !handle.is_empty() => Ok(Self::Wayland(handle.to_owned())),
+            "wayland" => Err(PortalError::InvalidArgument(
+                "Empty Wayland handle".to_owned(),
+            )),
             t => Err(PortalError::InvalidArgument(format!(
                 "Invalid Window Identifier type {t}",
             ))),
@@ -358,7 +459,7 @@ mod gtk3;
 #[cfg(feature = "gtk3")]
 pub use self::gtk3::Gtk3WindowIdentifier;
 
-#[cfg(any(feature = "wayland"))]
+#[cfg(feature = "wayland")]
 mod wayland;
 
 #[cfg(feature = "wayland")]
@@ -389,5 +490,29 @@ mod tests {
         );
         assert!(WindowIdentifierType::from_str("some_handle").is_err());
         assert!(WindowIdentifierType::from_str("some_type:some_handle").is_err());
+        assert!(WindowIdentifierType::from_str("wayland:").is_err());
+    }
+
+    #[test]
+    fn test_from_str() {
+        for handle in ["x11:0x400", "wayland:Somerandomchars", ""] {
+            let identifier = WindowIdentifier::from_str(handle).unwrap();
+            assert_eq!(identifier.to_string(), handle);
+        }
+        assert!(WindowIdentifier::try_from("x11:nothex".to_owned()).is_err());
+        assert!(WindowIdentifier::try_from("win32:1234").is_err());
+    }
+
+    #[test]
+    fn test_from_handles() {
+        let x11 = WindowIdentifier::from_xid(0x2a00004);
+        assert!(x11.to_string().starts_with("x11:"));
+        assert_eq!(x11.to_string(), "x11:0x2a00004");
+
+        let wayland = WindowIdentifier::from_wayland_handle("exported-handle");
+        assert!(wayland.to_string().starts_with("wayland:"));
+        assert_eq!(wayland.to_string(), "wayland:exported-handle");
+
+        assert_eq!(WindowIdentifier::from_wayland_handle("").to_string(), "");
     }
 }
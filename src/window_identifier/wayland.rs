@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, io};
 
 use wayland_backend::sys::client::Backend;
 use wayland_client::{
@@ -54,7 +54,14 @@ impl WaylandWindowIdentifier {
         let backend = surface.backend().upgrade()?;
         let conn = wayland_client::Connection::from_backend(backend);
 
-        Self::new_inner(conn, surface).await
+        Self::new_inner(conn, surface).await.ok()
+    }
+
+    pub async fn with_connection(
+        conn: &wayland_client::Connection,
+        surface: &WlSurface,
+    ) -> Result<Self, crate::Error> {
+        Self::new_inner(conn.clone(), surface).await
     }
 
     pub async unsafe fn from_raw(
@@ -75,25 +82,28 @@ impl WaylandWindowIdentifier {
 
         let surface = WlSurface::from_id(&conn, obj_id).ok()?;
 
-        Self::new_inner(conn, &surface).await
+        Self::new_inner(conn, &surface).await.ok()
     }
 
-    async fn new_inner(conn: wayland_client::Connection, surface: &WlSurface) -> Option<Self> {
+    async fn new_inner(
+        conn: wayland_client::Connection,
+        surface: &WlSurface,
+    ) -> Result<Self, crate::Error> {
         let (sender, receiver) =
-            futures_channel::oneshot::channel::<Option<WaylandWindowIdentifier>>();
+            futures_channel::oneshot::channel::<Result<WaylandWindowIdentifier, crate::Error>>();
 
         // Cheap clone, protocol objects are essentially smart pointers
         let surface = surface.clone();
-        std::thread::spawn(move || match wayland_export_handle(conn, &surface) {
-            Ok(window_handle) => sender.send(Some(window_handle)).unwrap(),
-            Err(_err) => {
-                #[cfg(feature = "tracing")]
-                tracing::info!("Could not get wayland window identifier: {_err}");
-                sender.send(None).unwrap();
+        std::thread::spawn(move || {
+            let result = wayland_export_handle(conn, &surface);
+            #[cfg(feature = "tracing")]
+            if let Err(err) = &result {
+                tracing::info!("Could not get wayland window identifier: {err}");
             }
+            let _ = sender.send(result);
         });
 
-        receiver.await.unwrap()
+        receiver.await.map_err(|_| crate::Error::NoResponse)?
     }
 }
 
@@ -222,25 +232,26 @@ impl wayland_client::Dispatch<wl_registry::WlRegistry, ()> for State {
 fn wayland_export_handle(
     conn: wayland_client::Connection,
     surface: &WlSurface,
-) -> Result<WaylandWindowIdentifier, Box<dyn std::error::Error>> {
+) -> Result<WaylandWindowIdentifier, crate::Error> {
+    let dispatch_error = |err: wayland_client::DispatchError| io::Error::other(err.to_string());
     let display = conn.display();
     let mut event_queue = conn.new_event_queue();
     let qhandle = event_queue.handle();
     let mut state = State::default();
     display.get_registry(&qhandle, ());
-    event_queue.roundtrip(&mut state)?;
+    event_queue.roundtrip(&mut state).map_err(dispatch_error)?;
 
     let exported = match state.exporter.take() {
         Some(Exporter::V2(exporter)) => {
             let exp = exporter.export_toplevel(surface, &qhandle, ());
-            event_queue.roundtrip(&mut state)?;
+            event_queue.roundtrip(&mut state).map_err(dispatch_error)?;
             exporter.destroy();
 
             Some(Exported::V2(exp))
         }
         Some(Exporter::V1(exporter)) => {
             let exp = exporter.export(surface, &qhandle, ());
-            event_queue.roundtrip(&mut state)?;
+            event_queue.roundtrip(&mut state).map_err(dispatch_error)?;
             exporter.destroy();
 
             Some(Exported::V1(exp))
@@ -260,6 +271,6 @@ fn wayland_export_handle(
             type_: WindowIdentifierType::Wayland(state.handle),
         })
     } else {
-        Err(Box::new(crate::Error::NoResponse))
+        Err(crate::Error::NoResponse)
     }
 }